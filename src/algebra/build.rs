@@ -0,0 +1,237 @@
+//! Helpers for building expression trees out of smaller ones.
+//!
+//! They fold away the trivial cases, like `x + 0`, `x * 1`, `x ^ 1` and `2 + 3`, so generated expressions stay readable.
+
+use crate::{
+    parser::trees::{TreeNode, TreeNodeRef},
+    types::{
+        cas_num::{CASComplex, CASInterval, Number},
+        symbol::{
+            constant::{Const, ResConst},
+            function::{Func, ResFun},
+            operator::Operator,
+            Symbol, SymbolType,
+        },
+    },
+};
+
+/// A reference to a node of an expression tree.
+pub(crate) type Node = TreeNodeRef<Symbol>;
+
+/// Creates a node with the given data and children.
+pub(crate) fn node(symbol_type: SymbolType, line_pos: usize, children: Vec<Node>) -> Node {
+    TreeNodeRef::new_from_node(TreeNode {
+        data: Symbol {
+            symbol_type,
+            line_pos,
+        },
+        children,
+    })
+}
+
+/// Creates a numeric literal.
+pub(crate) fn num<T>(value: T, line_pos: usize) -> Node
+where
+    Number: From<T>,
+{
+    node(
+        SymbolType::Num {
+            value: Number::from(value),
+        },
+        line_pos,
+        vec![],
+    )
+}
+
+/// Creates a complex number, written as `a + b * i` with the real or imaginary part left out if it's zero.
+pub(crate) fn complex(value: CASComplex, line_pos: usize) -> Node {
+    if let Some(real) = value.to_real() {
+        return num(real, line_pos);
+    }
+    let im = mult(num(value.im, line_pos), res_const(ResConst::I, line_pos));
+    add(num(value.re, line_pos), im)
+}
+
+/// Creates an interval, written as `interval(lo, hi)`.
+pub(crate) fn interval(value: CASInterval, line_pos: usize) -> Node {
+    node(
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Interval,
+            num_args: 2,
+        }),
+        line_pos,
+        vec![num(value.lo, line_pos), num(value.hi, line_pos)],
+    )
+}
+
+/// Creates a variable.
+pub(crate) fn var(name: &str, line_pos: usize) -> Node {
+    node(
+        SymbolType::Variable {
+            name: name.to_string(),
+        },
+        line_pos,
+        vec![],
+    )
+}
+
+/// Creates a reserved constant like π or e.
+pub(crate) fn res_const(constant: ResConst, line_pos: usize) -> Node {
+    node(
+        SymbolType::Const(Const::ResConst(constant)),
+        line_pos,
+        vec![],
+    )
+}
+
+/// Creates a call to a reserved function.
+pub(crate) fn call(fun: ResFun, args: Vec<Node>) -> Node {
+    let line_pos = args.first().map_or(0, line_pos);
+    let num_args = args.len();
+    node(
+        SymbolType::Function(Func::ResFun { fun, num_args }),
+        line_pos,
+        args,
+    )
+}
+
+/// Returns the line position of a node.
+pub(crate) fn line_pos(node: &Node) -> usize {
+    node.0.borrow().data.line_pos
+}
+
+/// Returns the value of a node if it is a numeric literal.
+pub(crate) fn num_value(node: &Node) -> Option<Number> {
+    match &node.0.borrow().data.symbol_type {
+        SymbolType::Num { value } => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// Checks if a node is a numeric literal equal to `value`.
+pub(crate) fn is_num<T>(node: &Node, value: T) -> bool
+where
+    Number: From<T>,
+{
+    num_value(node).is_some_and(|num| num == Number::from(value))
+}
+
+/// Checks if the variable `name` appears anywhere in the subtree.
+pub(crate) fn contains_var(node: &Node, name: &str) -> bool {
+    let node = node.0.borrow();
+    match &node.data.symbol_type {
+        SymbolType::Variable { name: var_name } if var_name == name => true,
+        _ => node.children.iter().any(|child| contains_var(child, name)),
+    }
+}
+
+/// Checks if a call to the reserved function `fun` appears anywhere in the subtree.
+pub(crate) fn contains_call(node: &Node, fun: ResFun) -> bool {
+    let node = node.0.borrow();
+    match &node.data.symbol_type {
+        SymbolType::Function(Func::ResFun { fun: called, .. }) if *called == fun => true,
+        _ => node.children.iter().any(|child| contains_call(child, fun)),
+    }
+}
+
+/// Returns the arguments of a node.
+pub(crate) fn children(node: &Node) -> Vec<Node> {
+    node.0.borrow().children.clone()
+}
+
+/// Checks if the root of a node is the operator `op`.
+pub(crate) fn is_operator(node: &Node, op: Operator) -> bool {
+    node.0.borrow().data.symbol_type == SymbolType::Operator(op)
+}
+
+/// Builds `lhs + rhs`.
+pub(crate) fn add(lhs: Node, rhs: Node) -> Node {
+    if is_num(&lhs, 0) {
+        return rhs;
+    }
+    if is_num(&rhs, 0) {
+        return lhs;
+    }
+    if let (Some(a), Some(b)) = (num_value(&lhs), num_value(&rhs)) {
+        return node(SymbolType::Num { value: a + b }, line_pos(&lhs), vec![]);
+    }
+    binary(Operator::Add, lhs, rhs)
+}
+
+/// Builds `lhs - rhs`.
+pub(crate) fn sub(lhs: Node, rhs: Node) -> Node {
+    if is_num(&rhs, 0) {
+        return lhs;
+    }
+    if is_num(&lhs, 0) {
+        return neg(rhs);
+    }
+    if let (Some(a), Some(b)) = (num_value(&lhs), num_value(&rhs)) {
+        return node(SymbolType::Num { value: a - b }, line_pos(&lhs), vec![]);
+    }
+    binary(Operator::Sub, lhs, rhs)
+}
+
+/// Builds `lhs * rhs`.
+pub(crate) fn mult(lhs: Node, rhs: Node) -> Node {
+    if is_num(&lhs, 0) || is_num(&rhs, 0) {
+        return num(0, line_pos(&lhs));
+    }
+    if is_num(&lhs, 1) {
+        return rhs;
+    }
+    if is_num(&rhs, 1) {
+        return lhs;
+    }
+    if is_num(&lhs, -1) {
+        return neg(rhs);
+    }
+    if is_num(&rhs, -1) {
+        return neg(lhs);
+    }
+    if let (Some(a), Some(b)) = (num_value(&lhs), num_value(&rhs)) {
+        return node(SymbolType::Num { value: a * b }, line_pos(&lhs), vec![]);
+    }
+    binary(Operator::Mult, lhs, rhs)
+}
+
+/// Builds `lhs / rhs`.
+pub(crate) fn div(lhs: Node, rhs: Node) -> Node {
+    if is_num(&rhs, 1) {
+        return lhs;
+    }
+    if is_num(&lhs, 0) && !is_num(&rhs, 0) {
+        return lhs;
+    }
+    binary(Operator::Div, lhs, rhs)
+}
+
+/// Builds `base ^ exp`.
+pub(crate) fn pow(base: Node, exp: Node) -> Node {
+    if is_num(&exp, 0) {
+        return num(1, line_pos(&base));
+    }
+    if is_num(&exp, 1) {
+        return base;
+    }
+    binary(Operator::Exp, base, exp)
+}
+
+/// Builds `-arg`.
+pub(crate) fn neg(arg: Node) -> Node {
+    if let Some(value) = num_value(&arg) {
+        return node(SymbolType::Num { value: -value }, line_pos(&arg), vec![]);
+    }
+    if arg.0.borrow().data.symbol_type == SymbolType::Operator(Operator::Neg) {
+        return arg.0.borrow().children[0].clone();
+        //-(-a) -> a
+    }
+    let line_pos = line_pos(&arg);
+    node(SymbolType::Operator(Operator::Neg), line_pos, vec![arg])
+}
+
+/// Builds an operator node with two arguments.
+fn binary(op: Operator, lhs: Node, rhs: Node) -> Node {
+    let line_pos = line_pos(&lhs);
+    node(SymbolType::Operator(op), line_pos, vec![lhs, rhs])
+}
@@ -1,100 +1,309 @@
-use std::{borrow::Borrow, cell::RefCell, ops::Mul, rc::Rc};
-
-use num_traits::Zero;
-
 ///Contains functionality for finding derivatives of mathematical expressions. i.e.
-/// ```
-/// d/dx x^2 => x * 2
+/// ```text
+/// d/dx x^2 => 2 * x
 /// d/dx sin x => cos x,
 /// etc.
 /// ```
 use crate::{
-    parser::{
-        trees::{Tree, TreeNode, TreeNodeRef},
-        vars::Var,
-    },
+    parser::trees::Tree,
     types::{
-        cas_num::ZERO,
-        symbol::{Symbol, SymbolType},
+        cas_error::{CASError, CASErrorKind},
+        symbol::{function::Func, function::ResFun, Symbol, SymbolType},
     },
 };
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::build::*;
 use crate::types::symbol::operator::Operator;
-use crate::types::symbol::SymbolType::*;
 
 impl Tree<Symbol> {
-    fn derivative(&mut self, wrt: &Var) {
-        TreeNode::<Symbol>::derivative(&mut self.root.0.borrow_mut(), wrt);
+    /// Finds the derivative of the expression with respect to the variable `wrt`, leaving the expression itself untouched.
+    pub(crate) fn derivative(&self, wrt: &str) -> Result<Tree<Symbol>, CASError> {
+        Ok(Tree {
+            root: Differentiator::default().derivative(&self.root, wrt)?,
+        })
     }
 }
 
-impl TreeNode<Symbol> {
-    fn derivative(&mut self, wrt: &Var) {
-        for child in &self.children {
-            Self::derivative(&mut child.0.borrow_mut(), wrt);
-        }
+/// Finds derivatives of subtrees, remembering every derivative it has already found.
+///
+/// Higher order and mixed derivatives differentiate the same subexpressions over and over, e.g. the `sin(x)` in each term of `der(x * sin(x), x, 3)`, so a single differentiator should be used for every step of a `der` call.
+#[derive(Default)]
+pub(crate) struct Differentiator {
+    /// Derivatives found so far, keyed by the hash of the subtree and the variable they were taken with respect to, along with the subtree itself to tell apart subtrees with the same hash.
+    //both are copies, so changing a tree in place after it was differentiated, or changing a derivative that was returned, can't change the cache
+    cache: HashMap<(u64, String), (Node, Node)>,
+}
 
-        match self.data.symbol_type {
-            SymbolType::Operator(Operator::Add) => simplify_add(self, wrt),
-            SymbolType::Operator(Operator::Sub) => simplify_add(self, wrt),
-            //addition works the same way as subtraction
-            //d/dx f + g -> d/dx f + d/dx g
-            //d/dx f - g -> d/dx f - d/dx g
-            SymbolType::Operator(Operator::Mult) => simplify_mult(self, wrt),
-            SymbolType::Operator(Operator::Div) => simplify_div(self, wrt),
-            SymbolType::Const(_) | SymbolType::Num { .. } => {
-                self.data = Symbol {
-                    symbol_type: Num { value: ZERO },
-                    line_pos: self.data.line_pos,
-                };
-                //derivative of constant is 0
+impl Differentiator {
+    /// Finds the derivative of a subtree with respect to `wrt`.
+    ///
+    /// The result never shares nodes with `node` or with earlier results.
+    pub(crate) fn derivative(&mut self, node: &Node, wrt: &str) -> Result<Node, CASError> {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        let key = (hasher.finish(), wrt.to_string());
+        if let Some((cached_node, cached)) = self.cache.get(&key) {
+            if cached_node == node {
+                return Ok(cached.deep_clone());
             }
-            _ => {}
-        };
+        }
 
-        todo!();
+        let result = self.find_derivative(node, wrt)?;
+        self.cache
+            .insert(key, (node.deep_clone(), result.deep_clone()));
+        Ok(result)
     }
-}
 
-fn simplify_add(node: &mut TreeNode<Symbol>, wrt: &Var) {
-    for child in &node.children {
-        child.0.borrow_mut().derivative(wrt);
-        // d /dx (f(x) + g(x)) = d/dx f(x) + d/dx g(x)
-    }
-}
+    /// Applies the differentiation rule for the root of `node`, without checking the cache.
+    fn find_derivative(&mut self, node: &Node, wrt: &str) -> Result<Node, CASError> {
+        let line_pos = line_pos(node);
 
-fn simplify_mult(node: &mut TreeNode<Symbol>, wrt: &Var) {
-    let derivatives: Vec<TreeNodeRef<Symbol>> = node
-        .children
-        .iter()
-        .map(|child| {
-            let der = child.clone();
-            der.0.borrow_mut().derivative(wrt);
-            //makes a clone and takes the derivative
-            der
-        })
-        .collect();
-
-    let new_children: Vec<TreeNodeRef<Symbol>> = vec![];
-
-    for (idx, diff) in derivatives.iter().enumerate() {
-        let mut new_node = TreeNode::from(Symbol {
-            symbol_type: Operator(Operator::Mult),
-            line_pos: node.data.line_pos,
-        });
-        new_node.children.push(diff.clone());
-        for (idx_2, arg) in node.children.iter().enumerate() {
-            if idx_2 == idx {
-                continue;
-            }
-            new_node.children.push(arg.clone());
+        if !contains_var(node, wrt) {
+            return Ok(num(0, line_pos));
+            //derivative of constant is 0
         }
+
+        let symbol_type = node.0.borrow().data.symbol_type.clone();
+        let args: Vec<Node> = node.0.borrow().children.clone();
+
+        let arg = |idx: usize| args[idx].deep_clone();
+        //parts of the original expression are copied so the result doesn't share nodes with it
+
+        Ok(match symbol_type {
+            SymbolType::Variable { .. } => num(1, line_pos),
+            //we know this is wrt since it contains wrt
+            SymbolType::Operator(Operator::Add) => {
+                // d/dx (f + g + ...) -> d/dx f + d/dx g + ...
+                let mut sum = num(0, line_pos);
+                for term in &args {
+                    sum = add(sum, self.derivative(term, wrt)?);
+                }
+                sum
+            }
+            SymbolType::Operator(Operator::Sub) => sub(
+                self.derivative(&args[0], wrt)?,
+                self.derivative(&args[1], wrt)?,
+            ),
+            SymbolType::Operator(Operator::Neg) => neg(self.derivative(&args[0], wrt)?),
+
+            SymbolType::Operator(Operator::Mult) => {
+                // (f * g * h)' -> f' * g * h + f * g' * h + f * g * h'
+                let mut sum = num(0, line_pos);
+                for idx in 0..args.len() {
+                    let mut term = num(1, line_pos);
+                    for (other, factor) in args.iter().enumerate() {
+                        term = mult(
+                            term,
+                            if other == idx {
+                                self.derivative(factor, wrt)?
+                            } else {
+                                factor.deep_clone()
+                            },
+                        );
+                    }
+                    sum = add(sum, term);
+                }
+                sum
+            }
+
+            SymbolType::Operator(Operator::Div) => {
+                if contains_var(&args[1], wrt) {
+                    // (f / g)' -> (f' * g - f * g') / g^2
+                    div(
+                        sub(
+                            mult(self.derivative(&args[0], wrt)?, arg(1)),
+                            mult(arg(0), self.derivative(&args[1], wrt)?),
+                        ),
+                        pow(arg(1), num(2, line_pos)),
+                    )
+                } else {
+                    // (f / c)' -> f' / c
+                    div(self.derivative(&args[0], wrt)?, arg(1))
+                }
+            }
+
+            SymbolType::Operator(Operator::Exp) => {
+                match (contains_var(&args[0], wrt), contains_var(&args[1], wrt)) {
+                    (true, false) => {
+                        // (f ^ c)' -> c * f ^ (c - 1) * f'
+                        mult(
+                            mult(arg(1), pow(arg(0), sub(arg(1), num(1, line_pos)))),
+                            self.derivative(&args[0], wrt)?,
+                        )
+                    }
+                    (false, true) => {
+                        // (c ^ g)' -> c ^ g * ln(c) * g'
+                        mult(
+                            mult(pow(arg(0), arg(1)), call(ResFun::Ln, vec![arg(0)])),
+                            self.derivative(&args[1], wrt)?,
+                        )
+                    }
+                    _ => {
+                        // f ^ g = e ^ (g * ln(f))
+                        // (f ^ g)' -> f ^ g * (g' * ln(f) + g * f' / f)
+                        mult(
+                            pow(arg(0), arg(1)),
+                            add(
+                                mult(
+                                    self.derivative(&args[1], wrt)?,
+                                    call(ResFun::Ln, vec![arg(0)]),
+                                ),
+                                div(mult(arg(1), self.derivative(&args[0], wrt)?), arg(0)),
+                            ),
+                        )
+                    }
+                }
+            }
+
+            SymbolType::Function(Func::ResFun {
+                fun: ResFun::Log, ..
+            }) => {
+                // log(f, b) = ln(f) / ln(b)
+                let change_of_base = div(
+                    call(ResFun::Ln, vec![arg(0)]),
+                    call(ResFun::Ln, vec![arg(1)]),
+                );
+                self.derivative(&change_of_base, wrt)?
+            }
+
+            SymbolType::Function(Func::ResFun {
+                fun: ResFun::SymInt,
+                ..
+            }) if matches!(
+                &args[1].0.borrow().data.symbol_type,
+                SymbolType::Variable { name } if name == wrt
+            ) =>
+            {
+                // d/dx ∫ f dx -> f
+                arg(0)
+            }
+
+            SymbolType::Function(Func::ResFun { fun, .. }) if fun.num_args() == 1 => {
+                // f(g(x))' -> f'(g(x)) * g'(x)
+                match outer_derivative(fun, arg(0)) {
+                    Some(outer) => mult(outer, self.derivative(&args[0], wrt)?),
+                    None => return Err(cannot_differentiate(node)),
+                }
+            }
+
+            _ => return Err(cannot_differentiate(node)),
+        })
     }
+}
+
+/// Derivative of a single argument reserved function, evaluated at `u`.
+///
+/// Returns `None` if the function is not an elementary function and cannot be differentiated.
+fn outer_derivative(fun: ResFun, u: Node) -> Option<Node> {
+    let line_pos = line_pos(&u);
+    let n = |value: i32| num(value, line_pos);
+    let copy = || u.deep_clone();
+
+    Some(match fun {
+        // sqrt(u)' -> 1 / (2 * sqrt(u))
+        ResFun::Sqrt => div(n(1), mult(n(2), call(ResFun::Sqrt, vec![copy()]))),
+        // cbrt(u)' -> 1 / (3 * cbrt(u) ^ 2)
+        ResFun::Cbrt => div(
+            n(1),
+            mult(n(3), pow(call(ResFun::Cbrt, vec![copy()]), n(2))),
+        ),
+        // log2(u)' -> 1 / (u * ln(2))
+        ResFun::Log2 => div(n(1), mult(copy(), call(ResFun::Ln, vec![n(2)]))),
+        // log10(u)' -> 1 / (u * ln(10))
+        ResFun::Log10 => div(n(1), mult(copy(), call(ResFun::Ln, vec![n(10)]))),
+        // ln(u)' -> 1 / u
+        ResFun::Ln => div(n(1), copy()),
 
-    // (f * g * h)' -> f' * (g * h) + f * (g * h)' -> f' * g * h + f * g' * h + f * g * h'
-    node.children = new_children;
+        // sin(u)' -> cos(u)
+        ResFun::Sin => call(ResFun::Cos, vec![copy()]),
+        // cos(u)' -> -sin(u)
+        ResFun::Cos => neg(call(ResFun::Sin, vec![copy()])),
+        // tan(u)' -> sec(u) ^ 2
+        ResFun::Tan => pow(call(ResFun::Sec, vec![copy()]), n(2)),
+        // csc(u)' -> -csc(u) * cot(u)
+        ResFun::Csc => neg(mult(
+            call(ResFun::Csc, vec![copy()]),
+            call(ResFun::Cot, vec![copy()]),
+        )),
+        // sec(u)' -> sec(u) * tan(u)
+        ResFun::Sec => mult(
+            call(ResFun::Sec, vec![copy()]),
+            call(ResFun::Tan, vec![copy()]),
+        ),
+        // cot(u)' -> -csc(u) ^ 2
+        ResFun::Cot => neg(pow(call(ResFun::Csc, vec![copy()]), n(2))),
+
+        // asin(u)' -> 1 / sqrt(1 - u ^ 2)
+        ResFun::Asin => div(n(1), sqrt_one_minus(pow(copy(), n(2)))),
+        // acos(u)' -> -1 / sqrt(1 - u ^ 2)
+        ResFun::Acos => div(n(-1), sqrt_one_minus(pow(copy(), n(2)))),
+        // atan(u)' -> 1 / (1 + u ^ 2)
+        ResFun::Atan => div(n(1), add(n(1), pow(copy(), n(2)))),
+        // acsc(u)' -> -1 / (u ^ 2 * sqrt(1 - 1 / u ^ 2))
+        //this is the same as -1 / (|u| * sqrt(u ^ 2 - 1)) without needing an absolute value
+        ResFun::Acsc => div(
+            n(-1),
+            mult(
+                pow(copy(), n(2)),
+                sqrt_one_minus(div(n(1), pow(copy(), n(2)))),
+            ),
+        ),
+        // asec(u)' -> 1 / (u ^ 2 * sqrt(1 - 1 / u ^ 2))
+        ResFun::Asec => div(
+            n(1),
+            mult(
+                pow(copy(), n(2)),
+                sqrt_one_minus(div(n(1), pow(copy(), n(2)))),
+            ),
+        ),
+        // acot(u)' -> -1 / (1 + u ^ 2)
+        ResFun::Acot => div(n(-1), add(n(1), pow(copy(), n(2)))),
+
+        ResFun::Log
+        | ResFun::Calc
+        | ResFun::Der
+        | ResFun::Grad
+        | ResFun::Div
+        | ResFun::Curl
+        | ResFun::Jacob
+        | ResFun::SymInt
+        | ResFun::DefInt
+        | ResFun::Gcd
+        | ResFun::Lcm
+        | ResFun::Mod
+        | ResFun::Fact
+        | ResFun::Binom
+        | ResFun::IsPrime
+        | ResFun::Factor
+        | ResFun::Interval
+        | ResFun::Dot
+        | ResFun::Cross
+        | ResFun::Norm
+        | ResFun::Transpose
+        | ResFun::Det
+        | ResFun::Inv
+        | ResFun::Rank
+        | ResFun::Solve => return None,
+    })
 }
 
-fn simplify_div(node: &mut TreeNode<Symbol>, wrt: &Var) {
-    todo!();
+/// Builds `sqrt(1 - u)`.
+fn sqrt_one_minus(u: Node) -> Node {
+    let line_pos = line_pos(&u);
+    call(ResFun::Sqrt, vec![sub(num(1, line_pos), u)])
+}
+
+/// Error for an expression that has no derivative rule.
+fn cannot_differentiate(node: &Node) -> CASError {
+    let data = &node.0.borrow().data;
+    CASError {
+        line_pos: data.line_pos,
+        kind: CASErrorKind::CannotDifferentiate {
+            symbol: data.symbol_type.to_string(),
+        },
+    }
 }
@@ -0,0 +1,1095 @@
+///Contains functionality for finding antiderivatives of mathematical expressions. i.e.
+/// ```text
+/// ∫ x^2 dx => x^3 / 3 + C
+/// ∫ cos x dx => sin x + C,
+/// etc.
+/// ```
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::types::{
+    cas_error::{CASError, CASErrorKind},
+    cas_num::{CASNum, CASValue, Number},
+    symbol::{
+        constant::{Const, ResConst},
+        function::{Func, ResFun},
+        operator::Operator,
+        SymbolType,
+    },
+};
+
+use super::build::*;
+use super::derivative::Differentiator;
+use super::limit::{limit_at_infinity, Limit};
+use super::numeric::{self, context_for_digits};
+use super::quadrature::{quadrature, Bound};
+use super::simplify::{expand, integer_value, simplify};
+
+/// How deeply u-substitution and integration by parts can be nested inside each other before giving up, which stops integrals like `∫ e^x * sin(x) dx` from going on forever.
+const MAX_DEPTH: usize = 4;
+
+/// Largest constant term of a denominator that integer roots are searched for, when splitting a rational function into partial fractions.
+const MAX_ROOT_SEARCH: i128 = 1_000_000;
+
+/// Largest power of a polynomial that is multiplied out when integrating rational functions.
+const MAX_EXPANDED_POWER: i64 = 64;
+
+/// A polynomial with integer coefficients, where the coefficient of `x ^ n` is at index `n`.
+type Polynomial = Vec<i128>;
+
+/// Finds the antiderivative of `integrand` with respect to `wrt`, including the constant of integration `C`.
+///
+/// Returns `None` if no antiderivative could be found.
+pub(crate) fn integral(integrand: &Node, wrt: &str) -> Option<Node> {
+    let line_pos = line_pos(integrand);
+    let antiderivative = integrate(&simplify(integrand), wrt, 0)?;
+    Some(simplify(&add(
+        antiderivative,
+        res_const(ResConst::C, line_pos),
+    )))
+}
+
+/// Value of a definite integral.
+pub(crate) struct DefiniteIntegral {
+    /// The value of the integral, which is exact unless there is an `error`.
+    pub(crate) value: Node,
+    /// Estimate of the absolute error of `value` if it was approximated numerically.
+    pub(crate) error: Option<CASNum>,
+}
+
+/// Finds the integral of `integrand` with respect to `wrt` from `lower` to `upper`.
+///
+/// If an antiderivative can be found it's evaluated at the bounds, taking limits for bounds of `∞` and `-∞`. Otherwise the integral is approximated numerically to `digits` significant digits, which needs the integrand and bounds to have numeric values.
+///
+/// Either way the integrand is first checked for poles between the bounds, where the antiderivative can't be used, see [pole_divergence].
+pub(crate) fn definite_integral(
+    integrand: &Node,
+    wrt: &str,
+    lower: &Node,
+    upper: &Node,
+    digits: usize,
+) -> Result<DefiniteIntegral, CASError> {
+    if let Some(value) = pole_divergence(&simplify(integrand), wrt, lower, upper)? {
+        return Ok(DefiniteIntegral { value, error: None });
+    }
+    if let Some(value) = exact_integral(integrand, wrt, lower, upper)? {
+        return Ok(DefiniteIntegral { value, error: None });
+    }
+
+    let bound = |bound: &Node| -> Result<Bound, CASError> {
+        Ok(match infinite_bound(bound) {
+            Some(Ordering::Greater) => Bound::PosInf,
+            Some(_) => Bound::NegInf,
+            None => Bound::Finite(numeric::evaluate(
+                bound,
+                &HashMap::new(),
+                context_for_digits(digits),
+            )?),
+        })
+    };
+    let result = quadrature(integrand, wrt, &bound(lower)?, &bound(upper)?, digits)?;
+    Ok(DefiniteIntegral {
+        value: num(result.value, line_pos(integrand)),
+        error: Some(result.error),
+    })
+}
+
+/// Evaluates the antiderivative of `integrand` at the bounds, or returns `None` if there isn't one.
+///
+/// Returns an error if the antiderivative has no limit that can be found at an infinite bound, like `-cos(x)` at ∞, or if it goes to the same infinity at both bounds, since numeric approximations of those integrals would be meaningless.
+fn exact_integral(
+    integrand: &Node,
+    wrt: &str,
+    lower: &Node,
+    upper: &Node,
+) -> Result<Option<Node>, CASError> {
+    let line_pos = line_pos(integrand);
+    let Some(antiderivative) = integrate(&simplify(integrand), wrt, 0) else {
+        return Ok(None);
+    };
+    let antiderivative = simplify(&antiderivative);
+    let infinity_name = |sign: Ordering| match sign {
+        Ordering::Greater => "∞",
+        _ => "-∞",
+    };
+
+    let value_at = |bound: &Node| match infinite_bound(bound) {
+        Some(direction) => {
+            limit_at_infinity(&antiderivative, wrt, direction).ok_or_else(|| CASError {
+                line_pos,
+                kind: CASErrorKind::NoLimit {
+                    point: infinity_name(direction).to_string(),
+                },
+            })
+        }
+        None => Ok(Limit::Finite(simplify(&replace(
+            &antiderivative,
+            &var(wrt, line_pos),
+            bound,
+        )))),
+    };
+    let infinity = |sign: Ordering| infinity(sign, line_pos);
+
+    Ok(Some(match (value_at(upper)?, value_at(lower)?) {
+        (Limit::Finite(upper), Limit::Finite(lower)) => simplify(&sub(upper, lower)),
+        // F(∞) - F(a) = ∞
+        (Limit::Infinite(sign), Limit::Finite(_)) => infinity(sign),
+        (Limit::Finite(_), Limit::Infinite(sign)) => infinity(sign.reverse()),
+        (Limit::Infinite(upper), Limit::Infinite(lower)) if upper != lower => infinity(upper),
+        //∞ - ∞, where the integral diverges to ∞ on one side and -∞ on the other
+        (Limit::Infinite(_), Limit::Infinite(_)) => {
+            return Err(CASError {
+                line_pos,
+                kind: CASErrorKind::DivergentIntegral {
+                    point: String::from("-∞ and ∞"),
+                },
+            })
+        }
+    }))
+}
+
+/// Checks how the integral behaves at the poles of a canonical integrand between the bounds, including poles at the bounds themselves.
+///
+/// Next to a pole at `c` the integrand grows like `L / (x - c)` or faster when `L = lim (x - c) * f(x)` isn't 0, and then the integral diverges on that side of `c`. Returns `∞` or `-∞` if it diverges towards the same one everywhere it does, or `None` if it converges at every pole, so the antiderivative can be used as usual. It also returns `None` if it can't tell, when the bounds aren't numbers or one of the limits can't be found.
+///
+/// Poles are only looked for where the base of a negative power is 0, and only found when the base is a polynomial with integer coefficients whose roots are rational, like the `x - 1` in `(x - 1) ^ -2`.
+fn pole_divergence(
+    integrand: &Node,
+    wrt: &str,
+    lower: &Node,
+    upper: &Node,
+) -> Result<Option<Node>, CASError> {
+    let line_pos = line_pos(integrand);
+    let (Some(lower), Some(upper)) = (bound_value(lower), bound_value(upper)) else {
+        return Ok(None);
+    };
+    let reversed = matches!((&lower, &upper), (Some(lower), Some(upper)) if lower > upper);
+    let (lower, upper) = match reversed {
+        true => (upper, lower),
+        false => (lower, upper),
+    };
+    //None is an infinite bound, which is past every pole
+    let above_lower = |pole: &Number| lower.as_ref().is_none_or(|lower| pole > lower);
+    let below_upper = |pole: &Number| upper.as_ref().is_none_or(|upper| pole < upper);
+
+    let mut poles: Vec<Number> = vec![];
+    find_poles(integrand, wrt, &mut poles);
+    let mut signs: Vec<(Ordering, &Number)> = vec![];
+    for pole in &poles {
+        let sides = [
+            (Ordering::Greater, below_upper(pole)),
+            (Ordering::Less, above_lower(pole)),
+        ];
+        let between = sides.iter().all(|(_, inside)| *inside)
+            || lower.as_ref() == Some(pole)
+            || upper.as_ref() == Some(pole);
+        if !between {
+            continue;
+        }
+
+        //the pole is approached from each side that's between the bounds
+        for side in sides
+            .into_iter()
+            .filter_map(|(side, inside)| inside.then_some(side))
+        {
+            // x = c ± 1 / x, so x -> c from that side as the new x -> ∞
+            let offset = mult(
+                num(side as i64, line_pos),
+                pow(var(wrt, line_pos), num(-1, line_pos)),
+            );
+            let at = add(num(pole.clone(), line_pos), offset.clone());
+            let scaled = simplify(&mult(offset, approach(integrand, wrt, &at)));
+            let sign = match limit_at_infinity(&scaled, wrt, Ordering::Greater) {
+                Some(Limit::Finite(value)) if is_num(&value, 0) => continue,
+                Some(Limit::Finite(value)) => {
+                    match num_value(&value).and_then(|value| value.partial_cmp(&Number::from(0))) {
+                        Some(sign) => sign,
+                        None => return Ok(None),
+                    }
+                }
+                Some(Limit::Infinite(sign)) => sign,
+                None => return Ok(None),
+            };
+            //L / (x - c) has the sign of L to the right of c and the opposite sign to the left of it
+            signs.push((
+                if side == Ordering::Greater {
+                    sign
+                } else {
+                    sign.reverse()
+                },
+                pole,
+            ));
+        }
+    }
+
+    match signs.first() {
+        None => Ok(None),
+        Some((sign, _)) => match signs.iter().find(|(other, _)| other != sign) {
+            //integrating from the upper bound to the lower one flips the sign
+            None if reversed => Ok(Some(infinity(sign.reverse(), line_pos))),
+            None => Ok(Some(infinity(*sign, line_pos))),
+            Some((_, pole)) => Err(CASError {
+                line_pos,
+                kind: CASErrorKind::DivergentIntegral {
+                    point: format!("{} = {}", wrt, pole),
+                },
+            }),
+        },
+    }
+}
+
+/// Substitutes `at` for `wrt` in a canonical expression, where `at` is a pole approached as `wrt` goes to ∞, like `1 + 1 / x`.
+///
+/// The base of a negative power goes to 0 at the pole, which [limit_at_infinity] can't raise to a negative power, so its largest term is factored out first, i.e. `(2 * x ^ -1 + x ^ -2) ^ -1 -> (2 * x ^ -1) ^ -1 * (1 + x ^ -1 / 2) ^ -1`.
+fn approach(tree_node: &Node, wrt: &str, at: &Node) -> Node {
+    let line_pos = line_pos(tree_node);
+    if !contains_var(tree_node, wrt) {
+        return tree_node.deep_clone();
+    }
+    if is_variable(tree_node, wrt) {
+        return at.deep_clone();
+    }
+
+    let args = children(tree_node);
+    let negative = num_value(args.last().unwrap()).is_some_and(|exp| exp < Number::from(0));
+    if !(is_operator(tree_node, Operator::Exp) && negative) {
+        let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+        let args = args.iter().map(|arg| approach(arg, wrt, at)).collect();
+        return node(symbol_type, line_pos, args);
+    }
+
+    let base = expand(&approach(&args[0], wrt, at));
+    let exp = &args[1];
+    let largest = match is_operator(&base, Operator::Add) {
+        true => children(&base)
+            .into_iter()
+            .map(|term| Some((degree(&term, wrt)?, term)))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|terms| {
+                terms
+                    .into_iter()
+                    .max_by(|(lhs, _), (rhs, _)| lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal))
+            }),
+        false => None,
+    };
+    match largest {
+        Some((_, largest)) => {
+            let rest = expand(&mult(base, pow(largest.clone(), num(-1, line_pos))));
+            mult(pow(largest, exp.deep_clone()), pow(rest, exp.deep_clone()))
+        }
+        None => pow(base, exp.deep_clone()),
+    }
+}
+
+/// Power of `wrt` in a canonical term like `3 * x ^ -2`, or `None` if `wrt` is in it some other way, like in `sin(x)`.
+fn degree(term: &Node, wrt: &str) -> Option<Number> {
+    let factors = match is_operator(term, Operator::Mult) {
+        true => children(term),
+        false => vec![term.clone()],
+    };
+    let mut degree = Number::from(0);
+    for factor in factors.iter().filter(|factor| contains_var(factor, wrt)) {
+        if is_variable(factor, wrt) {
+            degree = degree + Number::from(1);
+            continue;
+        }
+        let args = children(factor);
+        if !(is_operator(factor, Operator::Exp) && is_variable(&args[0], wrt)) {
+            return None;
+        }
+        degree = degree + num_value(&args[1])?;
+    }
+    Some(degree)
+}
+
+/// Value of a bound for comparing it with poles, which is `None` inside the result for `∞` and `-∞`, or `None` if the bound isn't a number.
+fn bound_value(bound: &Node) -> Option<Option<Number>> {
+    if infinite_bound(bound).is_some() {
+        return Some(None);
+    }
+    let bound = simplify(bound);
+    match num_value(&bound) {
+        Some(value) => Some(Some(value)),
+        None => numeric::evaluate(&bound, &HashMap::new(), context_for_digits(20))
+            .ok()
+            .filter(|value| matches!(value.value, CASValue::Finite { .. }))
+            .map(|value| Some(Number::from(value))),
+    }
+}
+
+/// Adds the rational roots of the base of every negative power in a canonical expression to `poles`, see [pole_divergence].
+fn find_poles(tree_node: &Node, wrt: &str, poles: &mut Vec<Number>) {
+    for child in children(tree_node) {
+        find_poles(&child, wrt, poles);
+    }
+    if !is_operator(tree_node, Operator::Exp) {
+        return;
+    }
+    let args = children(tree_node);
+    let negative = num_value(&args[1]).is_some_and(|exp| exp < Number::from(0));
+    if negative && contains_var(&args[0], wrt) {
+        for root in zeros(&args[0], wrt) {
+            if !poles.contains(&root) {
+                poles.push(root);
+            }
+        }
+    }
+}
+
+/// Rational roots of a canonical expression, as far as it can find them.
+fn zeros(tree_node: &Node, wrt: &str) -> Vec<Number> {
+    let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+    let args = children(tree_node);
+    match symbol_type {
+        // a * b = 0 when a = 0 or b = 0
+        SymbolType::Operator(Operator::Mult) => {
+            args.iter().flat_map(|factor| zeros(factor, wrt)).collect()
+        }
+        SymbolType::Operator(Operator::Exp)
+            if num_value(&args[1]).is_some_and(|exp| exp > Number::from(0)) =>
+        {
+            zeros(&args[0], wrt)
+        }
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Sqrt | ResFun::Cbrt,
+            ..
+        }) => zeros(&args[0], wrt),
+        _ => match polynomial(tree_node, wrt).map(trim) {
+            // a * x + b = 0 -> x = -b / a
+            Some(poly) if poly.len() == 2 => {
+                vec![Number::from(-poly[0]) * Number::from(poly[1]).recip().unwrap()]
+            }
+            Some(poly) => integer_roots(&poly)
+                .map(|(roots, _)| roots.into_iter().map(Number::from).collect())
+                .unwrap_or_default(),
+            None => vec![],
+        },
+    }
+}
+
+/// `∞` if `sign` is [Ordering::Greater], and `-∞` otherwise.
+fn infinity(sign: Ordering, line_pos: usize) -> Node {
+    res_const(
+        if sign == Ordering::Greater {
+            ResConst::Inf
+        } else {
+            ResConst::NegInf
+        },
+        line_pos,
+    )
+}
+
+/// Returns [Ordering::Greater] if a bound is `∞` and [Ordering::Less] if it is `-∞`.
+fn infinite_bound(bound: &Node) -> Option<Ordering> {
+    let bound = simplify(bound);
+    let symbol_type = bound.0.borrow().data.symbol_type.clone();
+    match symbol_type {
+        SymbolType::Const(Const::ResConst(ResConst::Inf)) => Some(Ordering::Greater),
+        SymbolType::Const(Const::ResConst(ResConst::NegInf)) => Some(Ordering::Less),
+        SymbolType::Operator(Operator::Mult) => {
+            // -∞ can also be written as -1 * ∞
+            let args = children(&bound);
+            (args.len() == 2
+                && is_num(&args[0], -1)
+                && args[1].0.borrow().data.symbol_type
+                    == SymbolType::Const(Const::ResConst(ResConst::Inf)))
+            .then_some(Ordering::Less)
+        }
+        _ => None,
+    }
+}
+
+/// Finds an antiderivative of a canonical expression, trying each method in turn.
+fn integrate(integrand: &Node, wrt: &str, depth: usize) -> Option<Node> {
+    let line_pos = line_pos(integrand);
+
+    if !contains_var(integrand, wrt) {
+        // ∫ c dx -> c * x
+        return Some(mult(integrand.deep_clone(), var(wrt, line_pos)));
+    }
+
+    if is_operator(integrand, Operator::Add) {
+        // ∫ f + g dx -> ∫ f dx + ∫ g dx
+        let mut sum = num(0, line_pos);
+        for term in children(integrand) {
+            sum = add(sum, integrate(&term, wrt, depth)?);
+        }
+        return Some(sum);
+    }
+
+    if is_operator(integrand, Operator::Mult) {
+        let (constant, dependent): (Vec<Node>, Vec<Node>) = children(integrand)
+            .into_iter()
+            .partition(|factor| !contains_var(factor, wrt));
+        if !constant.is_empty() {
+            // ∫ c * f dx -> c * ∫ f dx
+            let dependent = simplify(&product(dependent, line_pos));
+            return Some(mult(
+                product(constant, line_pos),
+                integrate(&dependent, wrt, depth)?,
+            ));
+        }
+    }
+
+    standard_integral(integrand, wrt)
+        .or_else(|| rational_integral(integrand, wrt))
+        .or_else(|| substitution(integrand, wrt, depth))
+        .or_else(|| by_parts(integrand, wrt, depth))
+}
+
+/// Integrals of powers, exponentials and reserved functions of a linear expression, i.e. `∫ sin(a * x + b) dx -> -cos(a * x + b) / a`.
+fn standard_integral(integrand: &Node, wrt: &str) -> Option<Node> {
+    let line_pos = line_pos(integrand);
+    let symbol_type = integrand.0.borrow().data.symbol_type.clone();
+    let args = children(integrand);
+
+    match symbol_type {
+        // ∫ x dx -> x ^ 2 / 2
+        SymbolType::Variable { .. } => Some(div(
+            pow(integrand.deep_clone(), num(2, line_pos)),
+            num(2, line_pos),
+        )),
+
+        SymbolType::Operator(Operator::Exp) => {
+            let (base, exp) = (&args[0], &args[1]);
+            match (contains_var(base, wrt), contains_var(exp, wrt)) {
+                (true, false) => {
+                    let a = linear_coefficient(base, wrt)?;
+                    if is_num(exp, -1) {
+                        // ∫ 1 / (a * x + b) dx -> ln(a * x + b) / a
+                        Some(div(call(ResFun::Ln, vec![base.deep_clone()]), a))
+                    } else {
+                        // ∫ (a * x + b) ^ n dx -> (a * x + b) ^ (n + 1) / (a * (n + 1))
+                        let n_plus_one = add(exp.deep_clone(), num(1, line_pos));
+                        Some(div(
+                            pow(base.deep_clone(), n_plus_one.deep_clone()),
+                            mult(a, n_plus_one),
+                        ))
+                    }
+                }
+                (false, true) => {
+                    // ∫ c ^ (a * x + b) dx -> c ^ (a * x + b) / (a * ln(c))
+                    let a = linear_coefficient(exp, wrt)?;
+                    let ln_base = if base.0.borrow().data.symbol_type
+                        == SymbolType::Const(Const::ResConst(ResConst::E))
+                    {
+                        num(1, line_pos)
+                    } else {
+                        call(ResFun::Ln, vec![base.deep_clone()])
+                    };
+                    Some(div(integrand.deep_clone(), mult(a, ln_base)))
+                }
+                _ => None,
+            }
+        }
+
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Log, ..
+        }) => {
+            // ∫ log(a * x + c, b) dx -> ((a * x + c) * ln(a * x + c) - (a * x + c)) / (a * ln(b))
+            if contains_var(&args[1], wrt) {
+                return None;
+            }
+            let a = linear_coefficient(&args[0], wrt)?;
+            Some(div(
+                ln_antiderivative(args[0].deep_clone()),
+                mult(a, call(ResFun::Ln, vec![args[1].deep_clone()])),
+            ))
+        }
+
+        SymbolType::Function(Func::ResFun { fun, .. }) if fun.num_args() == 1 => {
+            // ∫ f(a * x + b) dx -> F(a * x + b) / a
+            let a = linear_coefficient(&args[0], wrt)?;
+            Some(div(antiderivative(fun, args[0].deep_clone())?, a))
+        }
+
+        _ => None,
+    }
+}
+
+/// Antiderivative of a single argument reserved function, evaluated at `u`.
+///
+/// Returns `None` if the function is not an elementary function.
+fn antiderivative(fun: ResFun, u: Node) -> Option<Node> {
+    let line_pos = line_pos(&u);
+    let n = |value: i32| num(value, line_pos);
+    let copy = || u.deep_clone();
+    let f = |fun: ResFun, arg: Node| call(fun, vec![arg]);
+
+    Some(match fun {
+        // ∫ sqrt(u) du -> 2 / 3 * u ^ (3 / 2)
+        ResFun::Sqrt => mult(div(n(2), n(3)), pow(copy(), div(n(3), n(2)))),
+        // ∫ cbrt(u) du -> 3 / 4 * u ^ (4 / 3)
+        ResFun::Cbrt => mult(div(n(3), n(4)), pow(copy(), div(n(4), n(3)))),
+        // ∫ ln(u) du -> u * ln(u) - u
+        ResFun::Ln => ln_antiderivative(copy()),
+        // ∫ log2(u) du -> (u * ln(u) - u) / ln(2)
+        ResFun::Log2 => div(ln_antiderivative(copy()), f(ResFun::Ln, n(2))),
+        // ∫ log10(u) du -> (u * ln(u) - u) / ln(10)
+        ResFun::Log10 => div(ln_antiderivative(copy()), f(ResFun::Ln, n(10))),
+
+        // ∫ sin(u) du -> -cos(u)
+        ResFun::Sin => neg(f(ResFun::Cos, copy())),
+        // ∫ cos(u) du -> sin(u)
+        ResFun::Cos => f(ResFun::Sin, copy()),
+        // ∫ tan(u) du -> -ln(cos(u))
+        ResFun::Tan => neg(f(ResFun::Ln, f(ResFun::Cos, copy()))),
+        // ∫ csc(u) du -> -ln(csc(u) + cot(u))
+        ResFun::Csc => neg(f(
+            ResFun::Ln,
+            add(f(ResFun::Csc, copy()), f(ResFun::Cot, copy())),
+        )),
+        // ∫ sec(u) du -> ln(sec(u) + tan(u))
+        ResFun::Sec => f(
+            ResFun::Ln,
+            add(f(ResFun::Sec, copy()), f(ResFun::Tan, copy())),
+        ),
+        // ∫ cot(u) du -> ln(sin(u))
+        ResFun::Cot => f(ResFun::Ln, f(ResFun::Sin, copy())),
+
+        // ∫ asin(u) du -> u * asin(u) + sqrt(1 - u ^ 2)
+        ResFun::Asin => add(
+            mult(copy(), f(ResFun::Asin, copy())),
+            f(ResFun::Sqrt, sub(n(1), pow(copy(), n(2)))),
+        ),
+        // ∫ acos(u) du -> u * acos(u) - sqrt(1 - u ^ 2)
+        ResFun::Acos => sub(
+            mult(copy(), f(ResFun::Acos, copy())),
+            f(ResFun::Sqrt, sub(n(1), pow(copy(), n(2)))),
+        ),
+        // ∫ atan(u) du -> u * atan(u) - ln(1 + u ^ 2) / 2
+        ResFun::Atan => sub(
+            mult(copy(), f(ResFun::Atan, copy())),
+            div(f(ResFun::Ln, add(n(1), pow(copy(), n(2)))), n(2)),
+        ),
+        // ∫ acsc(u) du -> u * acsc(u) + ln(u + sqrt(u ^ 2 - 1))
+        ResFun::Acsc => add(
+            mult(copy(), f(ResFun::Acsc, copy())),
+            f(
+                ResFun::Ln,
+                add(copy(), f(ResFun::Sqrt, sub(pow(copy(), n(2)), n(1)))),
+            ),
+        ),
+        // ∫ asec(u) du -> u * asec(u) - ln(u + sqrt(u ^ 2 - 1))
+        ResFun::Asec => sub(
+            mult(copy(), f(ResFun::Asec, copy())),
+            f(
+                ResFun::Ln,
+                add(copy(), f(ResFun::Sqrt, sub(pow(copy(), n(2)), n(1)))),
+            ),
+        ),
+        // ∫ acot(u) du -> u * acot(u) + ln(1 + u ^ 2) / 2
+        ResFun::Acot => add(
+            mult(copy(), f(ResFun::Acot, copy())),
+            div(f(ResFun::Ln, add(n(1), pow(copy(), n(2)))), n(2)),
+        ),
+
+        ResFun::Log
+        | ResFun::Calc
+        | ResFun::Der
+        | ResFun::Grad
+        | ResFun::Div
+        | ResFun::Curl
+        | ResFun::Jacob
+        | ResFun::SymInt
+        | ResFun::DefInt
+        | ResFun::Gcd
+        | ResFun::Lcm
+        | ResFun::Mod
+        | ResFun::Fact
+        | ResFun::Binom
+        | ResFun::IsPrime
+        | ResFun::Factor
+        | ResFun::Interval
+        | ResFun::Dot
+        | ResFun::Cross
+        | ResFun::Norm
+        | ResFun::Transpose
+        | ResFun::Det
+        | ResFun::Inv
+        | ResFun::Rank
+        | ResFun::Solve => return None,
+    })
+}
+
+/// Builds `u * ln(u) - u`, the antiderivative of `ln(u)`.
+fn ln_antiderivative(u: Node) -> Node {
+    sub(
+        mult(u.deep_clone(), call(ResFun::Ln, vec![u.deep_clone()])),
+        u,
+    )
+}
+
+/// Integrals of polynomials divided by polynomials, found by splitting them into partial fractions.
+///
+/// Only handles denominators that are constants, products of distinct linear factors with integer roots, or quadratics with no real roots.
+fn rational_integral(integrand: &Node, wrt: &str) -> Option<Node> {
+    let line_pos = line_pos(integrand);
+    let (numerator, denominator) = rational_parts(integrand, wrt)?;
+
+    if denominator.len() == 1 {
+        return integrate_polynomial(&numerator, denominator[0], wrt, line_pos);
+    }
+
+    let (roots, rest) = integer_roots(&denominator)?;
+    let distinct = roots
+        .iter()
+        .enumerate()
+        .all(|(idx, root)| !roots[..idx].contains(root));
+
+    if rest.len() == 1 && distinct {
+        // p(x) / (c * (x - r_1) * (x - r_2) * ...) = (s(x) + a_1 / (x - r_1) + a_2 / (x - r_2) + ...) / c
+        //where s is the quotient of p and (x - r_1) * (x - r_2) * ..., and a_i = p(r_i) / (product of r_i - r_j for every j != i)
+        let leading = rest[0];
+        let mut monic: Polynomial = vec![1];
+        for root in &roots {
+            monic = multiply(&monic, &[-root, 1])?;
+        }
+        let (quotient, _) = divide_monic(&numerator, &monic)?;
+
+        let mut result = integrate_polynomial(&quotient, leading, wrt, line_pos)?;
+        for root in &roots {
+            let mut scale = leading;
+            for other in roots.iter().filter(|other| *other != root) {
+                scale = scale.checked_mul(root - other)?;
+            }
+            // ∫ a / (x - r) dx -> a * ln(x - r)
+            let coefficient = div(
+                num(evaluate(&numerator, *root)?, line_pos),
+                num(scale, line_pos),
+            );
+            let ln = call(
+                ResFun::Ln,
+                vec![sub(var(wrt, line_pos), num(*root, line_pos))],
+            );
+            result = add(result, mult(coefficient, ln));
+        }
+        return Some(result);
+    }
+
+    if rest.len() == 3 && roots.is_empty() {
+        let (c, b, a) = (rest[0], rest[1], rest[2]);
+        let discriminant = 4 * a * c - b * b;
+        if discriminant <= 0 {
+            return None;
+        }
+
+        let (quotient, remainder) = if numerator.len() <= 2 {
+            (vec![], numerator)
+        } else if a == 1 {
+            divide_monic(&numerator, &rest)?
+        } else {
+            return None;
+        };
+        let p = remainder.get(1).copied().unwrap_or(0);
+        let q = remainder.first().copied().unwrap_or(0);
+
+        let sqrt_discriminant = || match integer_sqrt(discriminant) {
+            Some(root) => num(root, line_pos),
+            None => call(ResFun::Sqrt, vec![num(discriminant, line_pos)]),
+        };
+        let x = var(wrt, line_pos);
+
+        // ∫ (p * x + q) / (a * x ^ 2 + b * x + c) dx -> p / (2 * a) * ln(a * x ^ 2 + b * x + c) + (2 * a * q - p * b) / (a * sqrt(d)) * atan((2 * a * x + b) / sqrt(d))
+        //where d = 4 * a * c - b ^ 2
+        let ln_term = mult(
+            div(num(p, line_pos), num(2 * a, line_pos)),
+            call(
+                ResFun::Ln,
+                vec![polynomial_node(&denominator, wrt, line_pos)],
+            ),
+        );
+        let atan_term = mult(
+            div(
+                num(2 * a * q - p * b, line_pos),
+                mult(num(a, line_pos), sqrt_discriminant()),
+            ),
+            call(
+                ResFun::Atan,
+                vec![div(
+                    add(mult(num(2 * a, line_pos), x), num(b, line_pos)),
+                    sqrt_discriminant(),
+                )],
+            ),
+        );
+        return Some(add(
+            integrate_polynomial(&quotient, 1, wrt, line_pos)?,
+            add(ln_term, atan_term),
+        ));
+    }
+
+    None
+}
+
+/// Splits a canonical expression into a numerator and denominator that are both polynomials with integer coefficients, if it can be written that way.
+fn rational_parts(integrand: &Node, wrt: &str) -> Option<(Polynomial, Polynomial)> {
+    let factors = if is_operator(integrand, Operator::Mult) {
+        children(integrand)
+    } else {
+        vec![integrand.clone()]
+    };
+
+    let mut numerator: Polynomial = vec![1];
+    let mut denominator: Polynomial = vec![1];
+    for factor in factors {
+        let exp = if is_operator(&factor, Operator::Exp) {
+            integer_value(&children(&factor)[1])
+        } else {
+            None
+        };
+
+        match exp {
+            Some(n) if n < 0 => {
+                let base = polynomial(&children(&factor)[0], wrt)?;
+                denominator = multiply(&denominator, &power(&base, -n)?)?;
+            }
+            _ => numerator = multiply(&numerator, &polynomial(&factor, wrt)?)?,
+        }
+    }
+    Some((numerator, denominator))
+}
+
+/// Converts a canonical expression into a polynomial in `wrt` with integer coefficients, if it is one.
+fn polynomial(tree_node: &Node, wrt: &str) -> Option<Polynomial> {
+    let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+    let args = children(tree_node);
+
+    match symbol_type {
+        SymbolType::Variable { name } if name == wrt => Some(vec![0, 1]),
+        SymbolType::Num { .. } => Some(trim(vec![integer_value(tree_node)? as i128])),
+        SymbolType::Operator(Operator::Add) => args
+            .iter()
+            .try_fold(vec![], |sum, term| plus(&sum, &polynomial(term, wrt)?)),
+        SymbolType::Operator(Operator::Mult) => args.iter().try_fold(vec![1], |product, factor| {
+            multiply(&product, &polynomial(factor, wrt)?)
+        }),
+        SymbolType::Operator(Operator::Exp) => {
+            let n = integer_value(&args[1])?;
+            if !(0..=MAX_EXPANDED_POWER).contains(&n) {
+                return None;
+            }
+            power(&polynomial(&args[0], wrt)?, n)
+        }
+        _ => None,
+    }
+}
+
+/// Builds the expression for a polynomial in `wrt`.
+fn polynomial_node(poly: &[i128], wrt: &str, line_pos: usize) -> Node {
+    let mut sum = num(0, line_pos);
+    for (degree, coefficient) in poly.iter().enumerate() {
+        sum = add(
+            sum,
+            mult(
+                num(*coefficient, line_pos),
+                pow(var(wrt, line_pos), num(degree as i128, line_pos)),
+            ),
+        );
+    }
+    sum
+}
+
+/// Integrates a polynomial divided by a constant, i.e. `∫ (c_n * x ^ n + ...) / d dx -> c_n / (d * (n + 1)) * x ^ (n + 1) + ...`.
+fn integrate_polynomial(poly: &[i128], divisor: i128, wrt: &str, line_pos: usize) -> Option<Node> {
+    let mut sum = num(0, line_pos);
+    for (degree, coefficient) in poly.iter().enumerate() {
+        let degree = degree as i128 + 1;
+        sum = add(
+            sum,
+            mult(
+                div(
+                    num(*coefficient, line_pos),
+                    num(divisor.checked_mul(degree)?, line_pos),
+                ),
+                pow(var(wrt, line_pos), num(degree, line_pos)),
+            ),
+        );
+    }
+    Some(sum)
+}
+
+/// Removes zero coefficients of the highest powers, so the length of a polynomial is one more than its degree (or 0 for the zero polynomial).
+fn trim(mut poly: Polynomial) -> Polynomial {
+    while poly.last() == Some(&0) {
+        poly.pop();
+    }
+    poly
+}
+
+/// Adds two polynomials, or returns `None` if a coefficient overflows.
+fn plus(lhs: &[i128], rhs: &[i128]) -> Option<Polynomial> {
+    let mut sum: Polynomial = vec![0; lhs.len().max(rhs.len())];
+    for (idx, coefficient) in lhs.iter().enumerate() {
+        sum[idx] = *coefficient;
+    }
+    for (idx, coefficient) in rhs.iter().enumerate() {
+        sum[idx] = sum[idx].checked_add(*coefficient)?;
+    }
+    Some(trim(sum))
+}
+
+/// Multiplies two polynomials, or returns `None` if a coefficient overflows.
+fn multiply(lhs: &[i128], rhs: &[i128]) -> Option<Polynomial> {
+    if lhs.is_empty() || rhs.is_empty() {
+        return Some(vec![]);
+    }
+    let mut product: Polynomial = vec![0; lhs.len() + rhs.len() - 1];
+    for (i, a) in lhs.iter().enumerate() {
+        for (j, b) in rhs.iter().enumerate() {
+            product[i + j] = product[i + j].checked_add(a.checked_mul(*b)?)?;
+        }
+    }
+    Some(trim(product))
+}
+
+/// Raises a polynomial to a non-negative integer power.
+fn power(poly: &[i128], exp: i64) -> Option<Polynomial> {
+    let mut result = vec![1];
+    for _ in 0..exp {
+        result = multiply(&result, poly)?;
+    }
+    Some(result)
+}
+
+/// Evaluates a polynomial at an integer.
+fn evaluate(poly: &[i128], at: i128) -> Option<i128> {
+    poly.iter().rev().try_fold(0_i128, |value, coefficient| {
+        value.checked_mul(at)?.checked_add(*coefficient)
+    })
+}
+
+/// Divides a polynomial by one whose highest coefficient is 1, returning the quotient and remainder.
+fn divide_monic(dividend: &[i128], divisor: &[i128]) -> Option<(Polynomial, Polynomial)> {
+    let degree = divisor.len() - 1;
+    let mut remainder = dividend.to_vec();
+    if remainder.len() <= degree {
+        return Some((vec![], remainder));
+    }
+
+    let mut quotient = vec![0; remainder.len() - degree];
+    for idx in (0..quotient.len()).rev() {
+        let coefficient = remainder[idx + degree];
+        quotient[idx] = coefficient;
+        for (offset, divisor_coefficient) in divisor.iter().enumerate() {
+            remainder[idx + offset] = remainder[idx + offset]
+                .checked_sub(coefficient.checked_mul(*divisor_coefficient)?)?;
+        }
+    }
+    Some((trim(quotient), trim(remainder)))
+}
+
+/// Finds the integer roots of a polynomial, with repeated roots listed once for each time they repeat.
+///
+/// Returns the roots along with what's left of the polynomial after dividing out `x - r` for every root `r`.
+fn integer_roots(poly: &[i128]) -> Option<(Vec<i128>, Polynomial)> {
+    let mut poly = poly.to_vec();
+    let mut roots = vec![];
+
+    while poly.len() > 1 && poly[0] == 0 {
+        // x is a factor
+        poly.remove(0);
+        roots.push(0);
+    }
+
+    let constant = poly[0].checked_abs()?;
+    if poly.len() > 1 && constant <= MAX_ROOT_SEARCH {
+        //any integer root has to divide the constant term
+        for divisor in (1..=constant).filter(|divisor| constant % divisor == 0) {
+            for root in [divisor, -divisor] {
+                while poly.len() > 1 && evaluate(&poly, root)? == 0 {
+                    poly = divide_monic(&poly, &[-root, 1])?.0;
+                    roots.push(root);
+                }
+            }
+        }
+    }
+    Some((roots, poly))
+}
+
+/// Finds the square root of a perfect square.
+fn integer_sqrt(value: i128) -> Option<i128> {
+    let root = (value as f64).sqrt().round() as i128;
+    (root * root == value).then_some(root)
+}
+
+/// Integrates by substituting `u` for a subexpression whose derivative divides the rest of the integrand, i.e. `∫ 2 * x * cos(x ^ 2) dx -> ∫ cos(u) du` with `u = x ^ 2`.
+fn substitution(integrand: &Node, wrt: &str, depth: usize) -> Option<Node> {
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+
+    let line_pos = line_pos(integrand);
+    let sub_var = format!("#u{}", depth);
+    //can't clash with any of the user's variables since it isn't a valid name
+
+    let mut candidates: Vec<Node> = vec![];
+    subexpressions(integrand, wrt, &mut candidates);
+
+    for u in candidates {
+        if u == *integrand || is_variable(&u, wrt) {
+            continue;
+        }
+        let Some(du) = derivative(&u, wrt) else {
+            continue;
+        };
+        if is_num(&du, 0) {
+            continue;
+        }
+
+        let quotient = simplify(&div(integrand.deep_clone(), du));
+        let substituted = replace(&quotient, &u, &var(&sub_var, line_pos));
+        if contains_var(&substituted, wrt) {
+            continue;
+        }
+
+        if let Some(result) = integrate(&simplify(&substituted), &sub_var, depth + 1) {
+            return Some(replace(&result, &var(&sub_var, line_pos), &u));
+        }
+    }
+    None
+}
+
+/// How good a factor is as the `u` in integration by parts, from worst to best.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum PartsPriority {
+    /// `e ^ x`, which stays the same when differentiated.
+    Exponential,
+    /// `sin(x)`, `cos(x)`, etc, which cycle when differentiated.
+    Trig,
+    /// Polynomials, which eventually differentiate to a constant.
+    Algebraic,
+    /// `atan(x)`, `asin(x)`, etc, which differentiate to algebraic functions.
+    InverseTrig,
+    /// `ln(x)` and other logarithms, which differentiate to algebraic functions.
+    Logarithmic,
+}
+
+/// Integrates by parts, `∫ u dv = u * v - ∫ v du`, choosing the factor that becomes simplest when differentiated as `u`.
+fn by_parts(integrand: &Node, wrt: &str, depth: usize) -> Option<Node> {
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+
+    let line_pos = line_pos(integrand);
+    let factors = if is_operator(integrand, Operator::Mult) {
+        children(integrand)
+    } else {
+        vec![integrand.clone()]
+    };
+
+    let (idx, priority) = factors
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, factor)| parts_priority(factor, wrt).map(|priority| (idx, priority)))
+        .max_by_key(|(_, priority)| *priority)?;
+
+    if factors.len() == 1 && priority < PartsPriority::InverseTrig {
+        //dv would be dx, which only helps if u gets simpler when differentiated
+        return None;
+    }
+
+    let u = factors[idx].clone();
+    let dv: Vec<Node> = factors
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != idx)
+        .map(|(_, factor)| factor.clone())
+        .collect();
+
+    let v = integrate(&simplify(&product(dv, line_pos)), wrt, depth + 1)?;
+    let du = derivative(&u, wrt)?;
+    let v_du = integrate(&simplify(&mult(v.deep_clone(), du)), wrt, depth + 1)?;
+    Some(sub(mult(u.deep_clone(), v), v_du))
+}
+
+/// Classifies a factor for [by_parts], or returns `None` if it isn't any of the kinds of factors it knows about.
+fn parts_priority(factor: &Node, wrt: &str) -> Option<PartsPriority> {
+    let symbol_type = factor.0.borrow().data.symbol_type.clone();
+    let args = children(factor);
+
+    match symbol_type {
+        SymbolType::Function(Func::ResFun { fun, .. }) => match fun {
+            ResFun::Ln | ResFun::Log | ResFun::Log2 | ResFun::Log10 => {
+                Some(PartsPriority::Logarithmic)
+            }
+            ResFun::Asin
+            | ResFun::Acos
+            | ResFun::Atan
+            | ResFun::Acsc
+            | ResFun::Asec
+            | ResFun::Acot => Some(PartsPriority::InverseTrig),
+            ResFun::Sin | ResFun::Cos | ResFun::Tan | ResFun::Csc | ResFun::Sec | ResFun::Cot => {
+                Some(PartsPriority::Trig)
+            }
+            _ => None,
+        },
+        SymbolType::Operator(Operator::Exp)
+            if !contains_var(&args[0], wrt) && contains_var(&args[1], wrt) =>
+        {
+            Some(PartsPriority::Exponential)
+        }
+        SymbolType::Operator(Operator::Exp)
+            if integer_value(&args[1]).is_some_and(|n| n > 0)
+                && polynomial(&args[0], wrt).is_none() =>
+        {
+            // ln(x) ^ 2 is as good as ln(x)
+            parts_priority(&args[0], wrt)
+        }
+        _ => polynomial(factor, wrt).map(|_| PartsPriority::Algebraic),
+    }
+}
+
+/// Finds the derivative of a subtree in canonical form, or `None` if it can't be differentiated.
+fn derivative(tree_node: &Node, wrt: &str) -> Option<Node> {
+    Differentiator::default()
+        .derivative(tree_node, wrt)
+        .ok()
+        .map(|derivative| simplify(&derivative))
+}
+
+/// Returns the derivative of `u` if `u` is linear in `wrt`, i.e. `a` for `u = a * x + b`.
+fn linear_coefficient(u: &Node, wrt: &str) -> Option<Node> {
+    let du = derivative(u, wrt)?;
+    (!contains_var(&du, wrt) && !is_num(&du, 0)).then_some(du)
+}
+
+/// Adds every distinct subtree that contains `wrt` to `found`, parents before their children.
+fn subexpressions(tree_node: &Node, wrt: &str, found: &mut Vec<Node>) {
+    if !contains_var(tree_node, wrt) {
+        return;
+    }
+    if !found.contains(tree_node) {
+        found.push(tree_node.clone());
+    }
+    for child in children(tree_node) {
+        subexpressions(&child, wrt, found);
+    }
+}
+
+/// Builds a copy of a subtree with every occurrence of `target` replaced by `replacement`.
+fn replace(tree_node: &Node, target: &Node, replacement: &Node) -> Node {
+    if tree_node == target {
+        return replacement.deep_clone();
+    }
+    let data = tree_node.0.borrow().data.clone();
+    node(
+        data.symbol_type,
+        data.line_pos,
+        children(tree_node)
+            .iter()
+            .map(|child| replace(child, target, replacement))
+            .collect(),
+    )
+}
+
+/// Builds the product of a list of factors.
+fn product(factors: Vec<Node>, line_pos: usize) -> Node {
+    factors.into_iter().fold(num(1, line_pos), mult)
+}
+
+/// Checks if a node is the variable `name`.
+fn is_variable(tree_node: &Node, name: &str) -> bool {
+    matches!(
+        &tree_node.0.borrow().data.symbol_type,
+        SymbolType::Variable { name: var_name } if var_name == name
+    )
+}
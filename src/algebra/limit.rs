@@ -0,0 +1,259 @@
+///Contains functionality for finding limits of mathematical expressions as a variable grows without bound. i.e.
+/// ```text
+/// lim x->∞ 1 / x => 0
+/// lim x->∞ atan(x) => π / 2,
+/// etc.
+/// ```
+use std::cmp::Ordering;
+
+use crate::types::{
+    cas_num::Number,
+    symbol::{
+        constant::{Const, ResConst},
+        function::{Func, ResFun},
+        operator::Operator,
+        SymbolType,
+    },
+};
+
+use super::build::*;
+use super::simplify::{integer_value, simplify};
+
+/// The limit of an expression.
+pub(crate) enum Limit {
+    /// The expression approaches a value that doesn't depend on the variable, in canonical form.
+    Finite(Node),
+    /// The expression grows without bound, towards ∞ for [Ordering::Greater] and -∞ for [Ordering::Less].
+    Infinite(Ordering),
+}
+
+/// Finds the limit of a canonical expression as `wrt` goes to ∞ if `direction` is [Ordering::Greater], or -∞ if it is [Ordering::Less].
+///
+/// Returns `None` if the limit doesn't exist or couldn't be found, like for `sin(x)` or `x - x ^ 2`.
+pub(crate) fn limit_at_infinity(tree_node: &Node, wrt: &str, direction: Ordering) -> Option<Limit> {
+    if !contains_var(tree_node, wrt) {
+        return Some(Limit::Finite(tree_node.deep_clone()));
+    }
+
+    let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+    let args = children(tree_node);
+
+    match symbol_type {
+        SymbolType::Variable { .. } => Some(Limit::Infinite(direction)),
+        //we know this is wrt since it contains wrt
+        SymbolType::Operator(Operator::Add) => sum_limit(&args, wrt, direction),
+        SymbolType::Operator(Operator::Mult) => product_limit(&args, wrt, direction),
+        SymbolType::Operator(Operator::Exp) => power_limit(&args[0], &args[1], wrt, direction),
+        SymbolType::Function(Func::ResFun { fun, .. }) if fun.num_args() == 1 => {
+            function_limit(fun, &args[0], wrt, direction)
+        }
+        _ => None,
+    }
+}
+
+/// Limit of a sum, where terms that grow exponentially outweigh any others.
+fn sum_limit(terms: &[Node], wrt: &str, direction: Ordering) -> Option<Limit> {
+    let line_pos = line_pos(&terms[0]);
+    let mut finite = num(0, line_pos);
+    let mut infinite: Vec<(Ordering, &Node)> = vec![];
+
+    for term in terms {
+        match limit_at_infinity(term, wrt, direction)? {
+            Limit::Finite(value) => finite = add(finite, value),
+            Limit::Infinite(sign) => infinite.push((sign, term)),
+        }
+    }
+
+    if infinite.is_empty() {
+        return Some(Limit::Finite(simplify(&finite)));
+    }
+
+    let dominant: Vec<Ordering> = if infinite.iter().any(|(_, term)| has_exponential(term, wrt)) {
+        // ∞ - ∞ only has a limit here if one side grows exponentially and the other doesn't, like e ^ x - x ^ 2 -> ∞
+        infinite
+            .iter()
+            .filter(|(_, term)| has_exponential(term, wrt))
+            .map(|(sign, _)| *sign)
+            .collect()
+    } else {
+        infinite.iter().map(|(sign, _)| *sign).collect()
+    };
+
+    let sign = dominant[0];
+    dominant
+        .iter()
+        .all(|other| *other == sign)
+        .then_some(Limit::Infinite(sign))
+}
+
+/// Limit of a product, where factors that decay exponentially outweigh factors that grow without growing exponentially.
+fn product_limit(factors: &[Node], wrt: &str, direction: Ordering) -> Option<Limit> {
+    let line_pos = line_pos(&factors[0]);
+    let mut finite = num(1, line_pos);
+    let mut vanishing: Vec<&Node> = vec![];
+    let mut infinite: Vec<(Ordering, &Node)> = vec![];
+
+    for factor in factors {
+        match limit_at_infinity(factor, wrt, direction)? {
+            Limit::Finite(value) if is_num(&value, 0) => vanishing.push(factor),
+            Limit::Finite(value) => finite = mult(finite, value),
+            Limit::Infinite(sign) => infinite.push((sign, factor)),
+        }
+    }
+
+    if infinite.is_empty() {
+        if !vanishing.is_empty() {
+            return Some(Limit::Finite(num(0, line_pos)));
+        }
+        return Some(Limit::Finite(simplify(&finite)));
+    }
+
+    if vanishing.is_empty() {
+        let finite_sign = sign(&simplify(&finite))?;
+        let sign = infinite
+            .iter()
+            .fold(finite_sign, |sign, (other, _)| multiply_signs(sign, *other));
+        return Some(Limit::Infinite(sign));
+    }
+
+    // 0 * ∞ only has a limit here if the factors going to 0 decay exponentially and the others don't grow exponentially, like x * e ^ -x -> 0
+    let decays = vanishing.iter().all(|factor| has_exponential(factor, wrt));
+    let grows = infinite
+        .iter()
+        .any(|(_, factor)| has_exponential(factor, wrt));
+    (decays && !grows).then(|| Limit::Finite(num(0, line_pos)))
+}
+
+/// Limit of `base ^ exp`.
+fn power_limit(base: &Node, exp: &Node, wrt: &str, direction: Ordering) -> Option<Limit> {
+    let line_pos = line_pos(base);
+
+    if !contains_var(base, wrt) {
+        // c ^ g
+        return match limit_at_infinity(exp, wrt, direction)? {
+            Limit::Finite(exp) => Some(Limit::Finite(simplify(&pow(base.deep_clone(), exp)))),
+            Limit::Infinite(exp_sign) => {
+                if sign(base)? != Ordering::Greater {
+                    return None;
+                }
+                match (compare_to_one(base)?, exp_sign) {
+                    (Ordering::Equal, _) => Some(Limit::Finite(num(1, line_pos))),
+                    (Ordering::Greater, Ordering::Greater) | (Ordering::Less, Ordering::Less) => {
+                        Some(Limit::Infinite(Ordering::Greater))
+                    }
+                    _ => Some(Limit::Finite(num(0, line_pos))),
+                }
+            }
+        };
+    }
+
+    if contains_var(exp, wrt) {
+        return None;
+    }
+
+    // f ^ c
+    match limit_at_infinity(base, wrt, direction)? {
+        Limit::Finite(base) => {
+            if is_num(&base, 0) && sign(exp)? != Ordering::Greater {
+                return None;
+            }
+            Some(Limit::Finite(simplify(&pow(base, exp.deep_clone()))))
+        }
+        Limit::Infinite(base_sign) => match sign(exp)? {
+            Ordering::Less => Some(Limit::Finite(num(0, line_pos))),
+            Ordering::Equal => Some(Limit::Finite(num(1, line_pos))),
+            Ordering::Greater if base_sign == Ordering::Greater => {
+                Some(Limit::Infinite(Ordering::Greater))
+            }
+            Ordering::Greater => match integer_value(exp)? % 2 {
+                0 => Some(Limit::Infinite(Ordering::Greater)),
+                _ => Some(Limit::Infinite(Ordering::Less)),
+            },
+        },
+    }
+}
+
+/// Limit of a single argument reserved function.
+fn function_limit(fun: ResFun, arg: &Node, wrt: &str, direction: Ordering) -> Option<Limit> {
+    let line_pos = line_pos(arg);
+    let half_pi = || div(res_const(ResConst::Pi, line_pos), num(2, line_pos));
+    let is_log = matches!(fun, ResFun::Ln | ResFun::Log2 | ResFun::Log10);
+
+    match limit_at_infinity(arg, wrt, direction)? {
+        Limit::Finite(value) if is_log && is_num(&value, 0) => {
+            //logarithms are only defined for positive numbers, so the argument approaches 0 from above
+            Some(Limit::Infinite(Ordering::Less))
+        }
+        Limit::Finite(value) => Some(Limit::Finite(simplify(&call(fun, vec![value])))),
+        Limit::Infinite(sign) => {
+            let value = match (fun, sign) {
+                (ResFun::Ln | ResFun::Log2 | ResFun::Log10 | ResFun::Sqrt, Ordering::Greater) => {
+                    return Some(Limit::Infinite(Ordering::Greater))
+                }
+                (ResFun::Cbrt, _) => return Some(Limit::Infinite(sign)),
+                (ResFun::Atan, Ordering::Greater) | (ResFun::Asec, _) => half_pi(),
+                (ResFun::Atan, _) => neg(half_pi()),
+                (ResFun::Acot, Ordering::Greater) | (ResFun::Acsc, _) => num(0, line_pos),
+                _ => return None,
+            };
+            Some(Limit::Finite(simplify(&value)))
+        }
+    }
+}
+
+/// Checks if a term has a factor like `e ^ x` or `2 ^ -x`, with a constant base and an exponent containing `wrt`.
+fn has_exponential(term: &Node, wrt: &str) -> bool {
+    let factors = if is_operator(term, Operator::Mult) {
+        children(term)
+    } else {
+        vec![term.clone()]
+    };
+    factors.iter().any(|factor| {
+        is_operator(factor, Operator::Exp) && {
+            let args = children(factor);
+            !contains_var(&args[0], wrt) && contains_var(&args[1], wrt)
+        }
+    })
+}
+
+/// Finds whether a constant expression is positive, negative or zero, if it can tell.
+fn sign(tree_node: &Node) -> Option<Ordering> {
+    let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+    let args = children(tree_node);
+
+    match symbol_type {
+        SymbolType::Num { value } => value.partial_cmp(&Number::from(0)),
+        SymbolType::Const(Const::ResConst(
+            ResConst::Pi | ResConst::E | ResConst::Phi | ResConst::Tau,
+        )) => Some(Ordering::Greater),
+        SymbolType::Operator(Operator::Mult) => {
+            args.iter().try_fold(Ordering::Greater, |acc, factor| {
+                Some(multiply_signs(acc, sign(factor)?))
+            })
+        }
+        SymbolType::Operator(Operator::Exp) if sign(&args[0])? == Ordering::Greater => {
+            Some(Ordering::Greater)
+        }
+        _ => None,
+    }
+}
+
+/// Compares a positive constant to 1, if it can tell.
+fn compare_to_one(tree_node: &Node) -> Option<Ordering> {
+    match tree_node.0.borrow().data.symbol_type.clone() {
+        SymbolType::Num { value } => value.partial_cmp(&Number::from(1)),
+        SymbolType::Const(Const::ResConst(
+            ResConst::Pi | ResConst::E | ResConst::Phi | ResConst::Tau,
+        )) => Some(Ordering::Greater),
+        _ => None,
+    }
+}
+
+/// Sign of the product of two numbers with the given signs.
+fn multiply_signs(lhs: Ordering, rhs: Ordering) -> Ordering {
+    match (lhs, rhs) {
+        (Ordering::Equal, _) | (_, Ordering::Equal) => Ordering::Equal,
+        _ if lhs == rhs => Ordering::Greater,
+        _ => Ordering::Less,
+    }
+}
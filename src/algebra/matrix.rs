@@ -0,0 +1,559 @@
+//! Matrix products, determinants, inverses, ranks and linear systems.
+//!
+//! Matrices of numbers are eliminated with exact rationals, or CASNums if any entry is inexact, and anything else symbolically with Bareiss' fraction-free elimination. Inexact entries are rounded after every step, to the precision calc works to by default.
+
+use std::cmp::Ordering;
+
+use crate::{
+    parser::trees::Tree,
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        cas_matrix::CASMatrix,
+        cas_num::{Number, RoundingMode},
+        cas_vec::CASVec,
+        symbol::{operator::Operator, Symbol, SymbolType},
+    },
+};
+
+use super::build::{self, is_num, node, num, num_value, Node};
+use super::numeric::context_for_digits;
+use super::simplify::{expand, integer_value, simplify};
+use super::vector::elementwise;
+use super::NUMERIC_DIGITS;
+
+/// Matrix product of the arguments of `*`, or None unless one of them is a matrix and the other is a matrix or vector.
+///
+/// A vector on the right is a column and a vector on the left is a row, so `A * v` and `v * A` are vectors.
+pub(crate) fn product(lhs: &Node, rhs: &Node, line_pos: usize) -> Result<Option<Node>, CASError> {
+    let lhs_matrix = CASMatrix::from_node(lhs, line_pos)?;
+    let rhs_matrix = CASMatrix::from_node(rhs, line_pos)?;
+
+    let elements = match (lhs_matrix, rhs_matrix) {
+        (Some(lhs), Some(rhs)) => {
+            return Ok(Some(multiply(&lhs, &rhs, line_pos)?.into_node(line_pos)))
+        }
+        (Some(lhs), None) => match CASVec::from_node(rhs) {
+            Some(rhs) => multiply(&lhs, &CASMatrix::column(rhs), line_pos)?
+                .rows
+                .into_iter()
+                .map(|mut row| row.remove(0))
+                .collect(),
+            None => return Ok(None),
+        },
+        (None, Some(rhs)) => match CASVec::from_node(lhs) {
+            Some(lhs) => multiply(&CASMatrix::row(lhs), &rhs, line_pos)?
+                .rows
+                .remove(0),
+            None => return Ok(None),
+        },
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(CASVec::new(elements).into_node(line_pos)))
+}
+
+/// Determinant of a square matrix.
+pub(crate) fn determinant(matrix: &CASMatrix, line_pos: usize) -> Result<Node, CASError> {
+    matrix.check_square(line_pos)?;
+    let size = matrix.num_rows();
+
+    if let Some(mut rows) = numeric(matrix) {
+        let (pivots, swapped) = eliminate(&mut rows, size);
+        if pivots.len() < size {
+            return Ok(num(0, line_pos));
+        }
+        let det = (0..size).fold(Number::from(1), |det, idx| {
+            rounded(det * rows[idx][idx].clone())
+        });
+        return Ok(num(written(if swapped { -det } else { det }), line_pos));
+    }
+
+    let mut rows = symbolic(matrix);
+    let (pivots, swapped) = bareiss(&mut rows, line_pos);
+    if pivots.len() < size {
+        return Ok(num(0, line_pos));
+    }
+    //the last pivot of Bareiss' elimination is the determinant
+    let det = rows[size - 1][size - 1].clone();
+    Ok(if swapped {
+        expand(&build::neg(det))
+    } else {
+        det
+    })
+}
+
+/// Inverse of a square matrix, or a [CASErrorKind::SingularMatrix] error if it has none.
+///
+/// Symbolic inverses are the adjugate over the determinant, with the entry in row `i` and column `j` found from the minor without row `j` and column `i`.
+pub(crate) fn inverse(matrix: &CASMatrix, line_pos: usize) -> Result<CASMatrix, CASError> {
+    matrix.check_square(line_pos)?;
+    let size = matrix.num_rows();
+
+    if let Some(rows) = numeric(matrix) {
+        let identity = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| Number::from((row == col) as i32))
+                    .collect()
+            })
+            .collect();
+        let inverse = solve_numeric(rows, identity, line_pos)?;
+        return Ok(CASMatrix::new(to_trees(inverse, line_pos), size));
+    }
+
+    let det = determinant(matrix, line_pos)?;
+    if is_num(&det, 0) {
+        return Err(singular(line_pos));
+    }
+    let rows = (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    let cofactor = determinant(&minor(matrix, col, row), line_pos)?;
+                    let cofactor = match (row + col) % 2 {
+                        0 => cofactor,
+                        _ => expand(&build::neg(cofactor)),
+                    };
+                    Ok(Tree {
+                        root: quotient(&cofactor, &det),
+                    })
+                })
+                .collect::<Result<Vec<_>, CASError>>()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(CASMatrix::new(rows, size))
+}
+
+/// Number of linearly independent rows of a matrix.
+///
+/// Symbolic entries are treated as independent of each other, so this is the rank for all but a few values of the variables, like `rank([[x, 1], [1, 1]])` which is 2 even though it's 1 when `x = 1`.
+pub(crate) fn rank(matrix: &CASMatrix, line_pos: usize) -> usize {
+    match numeric(matrix) {
+        Some(mut rows) => eliminate(&mut rows, matrix.num_cols).0.len(),
+        None => bareiss(&mut symbolic(matrix), line_pos).0.len(),
+    }
+}
+
+/// The vector `x` with `A * x = b` for a square matrix `A`, or a [CASErrorKind::SingularMatrix] error if there isn't exactly one.
+///
+/// Symbolic systems are solved with Cramer's rule, so each element is the determinant of `A` with a column replaced by `b`, over the determinant of `A`.
+pub(crate) fn solve(matrix: &CASMatrix, rhs: &CASVec, line_pos: usize) -> Result<Node, CASError> {
+    matrix.check_square(line_pos)?;
+    rhs.check_dim(matrix.num_rows(), line_pos)?;
+
+    let column = CASMatrix::column(rhs.clone());
+    if let (Some(lhs), Some(rhs)) = (numeric(matrix), numeric(&column)) {
+        let solution = solve_numeric(lhs, rhs, line_pos)?;
+        let elements = to_trees(solution, line_pos)
+            .into_iter()
+            .map(|mut row| row.remove(0))
+            .collect();
+        return Ok(CASVec::new(elements).into_node(line_pos));
+    }
+
+    let det = determinant(matrix, line_pos)?;
+    if is_num(&det, 0) {
+        return Err(singular(line_pos));
+    }
+    let elements = (0..matrix.num_cols)
+        .map(|col| {
+            let mut replaced = matrix.clone();
+            for (row, value) in replaced.rows.iter_mut().zip(&rhs.elements) {
+                row[col] = value.clone();
+            }
+            Ok(Tree {
+                root: quotient(&determinant(&replaced, line_pos)?, &det),
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(elements).into_node(line_pos))
+}
+
+/// Product of two matrices, where `lhs` must have as many columns as `rhs` has rows.
+fn multiply(lhs: &CASMatrix, rhs: &CASMatrix, line_pos: usize) -> Result<CASMatrix, CASError> {
+    if rhs.num_rows() != lhs.num_cols {
+        return Err(CASError {
+            line_pos,
+            kind: CASErrorKind::DimensionMismatch {
+                expected: lhs.num_cols,
+                found: rhs.num_rows(),
+            },
+        });
+    }
+
+    let rows = lhs
+        .rows
+        .iter()
+        .map(|row| {
+            (0..rhs.num_cols)
+                .map(|col| {
+                    let mut sum = num(0, line_pos);
+                    for (entry, rhs_row) in row.iter().zip(&rhs.rows) {
+                        let args = vec![entry.root.clone(), rhs_row[col].root.clone()];
+                        let term = elementwise(Operator::Mult, args, line_pos)?;
+                        sum = elementwise(Operator::Add, vec![sum, term], line_pos)?;
+                    }
+                    Ok(Tree { root: sum })
+                })
+                .collect::<Result<Vec<_>, CASError>>()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(CASMatrix::new(rows, rhs.num_cols))
+}
+
+/// The matrix without row `skip_row` and column `skip_col`.
+fn minor(matrix: &CASMatrix, skip_row: usize, skip_col: usize) -> CASMatrix {
+    let rows = matrix
+        .rows
+        .iter()
+        .enumerate()
+        .filter(|(row, _)| *row != skip_row)
+        .map(|(_, entries)| {
+            entries
+                .iter()
+                .enumerate()
+                .filter(|(col, _)| *col != skip_col)
+                .map(|(_, entry)| entry.clone())
+                .collect()
+        })
+        .collect();
+    CASMatrix::new(rows, matrix.num_cols - 1)
+}
+
+/// Error for a matrix with no inverse.
+fn singular(line_pos: usize) -> CASError {
+    CASError {
+        line_pos,
+        kind: CASErrorKind::SingularMatrix,
+    }
+}
+
+/// The value of every entry of a matrix, or None if any of them isn't a number once it's simplified.
+fn numeric(matrix: &CASMatrix) -> Option<Vec<Vec<Number>>> {
+    matrix
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|entry| num_value(&simplify(&entry.root)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Numeric literals for the entries of a matrix of numbers.
+fn to_trees(rows: Vec<Vec<Number>>, line_pos: usize) -> Vec<Vec<Tree<Symbol>>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|value| Tree {
+                    root: num(written(value), line_pos),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Rounds an inexact number to the precision numeric elimination works to, leaving exact numbers exact.
+fn rounded(value: Number) -> Number {
+    match value {
+        Number::Inexact(_) => Number::Inexact(value.round(context_for_digits(NUMERIC_DIGITS))),
+        exact => exact,
+    }
+}
+
+/// Rounds an inexact result to the significant digits `calc` gives by default, so it's written with no more digits than that.
+fn written(value: Number) -> Number {
+    match value {
+        Number::Inexact(value) => {
+            Number::Inexact(value.round_decimal(NUMERIC_DIGITS, RoundingMode::HalfEven))
+        }
+        exact => exact,
+    }
+}
+
+/// Reduces the first `num_cols` columns of a matrix of numbers to row echelon form by Gaussian elimination, returning the column of each pivot and whether the rows were swapped an odd number of times.
+///
+/// The pivot in each column is the entry with the largest absolute value, which keeps rounding errors small when some of the entries are inexact.
+fn eliminate(rows: &mut [Vec<Number>], num_cols: usize) -> (Vec<usize>, bool) {
+    let zero = Number::from(0);
+    let abs = |value: &Number| match *value < zero {
+        true => -value.clone(),
+        false => value.clone(),
+    };
+
+    let mut pivots = vec![];
+    let mut swapped = false;
+    for col in 0..num_cols {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len())
+            .filter(|idx| rows[*idx][col] != zero)
+            .max_by(|lhs, rhs| {
+                abs(&rows[*lhs][col])
+                    .partial_cmp(&abs(&rows[*rhs][col]))
+                    .unwrap_or(Ordering::Equal)
+            })
+        else {
+            continue;
+        };
+        if pivot != row {
+            rows.swap(pivot, row);
+            swapped = !swapped;
+        }
+
+        let recip = rounded(rows[row][col].recip().unwrap());
+        for idx in row + 1..rows.len() {
+            let factor = rounded(rows[idx][col].clone() * recip.clone());
+            for entry in col + 1..rows[idx].len() {
+                let product = rounded(factor.clone() * rows[row][entry].clone());
+                rows[idx][entry] = rounded(rows[idx][entry].clone() - product);
+            }
+            rows[idx][col] = zero.clone();
+            //set rather than subtracted, so inexact entries don't leave a tiny remainder below the pivot
+        }
+        pivots.push(col);
+    }
+    (pivots, swapped)
+}
+
+/// Turns a matrix of numbers in row echelon form into reduced row echelon form, where every pivot is 1 and has only zeros above it.
+fn back_substitute(rows: &mut [Vec<Number>], pivots: &[usize]) {
+    for (row, col) in pivots.iter().copied().enumerate().rev() {
+        let recip = rounded(rows[row][col].recip().unwrap());
+        for entry in col..rows[row].len() {
+            rows[row][entry] = rounded(rows[row][entry].clone() * recip.clone());
+        }
+        for idx in 0..row {
+            let factor = rows[idx][col].clone();
+            for entry in col..rows[idx].len() {
+                let product = rounded(factor.clone() * rows[row][entry].clone());
+                rows[idx][entry] = rounded(rows[idx][entry].clone() - product);
+            }
+        }
+    }
+}
+
+/// Solves `A * X = B` for a square matrix of numbers `A` by Gauss-Jordan elimination, where `B` has a column for each system to solve.
+fn solve_numeric(
+    lhs: Vec<Vec<Number>>,
+    rhs: Vec<Vec<Number>>,
+    line_pos: usize,
+) -> Result<Vec<Vec<Number>>, CASError> {
+    let size = lhs.len();
+    let mut rows: Vec<Vec<Number>> = lhs
+        .into_iter()
+        .zip(rhs)
+        .map(|(mut row, rhs)| {
+            row.extend(rhs);
+            row
+        })
+        .collect();
+
+    let (pivots, _) = eliminate(&mut rows, size);
+    if pivots.len() < size {
+        return Err(singular(line_pos));
+    }
+    back_substitute(&mut rows, &pivots);
+    Ok(rows.into_iter().map(|row| row[size..].to_vec()).collect())
+}
+
+/// The entries of a matrix multiplied out, so every entry equal to zero is the number 0.
+fn symbolic(matrix: &CASMatrix) -> Vec<Vec<Node>> {
+    matrix
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|entry| expand(&entry.root)).collect())
+        .collect()
+}
+
+/// Reduces a matrix of expanded expressions to row echelon form with Bareiss' fraction-free elimination, returning the column of each pivot and whether the rows were swapped an odd number of times.
+///
+/// Each step divides by the previous pivot, which always leaves a polynomial in the original entries, so no fractions build up. With every row used, the last pivot is the determinant.
+fn bareiss(rows: &mut [Vec<Node>], line_pos: usize) -> (Vec<usize>, bool) {
+    let num_cols = rows.first().map_or(0, Vec::len);
+    let mut previous = num(1, line_pos);
+
+    let mut pivots = vec![];
+    let mut swapped = false;
+    for col in 0..num_cols {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len()).find(|idx| !is_num(&rows[*idx][col], 0)) else {
+            continue;
+        };
+        if pivot != row {
+            rows.swap(pivot, row);
+            swapped = !swapped;
+        }
+
+        for idx in row + 1..rows.len() {
+            for entry in col + 1..num_cols {
+                // a_ij -> (a_kk * a_ij - a_ik * a_kj) / p
+                let numerator = build::sub(
+                    build::mult(rows[row][col].clone(), rows[idx][entry].clone()),
+                    build::mult(rows[idx][col].clone(), rows[row][entry].clone()),
+                );
+                rows[idx][entry] = quotient(&expand(&numerator), &previous);
+            }
+            rows[idx][col] = num(0, line_pos);
+        }
+        previous = rows[row][col].clone();
+        pivots.push(col);
+    }
+    (pivots, swapped)
+}
+
+/// Divides an expanded expression by another.
+///
+/// Divisions in Bareiss' elimination are exact, so when both are polynomials the quotient is found by polynomial division, see [divide_polynomials]. Otherwise a divisor that's a single term, like `2 * x`, is cancelled from each term of the numerator, and a divisor that's a sum is left as the divisor of a quotient.
+fn quotient(numerator: &Node, divisor: &Node) -> Node {
+    if let Some(quotient) = divide_polynomials(numerator, divisor) {
+        return quotient;
+    }
+    if build::is_operator(divisor, Operator::Add) {
+        return simplify(&build::div(numerator.clone(), divisor.clone()));
+    }
+    let recip = build::pow(divisor.clone(), num(-1, build::line_pos(divisor)));
+    expand(&build::mult(numerator.clone(), recip))
+}
+
+/// A term of a polynomial, with the power of each factor that isn't a number, in the order they're found in.
+type Monomial = (Number, Vec<i64>);
+
+/// Divides one expanded polynomial by another, or returns None if the division leaves a remainder.
+///
+/// Anything that isn't a sum, product, number or positive integer power, like `sin(x)` or `x ^ -1`, is treated as a variable of its own. Terms are ordered lexicographically by their powers, and the leading term of the divisor is taken out of the leading term of what's left of the numerator until nothing is left.
+fn divide_polynomials(numerator: &Node, divisor: &Node) -> Option<Node> {
+    let mut atoms = vec![];
+    let mut remainder = polynomial(numerator, &mut atoms);
+    let divisor = polynomial(divisor, &mut atoms);
+    let pad = |terms: &mut Vec<Monomial>| {
+        for (_, powers) in terms {
+            powers.resize(atoms.len(), 0);
+        }
+    };
+    pad(&mut remainder);
+    let mut divisor = divisor;
+    pad(&mut divisor);
+
+    let (lead_coefficient, lead_powers) = divisor.iter().max_by(|lhs, rhs| lhs.1.cmp(&rhs.1))?;
+    let lead_recip = lead_coefficient.recip()?;
+    let mut quotient: Vec<Monomial> = vec![];
+    while let Some((coefficient, powers)) = remainder.iter().max_by(|lhs, rhs| lhs.1.cmp(&rhs.1)) {
+        let powers: Vec<i64> = powers
+            .iter()
+            .zip(lead_powers)
+            .map(|(lhs, rhs)| lhs - rhs)
+            .collect();
+        if powers.iter().any(|power| *power < 0) {
+            return None;
+        }
+        let coefficient = coefficient.clone() * lead_recip.clone();
+
+        let lead = remainder.iter().position(|(_, other)| {
+            other
+                .iter()
+                .zip(lead_powers)
+                .map(|(lhs, rhs)| lhs - rhs)
+                .eq(powers.iter().copied())
+        });
+        remainder.swap_remove(lead.unwrap());
+        //removed rather than subtracted, so inexact coefficients can't leave a tiny remainder behind
+        for (other_coefficient, other_powers) in &divisor {
+            if other_powers == lead_powers {
+                continue;
+            }
+            let product = (
+                -(coefficient.clone() * other_coefficient.clone()),
+                powers
+                    .iter()
+                    .zip(other_powers)
+                    .map(|(lhs, rhs)| lhs + rhs)
+                    .collect(),
+            );
+            add_monomial(&mut remainder, product);
+        }
+        quotient.push((coefficient, powers));
+    }
+
+    let line_pos = build::line_pos(numerator);
+    let terms = quotient
+        .into_iter()
+        .map(|(coefficient, powers)| {
+            atoms
+                .iter()
+                .zip(powers)
+                .filter(|(_, power)| *power != 0)
+                .fold(num(coefficient, line_pos), |term, (atom, power)| {
+                    build::mult(term, build::pow(atom.clone(), num(power, line_pos)))
+                })
+        })
+        .collect();
+    Some(simplify(&node(
+        SymbolType::Operator(Operator::Add),
+        line_pos,
+        terms,
+    )))
+}
+
+/// Reads an expanded expression as a polynomial, adding any new variables to `atoms`.
+fn polynomial(tree_node: &Node, atoms: &mut Vec<Node>) -> Vec<Monomial> {
+    let terms = match build::is_operator(tree_node, Operator::Add) {
+        true => build::children(tree_node),
+        false => vec![tree_node.clone()],
+    };
+
+    let mut monomials = vec![];
+    for term in terms {
+        let factors = match build::is_operator(&term, Operator::Mult) {
+            true => build::children(&term),
+            false => vec![term],
+        };
+        let mut coefficient = Number::from(1);
+        let mut powers = vec![0; atoms.len()];
+        for factor in factors {
+            if let Some(value) = num_value(&factor) {
+                coefficient *= &value;
+                continue;
+            }
+            let (atom, power) = match build::is_operator(&factor, Operator::Exp) {
+                true => {
+                    let args = build::children(&factor);
+                    match integer_value(&args[1]) {
+                        Some(power) if power > 0 => (args[0].clone(), power),
+                        _ => (factor, 1),
+                    }
+                }
+                false => (factor, 1),
+            };
+            let idx = match atoms.iter().position(|other| *other == atom) {
+                Some(idx) => idx,
+                None => {
+                    atoms.push(atom);
+                    powers.push(0);
+                    atoms.len() - 1
+                }
+            };
+            powers[idx] += power;
+        }
+        add_monomial(&mut monomials, (coefficient, powers));
+    }
+    monomials
+}
+
+/// Adds a term to a polynomial, combining it with a like term if there is one.
+fn add_monomial(terms: &mut Vec<Monomial>, (coefficient, powers): Monomial) {
+    let zero = Number::from(0);
+    let like = |(_, other): &Monomial| {
+        let len = other.len().max(powers.len());
+        (0..len).all(|idx| other.get(idx).unwrap_or(&0) == powers.get(idx).unwrap_or(&0))
+    };
+    match terms.iter().position(like) {
+        Some(idx) => {
+            terms[idx].0 += &coefficient;
+            if terms[idx].0 == zero {
+                terms.swap_remove(idx);
+            }
+        }
+        None if coefficient != zero => terms.push((coefficient, powers)),
+        None => {}
+    }
+}
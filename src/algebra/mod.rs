@@ -1,5 +1,369 @@
+/// Helpers for building expression trees.
+mod build;
+/// Symbolic differentiation.
 mod derivative;
+/// Symbolic integration.
+mod integral;
+/// Limits of expressions as a variable goes to ∞ or -∞.
+mod limit;
+/// Matrix products, determinants, inverses and linear systems.
+mod matrix;
+/// Numeric values of expressions.
+mod numeric;
+/// Numeric integration.
+mod quadrature;
 ///Contains functionality related to expression manipulation: simplification, equation solving, derivatives and antiderivates, etc.
 mod simplify;
+/// Rewriting expressions with rules written as patterns.
+pub(crate) mod term_rewriter;
+/// Tests for expression manipulation.
+mod test;
+/// Arithmetic on vectors of expressions.
+mod vector;
+/// Gradients, divergences, curls and Jacobians.
+mod vector_calculus;
 
-mod term_rewriter;
+use std::collections::HashMap;
+
+use build::{node, num, Node};
+use derivative::Differentiator;
+
+use crate::{
+    parser::trees::Tree,
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        cas_matrix::CASMatrix,
+        cas_num::{CASComplex, CASInterval, CASNum, CASRational, Number, RoundingMode, ZERO},
+        cas_vec::CASVec,
+        symbol::{
+            function::{Func, ResFun},
+            operator::Operator,
+            Symbol, SymbolType,
+        },
+    },
+};
+
+/// Significant digits that numeric results, like integrals with no antiderivative, are found to, unless a number of digits is given like `calc(x, 50)`.
+const NUMERIC_DIGITS: usize = 30;
+
+/// Replaces every call to a reserved function that transforms expressions, like `der`, with its result.
+///
+/// Calls are replaced innermost first, so `der(der(f, x), x)` finds the second derivative of `f`.
+pub(crate) fn eval_commands(tree: &Tree<Symbol>) -> Result<Tree<Symbol>, CASError> {
+    Ok(Tree {
+        root: eval_node(&tree.root)?,
+    })
+}
+
+/// Replaces reserved function calls in a subtree, see [eval_commands].
+fn eval_node(tree_node: &Node) -> Result<Node, CASError> {
+    let data = tree_node.0.borrow().data.clone();
+    let children = tree_node
+        .0
+        .borrow()
+        .children
+        .iter()
+        .map(eval_node)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match data.symbol_type {
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Der, ..
+        }) => {
+            let mut differentiator = Differentiator::default();
+            //shared by every step so each subexpression is only differentiated once per variable
+            let mut result = children[0].clone();
+            let steps = derivative_steps(&children[1..])?
+                .into_iter()
+                .flat_map(|(wrt, order)| std::iter::repeat_n(wrt, order as usize));
+            for (idx, wrt) in steps.enumerate() {
+                if idx > 0 {
+                    //without collecting like terms in between, every order would multiply the size of the result
+                    result = simplify::expand(&result);
+                }
+                result = differentiator.derivative(&result, &wrt)?;
+            }
+            Ok(result)
+        }
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::SymInt,
+            ..
+        }) => {
+            let wrt = variable_name(&children[1])?;
+            Ok(integral::integral(&children[0], &wrt)
+                .unwrap_or_else(|| node(data.symbol_type, data.line_pos, children)))
+            //integrals with no elementary antiderivative we can find are left as they are
+        }
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::DefInt,
+            ..
+        }) => {
+            let wrt = variable_name(&children[1])?;
+            let integral = integral::definite_integral(
+                &children[0],
+                &wrt,
+                &children[2],
+                &children[3],
+                NUMERIC_DIGITS,
+            )?;
+            match (integral.error, build::num_value(&integral.value)) {
+                //numeric approximations are given as the interval their error estimate puts the integral in
+                (Some(error), Some(Number::Inexact(value))) => Ok(build::interval(
+                    CASInterval::new(value.clone() - error.clone(), value + error)
+                        .round_decimal(NUMERIC_DIGITS),
+                    data.line_pos,
+                )),
+                _ => Ok(integral.value),
+            }
+        }
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Calc, ..
+        }) => {
+            let digits = match children.get(1) {
+                Some(digits) => integer_argument(digits, ResFun::Calc, 1)? as usize,
+                None => NUMERIC_DIGITS,
+            };
+            calc(&children[0], digits, data.line_pos)
+        }
+        //calls with arguments that aren't numbers, like fact(n), are left as they are
+        SymbolType::Function(Func::ResFun {
+            fun:
+                fun @ (ResFun::Gcd
+                | ResFun::Lcm
+                | ResFun::Mod
+                | ResFun::Fact
+                | ResFun::Binom
+                | ResFun::IsPrime
+                | ResFun::Factor),
+            ..
+        }) => Ok(integer_function(fun, &children, data.line_pos)?
+            .unwrap_or_else(|| node(data.symbol_type, data.line_pos, children))),
+        SymbolType::Function(Func::ResFun {
+            fun: fun @ (ResFun::Dot | ResFun::Cross | ResFun::Norm),
+            ..
+        }) => {
+            let vectors = children
+                .iter()
+                .map(|child| CASVec::from_node(child).ok_or_else(|| invalid_argument(child, fun)))
+                .collect::<Result<Vec<_>, _>>()?;
+            match fun {
+                ResFun::Dot => vector::dot(&vectors[0], &vectors[1], data.line_pos),
+                ResFun::Cross => vector::cross(&vectors[0], &vectors[1], data.line_pos),
+                _ => vector::norm(&vectors[0], data.line_pos),
+            }
+        }
+        SymbolType::Function(Func::ResFun {
+            fun: fun @ (ResFun::Grad | ResFun::Div | ResFun::Curl | ResFun::Jacob),
+            ..
+        }) => {
+            let vars = CASVec::from_node(&children[1])
+                .ok_or_else(|| invalid_argument(&children[1], fun))?
+                .elements
+                .iter()
+                .map(|var| variable_name(&var.root))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut differentiator = Differentiator::default();
+            //shared by every partial derivative, since they often differentiate the same subexpressions
+            let field = CASVec::from_node(&children[0]);
+            match (fun, field) {
+                (ResFun::Grad, None) => vector_calculus::gradient(
+                    &mut differentiator,
+                    &children[0],
+                    &vars,
+                    data.line_pos,
+                ),
+                (ResFun::Div, Some(field)) => {
+                    vector_calculus::divergence(&mut differentiator, &field, &vars, data.line_pos)
+                }
+                (ResFun::Curl, Some(field)) => {
+                    vector_calculus::curl(&mut differentiator, &field, &vars, data.line_pos)
+                }
+                (ResFun::Jacob, Some(field)) => {
+                    vector_calculus::jacobian(&mut differentiator, &field, &vars, data.line_pos)
+                }
+                //the gradient is of a scalar field, and the others are of vector fields
+                _ => Err(invalid_argument(&children[0], fun)),
+            }
+        }
+        SymbolType::Function(Func::ResFun {
+            fun:
+                fun @ (ResFun::Transpose | ResFun::Det | ResFun::Inv | ResFun::Rank | ResFun::Solve),
+            ..
+        }) => {
+            let matrix = CASMatrix::from_node(&children[0], data.line_pos)?
+                .ok_or_else(|| invalid_argument(&children[0], fun))?;
+            match fun {
+                ResFun::Transpose => Ok(matrix.transpose().into_node(data.line_pos)),
+                ResFun::Det => matrix::determinant(&matrix, data.line_pos),
+                ResFun::Inv => {
+                    Ok(matrix::inverse(&matrix, data.line_pos)?.into_node(data.line_pos))
+                }
+                ResFun::Rank => Ok(num(
+                    matrix::rank(&matrix, data.line_pos) as u64,
+                    data.line_pos,
+                )),
+                _ => {
+                    let rhs = CASVec::from_node(&children[1])
+                        .ok_or_else(|| invalid_argument(&children[1], fun))?;
+                    matrix::solve(&matrix, &rhs, data.line_pos)
+                }
+            }
+        }
+        SymbolType::Operator(op) => Ok(vector::operate(op, &children, data.line_pos)?
+            .unwrap_or_else(|| node(data.symbol_type, data.line_pos, children))),
+        _ => Ok(node(data.symbol_type, data.line_pos, children)),
+    }
+}
+
+/// Finds the numeric value of an expression for `calc` to `digits` significant digits, with bounds on it if it has intervals in it, or the value of each element if it's a vector.
+///
+/// Values are rounded to the nearest number with `digits` significant decimal digits, so they're written with no more digits than were asked for. The ends of intervals are rounded outward instead, so they still hold the exact value.
+fn calc(tree_node: &Node, digits: usize, line_pos: usize) -> Result<Node, CASError> {
+    if let Some(vector) = CASVec::from_node(tree_node) {
+        let elements = vector
+            .elements
+            .iter()
+            .map(|element| {
+                Ok(Tree {
+                    root: calc(&element.root, digits, line_pos)?,
+                })
+            })
+            .collect::<Result<_, CASError>>()?;
+        return Ok(CASVec::new(elements).into_node(build::line_pos(tree_node)));
+    }
+    let context = numeric::context_for_digits(digits);
+    if build::contains_call(tree_node, ResFun::Interval) {
+        let value = numeric::evaluate_interval(tree_node, &HashMap::new(), context)?;
+        return Ok(build::interval(value.round_decimal(digits), line_pos));
+    }
+    let mut value = numeric::evaluate_complex(tree_node, &HashMap::new(), context)?;
+    if value.is_finite() {
+        //a part below the precision of the other one is rounding error, like the imaginary part of e ^ (i * π)
+        let precision = CASNum::from(10).pow(&CASNum::from(digits as u64), context.num_limbs);
+        let (re, im) = (value.re.abs(), value.im.abs());
+        if im.clone() * precision.clone() < re {
+            value.im = ZERO;
+        } else if re * precision < im {
+            value.re = ZERO;
+        }
+    }
+    let rounded = |part: &CASNum| part.round_decimal(digits, RoundingMode::HalfEven);
+    Ok(build::complex(
+        CASComplex::new(rounded(&value.re), rounded(&value.im)),
+        line_pos,
+    ))
+    //variables defined in the variable table were already replaced by their values
+}
+
+/// Finds the exact value of an integer function like `gcd` or `fact`, or returns None if any argument isn't a number once it's simplified.
+///
+/// `factor` gives a product of powers of primes, like `2 ^ 3 * 3 ^ 2 * 5` for 360. Arguments that are numbers but not integers, or integers the function doesn't take like `fact(-1)`, are an error.
+fn integer_function(fun: ResFun, args: &[Node], line_pos: usize) -> Result<Option<Node>, CASError> {
+    let mut values = vec![];
+    for arg in args {
+        let arg = simplify::simplify(arg);
+        let Some(value) = build::num_value(&arg) else {
+            return Ok(None);
+        };
+        match value.exact().filter(CASRational::is_integer) {
+            //integers are converted exactly whatever the number of limbs
+            Some(value) => values.push(value.to_cas_num(1)),
+            None => return Err(invalid_argument(&arg, fun)),
+        }
+    }
+    let invalid = || CASError {
+        line_pos,
+        kind: CASErrorKind::InvalidArgument {
+            arg: values
+                .iter()
+                .map(CASNum::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            func_name: fun.to_string(),
+        },
+    };
+
+    let exact = |value: &CASNum| num(CASRational::try_from(value).unwrap(), line_pos);
+    if fun == ResFun::Factor {
+        let x = &values[0];
+        let mut factors: Vec<Node> = x
+            .factor()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|(prime, count)| build::pow(exact(prime), num(*count, line_pos)))
+            .collect();
+        if x < &CASNum::from(0) {
+            factors.insert(0, num(-1, line_pos));
+        }
+        return Ok(Some(match factors.len() {
+            0 => num(1, line_pos),
+            1 => factors.pop().unwrap(),
+            _ => node(SymbolType::Operator(Operator::Mult), line_pos, factors),
+        }));
+    }
+    //integer functions are exact, so the precision isn't used
+    let value = numeric::function_value(fun, &values, 1).ok_or_else(invalid)?;
+    Ok(Some(exact(&value)))
+}
+
+/// Reads the arguments of `der` after the expression, which are variables each optionally followed by how many times to differentiate with respect to it.
+///
+/// `der(f, x, 2, y)` gives `[("x", 2), ("y", 1)]`.
+fn derivative_steps(args: &[Node]) -> Result<Vec<(String, u64)>, CASError> {
+    let is_order = |idx: usize| {
+        args.get(idx).is_some_and(|arg| {
+            !matches!(arg.0.borrow().data.symbol_type, SymbolType::Variable { .. })
+        })
+    };
+
+    let mut steps: Vec<(String, u64)> = vec![];
+    let mut idx = 0;
+    while idx < args.len() {
+        let wrt = variable_name(&args[idx])?;
+        if is_order(idx + 1) {
+            steps.push((wrt, integer_argument(&args[idx + 1], ResFun::Der, 0)?));
+            idx += 2;
+        } else {
+            steps.push((wrt, 1));
+            idx += 1;
+        }
+    }
+    Ok(steps)
+}
+
+/// Returns the value of an argument to `fun` that must be an integer of at least `min`, like the order of a derivative.
+fn integer_argument(tree_node: &Node, fun: ResFun, min: u64) -> Result<u64, CASError> {
+    build::num_value(tree_node)
+        .and_then(|value| i64::try_from(&value).ok())
+        .and_then(|value| u64::try_from(value).ok())
+        .filter(|value| *value >= min)
+        .ok_or_else(|| invalid_argument(tree_node, fun))
+}
+
+/// Error for an argument `fun` doesn't take.
+fn invalid_argument(tree_node: &Node, fun: ResFun) -> CASError {
+    let data = &tree_node.0.borrow().data;
+    CASError {
+        line_pos: data.line_pos,
+        kind: CASErrorKind::InvalidArgument {
+            arg: data.symbol_type.to_string(),
+            func_name: fun.to_string(),
+        },
+    }
+}
+
+/// Returns the name of a variable, or an error if the node is anything other than a single variable.
+fn variable_name(tree_node: &Node) -> Result<String, CASError> {
+    let data = &tree_node.0.borrow().data;
+    match &data.symbol_type {
+        SymbolType::Variable { name } if tree_node.0.borrow().children.is_empty() => {
+            Ok(name.to_string())
+        }
+        symbol_type => Err(CASError {
+            line_pos: data.line_pos,
+            kind: CASErrorKind::ExpectedVariable {
+                found: symbol_type.to_string(),
+            },
+        }),
+    }
+}
@@ -0,0 +1,430 @@
+///Contains functionality for finding the numeric values of mathematical expressions. i.e.
+/// ```text
+/// 2 * 3 + 1 => 7
+/// x ^ 2 => 9 when x = 3,
+/// etc.
+/// ```
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::types::{
+    cas_error::{CASError, CASErrorKind},
+    cas_num::{
+        CASComplex, CASInterval, CASNum, Context, RoundingMode, INDETERMINATE, INFINITY,
+        NEG_INFINITY, ZERO,
+    },
+    symbol::{
+        constant::{Const, ResConst},
+        function::{Func, ResFun},
+        operator::Operator,
+        SymbolType,
+    },
+};
+
+use super::build::*;
+
+/// Number of limbs of a [CASNum] needed to hold `digits` significant decimal digits, plus a guard limb.
+pub(crate) fn limbs_for_digits(digits: usize) -> usize {
+    //each 64 bit limb holds a little over 19 decimal digits
+    digits * 1000 / 19266 + 2
+}
+
+/// Context for evaluating to `digits` significant decimal digits, rounding to the nearest like floating point arithmetic.
+pub(crate) fn context_for_digits(digits: usize) -> Context {
+    Context::new(limbs_for_digits(digits), RoundingMode::HalfEven)
+}
+
+/// Finds the real value of a subtree, taking the value of each variable from `values`.
+///
+/// Values that aren't real, like `sqrt(-1)`, are indeterminate, see [evaluate_complex].
+pub(crate) fn evaluate(
+    tree_node: &Node,
+    values: &HashMap<String, CASNum>,
+    context: Context,
+) -> Result<CASNum, CASError> {
+    Ok(evaluate_complex(tree_node, values, context)?
+        .to_real()
+        .unwrap_or(INDETERMINATE))
+}
+
+/// Finds the complex value of a subtree, taking the value of each variable from `values`.
+///
+/// Every intermediate result is rounded to the precision of `context`. Infinite and indeterminate values are carried through like they are in [CASNum] arithmetic, and comparisons of real numbers give 1 if they hold and 0 if they don't.
+///
+/// Operators and functions of real numbers give their real value where they have one, so `cbrt(-8)` is -2 and `∞ * 2` is ∞. Anywhere else, like `sqrt(-1)` or `ln(i)`, they give their complex value on the principal branch.
+pub(crate) fn evaluate_complex(
+    tree_node: &Node,
+    values: &HashMap<String, CASNum>,
+    context: Context,
+) -> Result<CASComplex, CASError> {
+    let num_limbs = context.num_limbs;
+    let data = tree_node.0.borrow().data.clone();
+    let args = tree_node
+        .0
+        .borrow()
+        .children
+        .iter()
+        .map(|child| evaluate_complex(child, values, context))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let cannot_evaluate = || CASError {
+        line_pos: data.line_pos,
+        kind: CASErrorKind::CannotEvaluate {
+            symbol: data.symbol_type.to_string(),
+        },
+    };
+
+    let value = match &data.symbol_type {
+        SymbolType::Num { value } => CASComplex::from(value.round(context)),
+        SymbolType::Variable { name } => {
+            CASComplex::from(values.get(name).cloned().ok_or_else(cannot_evaluate)?)
+        }
+        SymbolType::Const(Const::ResConst(ResConst::Inf)) => CASComplex::from(INFINITY),
+        SymbolType::Const(Const::ResConst(ResConst::NegInf)) => CASComplex::from(NEG_INFINITY),
+        SymbolType::Const(Const::ResConst(ResConst::Pi)) => CASComplex::from(CASNum::pi(num_limbs)),
+        SymbolType::Const(Const::ResConst(ResConst::Tau)) => {
+            CASComplex::from(CASNum::tau(num_limbs))
+        }
+        SymbolType::Const(Const::ResConst(ResConst::E)) => CASComplex::from(CASNum::e(num_limbs)),
+        SymbolType::Const(Const::ResConst(ResConst::Phi)) => {
+            CASComplex::from(CASNum::phi(num_limbs))
+        }
+        SymbolType::Const(Const::ResConst(ResConst::I)) => CASComplex::i(),
+        symbol_type => {
+            let real_args = args
+                .iter()
+                .map(CASComplex::to_real)
+                .collect::<Option<Vec<_>>>();
+            let real = real_args.as_ref().and_then(|real_args| {
+                real_value(symbol_type, real_args, context)
+                    .map(CASComplex::from)
+                    //indeterminate results of determinate real arguments might have a complex value
+                    .filter(|value| {
+                        !value.is_indeterminate() || args.iter().any(CASComplex::is_indeterminate)
+                    })
+            });
+            match real {
+                Some(value) => value,
+                None => complex_value(symbol_type, &args, context).ok_or_else(cannot_evaluate)?,
+            }
+        }
+    };
+    if value.is_indeterminate() {
+        //a number with an indeterminate part is indeterminate as a whole
+        return Ok(CASComplex::from(INDETERMINATE));
+    }
+    Ok(value.round(context))
+}
+
+/// Finds an interval holding the exact value of a subtree, taking the value of each variable from `values`.
+///
+/// The ends of every intermediate result are rounded outward to the precision of `context`, so the exact value is inside the result however much is lost to rounding along the way. `interval(a, b)` is every number between `a` and `b`, so the result holds the value of the subtree for every choice of numbers in its intervals.
+///
+/// Comparisons give 1 if they hold for every number in their arguments, 0 if they hold for none, and the interval from 0 to 1 otherwise. Complex numbers can't be evaluated.
+pub(crate) fn evaluate_interval(
+    tree_node: &Node,
+    values: &HashMap<String, CASNum>,
+    context: Context,
+) -> Result<CASInterval, CASError> {
+    let num_limbs = context.num_limbs;
+    let data = tree_node.0.borrow().data.clone();
+    let args = tree_node
+        .0
+        .borrow()
+        .children
+        .iter()
+        .map(|child| evaluate_interval(child, values, context))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let cannot_evaluate = || CASError {
+        line_pos: data.line_pos,
+        kind: CASErrorKind::CannotEvaluate {
+            symbol: data.symbol_type.to_string(),
+        },
+    };
+
+    Ok(match &data.symbol_type {
+        SymbolType::Num { value } => CASInterval::new(
+            value.round(Context::new(num_limbs, RoundingMode::Floor)),
+            value.round(Context::new(num_limbs, RoundingMode::Ceiling)),
+        ),
+        SymbolType::Variable { name } => {
+            CASInterval::point(values.get(name).cloned().ok_or_else(cannot_evaluate)?)
+        }
+        SymbolType::Const(Const::ResConst(ResConst::Inf)) => CASInterval::point(INFINITY),
+        SymbolType::Const(Const::ResConst(ResConst::NegInf)) => CASInterval::point(NEG_INFINITY),
+        SymbolType::Const(Const::ResConst(ResConst::Pi)) => {
+            CASInterval::enclose(num_limbs, CASNum::pi)
+        }
+        SymbolType::Const(Const::ResConst(ResConst::Tau)) => {
+            CASInterval::enclose(num_limbs, CASNum::tau)
+        }
+        SymbolType::Const(Const::ResConst(ResConst::E)) => {
+            CASInterval::enclose(num_limbs, CASNum::e)
+        }
+        SymbolType::Const(Const::ResConst(ResConst::Phi)) => {
+            CASInterval::enclose(num_limbs, CASNum::phi)
+        }
+        SymbolType::Operator(Operator::Add) => args
+            .iter()
+            .fold(CASInterval::point(ZERO), |sum, arg| sum.add(arg, num_limbs)),
+        SymbolType::Operator(Operator::Mult) => args
+            .iter()
+            .fold(CASInterval::point(CASNum::from(1)), |product, arg| {
+                product.mul(arg, num_limbs)
+            }),
+        SymbolType::Operator(Operator::Sub) => args[0].sub(&args[1], num_limbs),
+        SymbolType::Operator(Operator::Neg) => -args[0].clone(),
+        SymbolType::Operator(Operator::Div) => args[0].div(&args[1], num_limbs),
+        SymbolType::Operator(Operator::Exp) => args[0].pow(&args[1], num_limbs),
+        SymbolType::Operator(
+            op @ (Operator::Less
+            | Operator::Greater
+            | Operator::Equal
+            | Operator::NotEqual
+            | Operator::LessEqual
+            | Operator::GreaterEqual),
+        ) => compare_intervals(*op, &args[0], &args[1]),
+        SymbolType::Function(Func::ResFun { fun, .. }) => {
+            interval_function_value(*fun, &args, num_limbs).ok_or_else(cannot_evaluate)?
+        }
+        _ => return Err(cannot_evaluate()),
+    })
+}
+
+/// The value of an operator or function of real numbers, or None if it doesn't have one.
+fn real_value(symbol_type: &SymbolType, args: &[CASNum], context: Context) -> Option<CASNum> {
+    let num_limbs = context.num_limbs;
+    Some(match symbol_type {
+        SymbolType::Operator(Operator::Add) => args
+            .iter()
+            .fold(ZERO, |sum, arg| sum.add_with(arg, context)),
+        SymbolType::Operator(Operator::Mult) => {
+            args.iter().fold(CASNum::from(1), |product, arg| {
+                product.mul_with(arg, context)
+            })
+        }
+        SymbolType::Operator(Operator::Sub) => args[0].sub_with(&args[1], context),
+        SymbolType::Operator(Operator::Neg) => -args[0].clone(),
+        SymbolType::Operator(Operator::Div) => args[0].div_with(&args[1], context),
+        SymbolType::Operator(Operator::Exp) => args[0].pow(&args[1], num_limbs),
+        SymbolType::Function(Func::ResFun { fun, .. }) => function_value(*fun, args, num_limbs)?,
+        SymbolType::Operator(
+            op @ (Operator::Less
+            | Operator::Greater
+            | Operator::Equal
+            | Operator::NotEqual
+            | Operator::LessEqual
+            | Operator::GreaterEqual),
+        ) => compare(*op, &args[0], &args[1]),
+        _ => return None,
+    })
+}
+
+/// The value of an operator or function of complex numbers, or None if it doesn't have one, like comparisons or integer functions.
+fn complex_value(
+    symbol_type: &SymbolType,
+    args: &[CASComplex],
+    context: Context,
+) -> Option<CASComplex> {
+    let num_limbs = context.num_limbs;
+    Some(match symbol_type {
+        SymbolType::Operator(Operator::Add) => {
+            args.iter().fold(CASComplex::from(ZERO), |sum, arg| {
+                sum.add_with(arg, context)
+            })
+        }
+        SymbolType::Operator(Operator::Mult) => args
+            .iter()
+            .fold(CASComplex::from(CASNum::from(1)), |product, arg| {
+                product.mul_with(arg, context)
+            }),
+        SymbolType::Operator(Operator::Sub) => args[0].sub_with(&args[1], context),
+        SymbolType::Operator(Operator::Neg) => -args[0].clone(),
+        SymbolType::Operator(Operator::Div) => args[0].div_with(&args[1], context),
+        SymbolType::Operator(Operator::Exp) => args[0].pow(&args[1], num_limbs),
+        SymbolType::Function(Func::ResFun { fun, .. }) => {
+            complex_function_value(*fun, args, num_limbs)?
+        }
+        _ => return None,
+    })
+}
+
+/// Evaluates a comparison to 1 if it holds or 0 if it doesn't. Comparisons with an indeterminate value are indeterminate.
+fn compare(op: Operator, lhs: &CASNum, rhs: &CASNum) -> CASNum {
+    let Some(ordering) = lhs.partial_cmp(rhs) else {
+        return INDETERMINATE;
+    };
+    let holds = match op {
+        Operator::Less => ordering == Ordering::Less,
+        Operator::Greater => ordering == Ordering::Greater,
+        Operator::Equal => ordering == Ordering::Equal,
+        Operator::NotEqual => ordering != Ordering::Equal,
+        Operator::LessEqual => ordering != Ordering::Greater,
+        Operator::GreaterEqual => ordering != Ordering::Less,
+        _ => unreachable!("{:?} is not a comparison", op),
+    };
+    CASNum::from(holds as u64)
+}
+
+/// Evaluates a comparison of intervals to 1 if it holds for every pair of numbers in them, 0 if it holds for none, and the interval from 0 to 1 if it's only sometimes true. Comparisons with an indeterminate interval are indeterminate.
+fn compare_intervals(op: Operator, lhs: &CASInterval, rhs: &CASInterval) -> CASInterval {
+    if lhs.is_indeterminate() || rhs.is_indeterminate() {
+        return CASInterval::point(INDETERMINATE);
+    }
+    //each comparison is true everywhere or nowhere if it is for the pairs of ends furthest apart
+    let (always, never) = match op {
+        Operator::Less => (lhs.hi < rhs.lo, lhs.lo >= rhs.hi),
+        Operator::Greater => (lhs.lo > rhs.hi, lhs.hi <= rhs.lo),
+        Operator::LessEqual => (lhs.hi <= rhs.lo, lhs.lo > rhs.hi),
+        Operator::GreaterEqual => (lhs.lo >= rhs.hi, lhs.hi < rhs.lo),
+        Operator::Equal | Operator::NotEqual => {
+            let equal = lhs.lo == lhs.hi && lhs == rhs;
+            let apart = lhs.hi < rhs.lo || rhs.hi < lhs.lo;
+            if op == Operator::Equal {
+                (equal, apart)
+            } else {
+                (apart, equal)
+            }
+        }
+        _ => unreachable!("{:?} is not a comparison", op),
+    };
+    match (always, never) {
+        (true, _) => CASInterval::point(CASNum::from(1)),
+        (_, true) => CASInterval::point(ZERO),
+        _ => CASInterval::new(ZERO, CASNum::from(1)),
+    }
+}
+
+/// Evaluates a reserved function, or returns `None` for functions like `der` that don't have a numeric value and integer functions like `fact` given something other than an integer they take.
+pub(crate) fn function_value(fun: ResFun, args: &[CASNum], num_limbs: usize) -> Option<CASNum> {
+    let x = &args[0];
+    Some(match fun {
+        ResFun::Sqrt => x.sqrt(num_limbs),
+        ResFun::Cbrt => x.cbrt(num_limbs),
+        ResFun::Log2 => x.log2(num_limbs),
+        ResFun::Log10 => x.log10(num_limbs),
+        ResFun::Log => x.log(&args[1], num_limbs),
+        ResFun::Ln => x.ln(num_limbs),
+        ResFun::Sin => x.sin(num_limbs),
+        ResFun::Cos => x.cos(num_limbs),
+        ResFun::Tan => x.tan(num_limbs),
+        ResFun::Csc => x.csc(num_limbs),
+        ResFun::Sec => x.sec(num_limbs),
+        ResFun::Cot => x.cot(num_limbs),
+        ResFun::Asin => x.asin(num_limbs),
+        ResFun::Acos => x.acos(num_limbs),
+        ResFun::Atan => x.atan(num_limbs),
+        ResFun::Acsc => x.acsc(num_limbs),
+        ResFun::Asec => x.asec(num_limbs),
+        ResFun::Acot => x.acot(num_limbs),
+        ResFun::Gcd => x.gcd(&args[1])?,
+        ResFun::Lcm => x.lcm(&args[1])?,
+        ResFun::Mod => match args.get(2) {
+            Some(modulus) => x.mod_pow(&args[1], modulus)?,
+            None => x.modulo(&args[1])?,
+        },
+        ResFun::Fact => x.factorial()?,
+        ResFun::Binom => x.binomial(&args[1])?,
+        ResFun::IsPrime => CASNum::from(x.is_prime()? as u64),
+        ResFun::Calc
+        | ResFun::Der
+        | ResFun::Grad
+        | ResFun::Div
+        | ResFun::Curl
+        | ResFun::Jacob
+        | ResFun::SymInt
+        | ResFun::DefInt
+        | ResFun::Factor
+        | ResFun::Interval
+        | ResFun::Dot
+        | ResFun::Cross
+        | ResFun::Norm
+        | ResFun::Transpose
+        | ResFun::Det
+        | ResFun::Inv
+        | ResFun::Rank
+        | ResFun::Solve => return None,
+    })
+}
+
+/// Evaluates a reserved function of intervals, or returns `None` for functions without a value.
+///
+/// Integer functions like `fact` have exact values, so they're only evaluated when each argument holds just one number.
+fn interval_function_value(
+    fun: ResFun,
+    args: &[CASInterval],
+    num_limbs: usize,
+) -> Option<CASInterval> {
+    let x = &args[0];
+    Some(match fun {
+        ResFun::Sqrt => x.sqrt(num_limbs),
+        ResFun::Cbrt => x.cbrt(num_limbs),
+        ResFun::Log2 => x.log2(num_limbs),
+        ResFun::Log10 => x.log10(num_limbs),
+        ResFun::Log => x.log(&args[1], num_limbs),
+        ResFun::Ln => x.ln(num_limbs),
+        ResFun::Sin => x.sin(num_limbs),
+        ResFun::Cos => x.cos(num_limbs),
+        ResFun::Tan => x.tan(num_limbs),
+        ResFun::Csc => x.csc(num_limbs),
+        ResFun::Sec => x.sec(num_limbs),
+        ResFun::Cot => x.cot(num_limbs),
+        ResFun::Asin => x.asin(num_limbs),
+        ResFun::Acos => x.acos(num_limbs),
+        ResFun::Atan => x.atan(num_limbs),
+        ResFun::Acsc => x.acsc(num_limbs),
+        ResFun::Asec => x.asec(num_limbs),
+        ResFun::Acot => x.acot(num_limbs),
+        ResFun::Interval => {
+            let y = args.get(1).unwrap_or(x);
+            if x.is_indeterminate() || y.is_indeterminate() {
+                CASInterval::point(INDETERMINATE)
+            } else {
+                x.hull(y)
+            }
+        }
+        ResFun::Gcd
+        | ResFun::Lcm
+        | ResFun::Mod
+        | ResFun::Fact
+        | ResFun::Binom
+        | ResFun::IsPrime => {
+            let points = args
+                .iter()
+                .map(|arg| (arg.lo == arg.hi).then(|| arg.lo.clone()))
+                .collect::<Option<Vec<_>>>()?;
+            CASInterval::point(function_value(fun, &points, num_limbs)?)
+        }
+        _ => return None,
+    })
+}
+
+/// Evaluates an elementary function of complex numbers on its principal branch, or returns `None` for other functions.
+fn complex_function_value(
+    fun: ResFun,
+    args: &[CASComplex],
+    num_limbs: usize,
+) -> Option<CASComplex> {
+    let z = &args[0];
+    Some(match fun {
+        ResFun::Sqrt => z.sqrt(num_limbs),
+        ResFun::Cbrt => z.cbrt(num_limbs),
+        ResFun::Log2 => z.log2(num_limbs),
+        ResFun::Log10 => z.log10(num_limbs),
+        ResFun::Log => z.log(&args[1], num_limbs),
+        ResFun::Ln => z.ln(num_limbs),
+        ResFun::Sin => z.sin(num_limbs),
+        ResFun::Cos => z.cos(num_limbs),
+        ResFun::Tan => z.tan(num_limbs),
+        ResFun::Csc => z.csc(num_limbs),
+        ResFun::Sec => z.sec(num_limbs),
+        ResFun::Cot => z.cot(num_limbs),
+        ResFun::Asin => z.asin(num_limbs),
+        ResFun::Acos => z.acos(num_limbs),
+        ResFun::Atan => z.atan(num_limbs),
+        ResFun::Acsc => z.acsc(num_limbs),
+        ResFun::Asec => z.asec(num_limbs),
+        ResFun::Acot => z.acot(num_limbs),
+        _ => return None,
+    })
+}
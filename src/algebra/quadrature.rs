@@ -0,0 +1,208 @@
+///Contains functionality for approximating definite integrals numerically. i.e.
+/// ```text
+/// ∫ 1 / (x^3 + 2) dx from 0 to 1 => 0.4627...
+/// ```
+use std::collections::HashMap;
+use std::f64::consts::{LN_10, PI};
+
+use crate::types::{
+    cas_error::{CASError, CASErrorKind},
+    cas_num::{CASNum, CASValue, ZERO},
+};
+
+use super::build::{line_pos, Node};
+use super::numeric::{context_for_digits, evaluate};
+
+/// Most times the step between sample points is halved before giving up on reaching the requested precision, which caps the number of samples at about 1200 for 30 digits.
+const MAX_LEVELS: i32 = 7;
+
+/// Fewest times the step between sample points is halved, so the integrand is sampled enough to trust the error estimate.
+const MIN_LEVELS: i32 = 3;
+
+/// Most significant digits an integral can be approximated to, which caps the precision the integrand is sampled with.
+const MAX_DIGITS: usize = 1000;
+
+/// A bound of a definite integral.
+pub(crate) enum Bound {
+    /// A finite bound.
+    Finite(CASNum),
+    /// ∞
+    PosInf,
+    /// -∞
+    NegInf,
+}
+
+/// Approximate value of a definite integral.
+pub(crate) struct Quadrature {
+    /// The approximate value.
+    pub(crate) value: CASNum,
+    /// Estimate of the absolute error of `value`.
+    pub(crate) error: CASNum,
+}
+
+/// Approximates the integral of `integrand` with respect to `wrt` from `lower` to `upper`, to `digits` significant digits.
+///
+/// Uses tanh-sinh quadrature, which never samples the integrand at the bounds. Infinite bounds are handled by substituting `x = a + t / (1 - t)` (and similar) to map the interval onto `0 < t < 1`. The step between sample points is halved until two successive approximations agree to the requested precision, and the difference between them is used as the error estimate.
+///
+/// Returns an error if they still don't agree after [MAX_LEVELS] halvings, like for integrals that diverge or oscillate forever, or if more than [MAX_DIGITS] digits are asked for.
+pub(crate) fn quadrature(
+    integrand: &Node,
+    wrt: &str,
+    lower: &Bound,
+    upper: &Bound,
+    digits: usize,
+) -> Result<Quadrature, CASError> {
+    let not_converged = || CASError {
+        line_pos: line_pos(integrand),
+        kind: CASErrorKind::IntegralDidNotConverge { digits },
+    };
+    if digits > MAX_DIGITS {
+        return Err(not_converged());
+    }
+    let context = context_for_digits(digits);
+    let one = || CASNum::from(1);
+    let reciprocal = |value: &CASNum| one().div_with(value, context);
+    let f = |x: CASNum| evaluate(integrand, &HashMap::from([(wrt.to_string(), x)]), context);
+
+    //each substitution takes t and 1 - t, which is found separately so it keeps its precision next to 1
+    let integrate = |substitution: &dyn Fn(&CASNum, &CASNum) -> (CASNum, CASNum)| {
+        tanh_sinh(
+            &|t: &CASNum, rest: &CASNum| {
+                let (x, dx_dt) = substitution(t, rest);
+                Ok(f(x)?.mul_with(&dx_dt, context))
+            },
+            digits,
+        )?
+        .ok_or_else(not_converged)
+    };
+
+    match (lower, upper) {
+        (Bound::Finite(a), Bound::Finite(b)) => {
+            // x = a + (b - a) * t
+            let width = b.sub_with(a, context);
+            integrate(&|t, rest| {
+                //measured from the nearer bound, so points next to either one stay distinct
+                let x = match t <= rest {
+                    true => a.add_with(&width.mul_with(t, context), context),
+                    false => b.sub_with(&width.mul_with(rest, context), context),
+                };
+                (x, width.clone())
+            })
+        }
+        (Bound::Finite(a), Bound::PosInf) => {
+            // x = a + t / (1 - t)
+            integrate(&|t, rest| {
+                let x = a.add_with(&t.div_with(rest, context), context);
+                (x, reciprocal(&rest.mul_with(rest, context)))
+            })
+        }
+        (Bound::NegInf, Bound::Finite(b)) => {
+            // x = b - (1 - t) / t
+            integrate(&|t, rest| {
+                let x = b.sub_with(&rest.div_with(t, context), context);
+                (x, reciprocal(&t.mul_with(t, context)))
+            })
+        }
+        (Bound::NegInf, Bound::PosInf) => {
+            // x = 1 / (1 - t) - 1 / t
+            integrate(&|t, rest| {
+                let x = reciprocal(rest).sub_with(&reciprocal(t), context);
+                let dx_dt = reciprocal(&rest.mul_with(rest, context))
+                    .add_with(&reciprocal(&t.mul_with(t, context)), context);
+                (x, dx_dt)
+            })
+        }
+        (Bound::PosInf, Bound::PosInf) | (Bound::NegInf, Bound::NegInf) => Ok(Quadrature {
+            value: ZERO,
+            error: ZERO,
+        }),
+        (Bound::PosInf, _) | (_, Bound::NegInf) => {
+            // ∫ f dx from b to a = -∫ f dx from a to b
+            let result = quadrature(integrand, wrt, upper, lower, digits)?;
+            Ok(Quadrature {
+                value: -result.value,
+                error: result.error,
+            })
+        }
+    }
+}
+
+/// Integrates `f(t, 1 - t)` from 0 to 1 with tanh-sinh quadrature, see [quadrature], or returns `None` if it doesn't reach `digits` significant digits.
+///
+/// Substituting `t = 1 / (1 + e^(-π * sinh(u)))` turns the integral into one over every `u`, whose integrand falls off so quickly that summing samples a step `h` apart gains about twice as many digits each time `h` is halved.
+fn tanh_sinh(
+    f: &dyn Fn(&CASNum, &CASNum) -> Result<CASNum, CASError>,
+    digits: usize,
+) -> Result<Option<Quadrature>, CASError> {
+    let context = context_for_digits(digits);
+    let num_limbs = context.num_limbs;
+    let one = || CASNum::from(1);
+    let pi = CASNum::pi(num_limbs);
+
+    let mut power_of_ten = one();
+    for _ in 0..digits {
+        power_of_ten = power_of_ten * CASNum::from(10);
+    }
+    let tolerance = one().div_with(&power_of_ten, context);
+
+    //past u_max, e^(-π * sinh(u)) is below 10^(-2 * digits), so the samples are too small to matter
+    let u_max = (2.0 * digits as f64 * LN_10 / PI).asinh();
+
+    //sum of the samples at u and -u, each of which is f(t, 1 - t) * dt/du
+    let samples = |u: f64| -> Result<CASNum, CASError> {
+        let exp_u = CASNum::from(u).exp(num_limbs);
+        let exp_neg_u = one().div_with(&exp_u, context);
+        let sinh = exp_u
+            .sub_with(&exp_neg_u, context)
+            .div_with(&CASNum::from(2), context);
+        let cosh = exp_u
+            .add_with(&exp_neg_u, context)
+            .div_with(&CASNum::from(2), context);
+        let exp_s = pi.mul_with(&sinh, context).exp(num_limbs);
+        let denominator = one().add_with(&exp_s, context);
+
+        let t = exp_s.div_with(&denominator, context);
+        let rest = one().div_with(&denominator, context);
+        let dt_du = pi
+            .mul_with(&cosh, context)
+            .mul_with(&t, context)
+            .mul_with(&rest, context);
+        let value = match u == 0.0 {
+            true => f(&t, &rest)?,
+            //-u gives 1 - t, so the two samples share their weight
+            false => f(&t, &rest)?.add_with(&f(&rest, &t)?, context),
+        };
+        Ok(value.mul_with(&dt_du, context))
+    };
+
+    let mut sum = ZERO;
+    let mut previous: Option<CASNum> = None;
+    for level in 0..=MAX_LEVELS {
+        let step = 0.5f64.powi(level);
+        let last = (u_max / step) as u64;
+        //every other point was already sampled at the previous level
+        let first = if level == 0 { 0 } else { 1 };
+        let stride = if level == 0 { 1 } else { 2 };
+        for idx in (first..=last).step_by(stride) {
+            sum = sum.add_with(&samples(idx as f64 * step)?, context);
+        }
+
+        let value = sum.mul_with(&CASNum::from(step), context);
+        if !matches!(value.value, CASValue::Finite { .. }) {
+            //the integrand isn't finite somewhere, so more samples won't help
+            return Ok(Some(Quadrature { value, error: ZERO }));
+        }
+        if let Some(previous) = previous.replace(value.clone()) {
+            let error = value.sub_with(&previous, context).abs();
+            //precision is relative to the value, or absolute for values smaller than 1
+            let scale = match value.abs() > one() {
+                true => value.abs(),
+                false => one(),
+            };
+            if level >= MIN_LEVELS && error <= tolerance.mul_with(&scale, context) {
+                return Ok(Some(Quadrature { value, error }));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -1,177 +1,447 @@
-use std::{cell::RefCell, collections::HashMap, ops::Mul, rc::Rc};
-
-use num_traits::Zero;
+use std::cmp::Ordering;
 
 ///Contains functionality for simplifying mathematical expressions. i.e.
-/// ```
-/// x + x => x * 2
+/// ```text
+/// x + x => 2 * x
 /// x * 0 => 0,
 /// etc.
 /// ```
 use crate::{
-    parser::trees::{Tree, TreeNode, TreeNodeRef},
+    parser::trees::Tree,
     types::{
-        cas_num::{CASNum, ZERO},
-        symbol::{Symbol, SymbolType},
+        cas_num::Number,
+        symbol::{
+            constant::{Const, ResConst},
+            function::{Func, ResFun},
+            operator::Operator,
+            Symbol, SymbolType,
+        },
     },
 };
 
-use crate::types::symbol::operator::Operator;
-use crate::types::symbol::SymbolType::*;
+use super::build::*;
+
+/// Largest exponent that powers of numbers like `2 ^ 10` are folded for, so something like `9 ^ 9 ^ 9` doesn't try to build a number with billions of digits.
+const MAX_FOLDED_EXPONENT: u64 = 1024;
+
+/// Largest power of a sum that's multiplied out when expanding, so something like `(x + 1) ^ 1000` isn't expanded into a thousand terms.
+const MAX_EXPANDED_EXPONENT: i64 = 32;
 
 impl Tree<Symbol> {
-    fn simplify(&mut self) {
-        TreeNode::<Symbol>::simplify(&mut self.root.0.borrow_mut());
+    /// Rewrites the expression into its canonical form, so expressions that only differ by the order of sums and products or by uncollected like terms end up structurally equal.
+    ///
+    /// In canonical form:
+    /// - subtraction, negation and division are written with addition, multiplication and exponents, i.e. `a - b` becomes `a + -1 * b` and `a / b` becomes `a * b ^ -1`
+    /// - nested sums and products are flattened into a single `+` or `*` with any number of arguments
+    /// - numbers in a sum or product are folded into one, which is the last argument of a sum and the first argument of a product
+    /// - powers of exact numbers are folded, so fractions like `2 / 6` become the exact number `1/3`, while inexact numbers like `0.5 ^ -1` are left as they are
+    /// - like terms are collected, i.e. `2 * x + 3 * x` becomes `5 * x` and `x * x ^ 2` becomes `x ^ 3`
+    /// - integer powers of `i` are reduced with `i ^ 2 = -1`, so `i * i` becomes `-1` and `i ^ 7` becomes `-1 * i`
+    /// - functions are replaced by their value at arguments where it is an integer, i.e. `ln(1)` becomes `0` and `cos(π)` becomes `-1`, and `sqrt(-1)` becomes `i`
+    /// - square roots of squares of rationals are found exactly, i.e. `sqrt(25)` becomes `5` and `sqrt(4 / 9)` becomes `2 / 3`
+    /// - arguments of products are sorted in ascending order and arguments of sums in descending order, see [SymbolType::partial_cmp]
+    pub(crate) fn simplify(&mut self) {
+        self.root = simplify(&self.root);
     }
 }
-impl TreeNode<Symbol> {
-    fn simplify(&mut self) {
-        for child in &self.children {
-            Self::simplify(&mut child.0.borrow_mut());
+
+/// Finds the canonical form of a subtree, see [Tree::simplify].
+pub(crate) fn simplify(tree_node: &Node) -> Node {
+    let data = tree_node.0.borrow().data.clone();
+    let mut args: Vec<Node> = tree_node.0.borrow().children.iter().map(simplify).collect();
+    let line_pos = data.line_pos;
+
+    match data.symbol_type {
+        SymbolType::Operator(Operator::Add) => sum(args, line_pos),
+        SymbolType::Operator(Operator::Mult) => product(args, line_pos),
+        SymbolType::Operator(Operator::Exp) => {
+            let exp = args.pop().unwrap();
+            power(args.pop().unwrap(), exp)
+        }
+        SymbolType::Operator(Operator::Sub) => {
+            // a - b -> a + -1 * b
+            let rhs = product(vec![num(-1, line_pos), args.pop().unwrap()], line_pos);
+            sum(vec![args.pop().unwrap(), rhs], line_pos)
+        }
+        SymbolType::Operator(Operator::Neg) => {
+            // -a -> -1 * a
+            product(vec![num(-1, line_pos), args.pop().unwrap()], line_pos)
+        }
+        SymbolType::Operator(Operator::Div) => {
+            // a / b -> a * b ^ -1
+            let rhs = power(args.pop().unwrap(), num(-1, line_pos));
+            product(vec![args.pop().unwrap(), rhs], line_pos)
+        }
+        SymbolType::Function(Func::ResFun { fun, .. }) if fun.num_args() == 1 => {
+            exact_value(fun, &args[0]).unwrap_or_else(|| node(data.symbol_type, line_pos, args))
         }
+        symbol_type => node(symbol_type, line_pos, args),
+    }
+}
 
-        match self.data.symbol_type {
-            SymbolType::Operator(Operator::Add) => simplify_add(self),
-            SymbolType::Operator(Operator::Sub) => simplify_sub(self),
-            SymbolType::Operator(Operator::Mult) => simplify_mult(self),
-            SymbolType::Operator(Operator::Div) => simplify_div(self),
-            _ => {}
-        };
+/// Multiplies out products and integer powers of sums, so a polynomial that is zero simplifies to the number 0.
+pub(crate) fn expand(tree_node: &Node) -> Node {
+    let line_pos = line_pos(tree_node);
+    let terms = expanded_terms(&simplify(tree_node));
+    simplify(&node(SymbolType::Operator(Operator::Add), line_pos, terms))
+}
 
-        todo!();
+/// The terms of a simplified expression once products of sums in it are multiplied out.
+fn expanded_terms(tree_node: &Node) -> Vec<Node> {
+    let symbol_type = tree_node.0.borrow().data.symbol_type.clone();
+    let args = children(tree_node);
+    match symbol_type {
+        SymbolType::Operator(Operator::Add) => args.iter().flat_map(expanded_terms).collect(),
+        SymbolType::Operator(Operator::Mult) => args
+            .iter()
+            .fold(vec![num(1, line_pos(tree_node))], |terms, factor| {
+                multiply_terms(&terms, &expanded_terms(factor))
+            }),
+        SymbolType::Operator(Operator::Exp) if is_operator(&args[0], Operator::Add) => {
+            match integer_value(&args[1]) {
+                Some(exp) if (1..=MAX_EXPANDED_EXPONENT).contains(&exp) => {
+                    let base = expanded_terms(&args[0]);
+                    (1..exp).fold(base.clone(), |terms, _| multiply_terms(&terms, &base))
+                }
+                _ => vec![tree_node.clone()],
+            }
+        }
+        _ => vec![tree_node.clone()],
     }
 }
 
-fn simplify_add(node: &mut TreeNode<Symbol>) {
-    let mut args = vec![];
+/// Every product of a term of `lhs` with a term of `rhs`.
+fn multiply_terms(lhs: &[Node], rhs: &[Node]) -> Vec<Node> {
+    lhs.iter()
+        .flat_map(|lhs| rhs.iter().map(|rhs| mult(lhs.clone(), rhs.clone())))
+        .collect()
+}
+
+/// Value of a single argument reserved function at an argument where the value is exact, like `ln(e) = 1`, `cos(π) = -1` or `sqrt(4) = 2`.
+fn exact_value(fun: ResFun, arg: &Node) -> Option<Node> {
+    let is_const = |constant: ResConst| {
+        arg.0.borrow().data.symbol_type == SymbolType::Const(Const::ResConst(constant))
+    };
+
+    let value = match fun {
+        ResFun::Ln if is_const(ResConst::E) => 1,
+        ResFun::Log2 if is_num(arg, 2) => 1,
+        ResFun::Log10 if is_num(arg, 10) => 1,
+        ResFun::Ln | ResFun::Log2 | ResFun::Log10 | ResFun::Acos if is_num(arg, 1) => 0,
+        ResFun::Sqrt if is_num(arg, -1) => return Some(res_const(ResConst::I, line_pos(arg))),
+        ResFun::Sqrt | ResFun::Cbrt if is_num(arg, 0) || is_num(arg, 1) => {
+            return Some(arg.clone())
+        }
+        //roots of squares of rationals, like sqrt(25) = 5 or sqrt(4 / 9) = 2 / 3
+        ResFun::Sqrt => {
+            let root = num_value(arg)?.exact()?.sqrt()?;
+            return Some(num(Number::from(root), line_pos(arg)));
+        }
+        ResFun::Sin | ResFun::Tan | ResFun::Asin | ResFun::Atan if is_num(arg, 0) => 0,
+        ResFun::Sin | ResFun::Tan if is_const(ResConst::Pi) => 0,
+        ResFun::Cos if is_num(arg, 0) => 1,
+        ResFun::Cos if is_const(ResConst::Pi) => -1,
+        _ => return None,
+    };
+    Some(num(value, line_pos(arg)))
+}
 
-    let mut arg_counts: HashMap<TreeNodeRef<Symbol>, u32> = HashMap::new();
-    for arg in &node.children {
-        let TreeNode {
-            data: Symbol { symbol_type, .. },
-            children,
-        } = &arg.0.borrow().to_owned();
+/// Builds the canonical form of the sum of canonical terms.
+fn sum(terms: Vec<Node>, line_pos: usize) -> Node {
+    let mut constant = Number::from(0);
+    let mut like_terms: Vec<(Node, Number)> = vec![];
+    //each distinct term along with its total coefficient, in the order they first appear
 
-        if *symbol_type == (Num { value: ZERO }) {
+    for term in flatten(terms, Operator::Add) {
+        if let Some(value) = num_value(&term) {
+            constant += &value;
             continue;
         }
-        //adding 0 does nothing
 
-        match symbol_type {
-            SymbolType::Operator(Operator::Add) => {
-                args.extend(children.clone());
-                for child in children {
-                    *arg_counts.entry(child.clone()).or_insert(0) += 1;
-                    //adds 1 to entry if it exists, and creates it if not
-                }
-            } //reduce
-            //a + (b + c) = a + b + c
-            _ => {
-                arg_counts.entry(arg.clone());
-            }
+        let (coefficient, term) = split_coefficient(&term);
+        match like_terms.iter_mut().find(|(other, _)| *other == term) {
+            Some((_, total)) => *total += &coefficient,
+            None => like_terms.push((term, coefficient)),
         }
     }
 
-    for (item, count) in arg_counts {
-        if count == 1 {
-            args.push(item);
-        } else {
-            //if the same expr occurs multiple times, replace it w multiplication
-            //a + a + a -> 3 * a
-            let coeff = TreeNode::from(Symbol {
-                symbol_type: Num {
-                    value: CASNum::from(count),
-                },
-                line_pos: item.0.borrow().data.line_pos,
-            });
-            let mult = TreeNodeRef::new(Symbol {
-                symbol_type: Operator(Operator::Mult),
-                line_pos: item.0.borrow().data.line_pos,
-            });
-            mult.0.borrow_mut().children.push(item);
+    let mut terms: Vec<Node> = like_terms
+        .into_iter()
+        .filter(|(_, coefficient)| *coefficient != Number::from(0))
+        .map(|(term, coefficient)| product(vec![num(coefficient, line_pos), term], line_pos))
+        .collect();
+    terms.sort_by(|lhs, rhs| compare_terms(rhs, lhs));
+    //descending, so polynomials are written from the highest power down
 
-            mult.0.borrow_mut().add_children(vec![coeff]);
-            args.push(mult);
-        }
+    if constant != Number::from(0) || terms.is_empty() {
+        terms.push(num(constant, line_pos));
     }
 
-    node.children = args;
+    if terms.len() == 1 {
+        return terms.pop().unwrap();
+    }
+    node(SymbolType::Operator(Operator::Add), line_pos, terms)
 }
 
-fn simplify_sub(node: &mut TreeNode<Symbol>) {}
-
-fn simplify_mult(node: &mut TreeNode<Symbol>) {
-    let mut args = vec![];
+/// Builds the canonical form of the product of canonical factors.
+fn product(factors: Vec<Node>, line_pos: usize) -> Node {
+    let mut coefficient = Number::from(1);
+    let mut powers: Vec<(Node, Vec<Node>)> = vec![];
+    //each distinct base along with every exponent it's raised to, in the order they first appear
 
-    let mut arg_counts: HashMap<TreeNodeRef<Symbol>, u32> = HashMap::new();
-    for arg in &node.children {
-        let TreeNode {
-            data: Symbol { symbol_type, .. },
-            children,
-        } = &arg.0.borrow().to_owned();
-
-        if *symbol_type
-            == (Num {
-                value: CASNum::from(1),
-            })
-        {
+    for factor in flatten(factors, Operator::Mult) {
+        if let Some(value) = num_value(&factor) {
+            coefficient *= &value;
             continue;
         }
-        //multiplying by 1 does nothing
-        else if *symbol_type == (Num { value: ZERO }) {
-            args = vec![TreeNodeRef::new(Symbol {
-                symbol_type: Num { value: ZERO },
-                line_pos: arg.0.borrow().data.line_pos,
-            })];
-            break;
-        }
-
-        match symbol_type {
-            SymbolType::Operator(Operator::Mult) => {
-                args.extend(children.clone());
-                for child in children {
-                    *arg_counts.entry(child.clone()).or_insert(0) += 1;
-                    //adds 1 to entry if it exists, and creates it if not
-                }
-            } //reduce
-            //a * (b * c) = a * b * c
-            _ => {
-                arg_counts.entry(arg.clone());
+
+        let (base, exp) = split_power(&factor);
+        match powers.iter_mut().find(|(other, _)| *other == base) {
+            Some((_, exps)) => exps.push(exp),
+            None => powers.push((base, vec![exp])),
+        }
+    }
+
+    if coefficient == Number::from(0) {
+        return num(0, line_pos);
+    }
+
+    let factors: Vec<Node> = powers
+        .into_iter()
+        .map(|(base, mut exps)| {
+            let exp = if exps.len() == 1 {
+                exps.pop().unwrap()
+            } else {
+                sum(exps, line_pos)
+            };
+            power(base, exp)
+        })
+        .collect();
+
+    if factors
+        .iter()
+        .any(|factor| is_operator(factor, Operator::Mult))
+    {
+        //a power like (2 * x) ^ 2 turned into a product, so its factors still need to be combined with the others
+        let mut factors = factors;
+        factors.push(num(coefficient, line_pos));
+        return product(factors, line_pos);
+    }
+
+    let mut factors: Vec<Node> = factors
+        .into_iter()
+        .filter(|factor| match num_value(factor) {
+            Some(value) => {
+                coefficient *= &value;
+                false
+            }
+            None => true,
+        })
+        .collect();
+    factors.sort_by(compare_factors);
+
+    if coefficient != Number::from(1) {
+        if let [factor] = factors.as_slice() {
+            if is_operator(factor, Operator::Add) {
+                // c * (a + b) -> c * a + c * b
+                let terms = factor.0.borrow().children.clone();
+                return sum(
+                    terms
+                        .into_iter()
+                        .map(|term| {
+                            product(vec![num(coefficient.clone(), line_pos), term], line_pos)
+                        })
+                        .collect(),
+                    line_pos,
+                );
             }
         }
+        factors.insert(0, num(coefficient, line_pos));
+    }
+
+    match factors.len() {
+        0 => num(1, line_pos),
+        1 => factors.pop().unwrap(),
+        _ => node(SymbolType::Operator(Operator::Mult), line_pos, factors),
+    }
+}
+
+/// Builds the canonical form of `base ^ exp`, where both are canonical.
+fn power(base: Node, exp: Node) -> Node {
+    let line_pos = line_pos(&base);
+
+    if is_num(&exp, 0) || is_num(&base, 1) {
+        return num(1, line_pos);
+    }
+    if is_num(&exp, 1) {
+        return base;
     }
+    if is_num(&base, 0) && num_value(&exp).is_some_and(|exp| exp > Number::from(0)) {
+        return num(0, line_pos);
+    }
+
+    if let Some(n) = integer_value(&exp) {
+        if base.0.borrow().data.symbol_type == SymbolType::Const(Const::ResConst(ResConst::I)) {
+            // i ^ 2 = -1, so powers of i repeat every 4
+            return match n.rem_euclid(4) {
+                0 => num(1, line_pos),
+                1 => base,
+                2 => num(-1, line_pos),
+                _ => product(vec![num(-1, line_pos), base], line_pos),
+            };
+        }
+        if let Some(value) = num_value(&base) {
+            if (0..=MAX_FOLDED_EXPONENT as i64).contains(&n) {
+                return num(pow_num(value, n as u64), line_pos);
+            }
+            let recip = match &value {
+                Number::Exact(value) => value.recip(),
+                Number::Inexact(_) => None,
+            };
+            if let Some(recip) = recip.filter(|_| (-(MAX_FOLDED_EXPONENT as i64)..0).contains(&n)) {
+                // a ^ -n -> (1 / a) ^ n, which stays exact for exact numbers
+                return num(pow_num(Number::from(recip), -n as u64), line_pos);
+            }
+            if value == Number::from(-1) {
+                return num(if n % 2 == 0 { 1 } else { -1 }, line_pos);
+            }
+            if n < 0 && value < Number::from(0) {
+                // (-a) ^ n -> (-1) ^ n * a ^ n
+                let sign = num(if n % 2 == 0 { 1 } else { -1 }, line_pos);
+                return product(vec![sign, power(num(-value, line_pos), exp)], line_pos);
+            }
+            if (-(MAX_FOLDED_EXPONENT as i64)..=-2).contains(&n) && value != Number::from(0) {
+                // a ^ -n -> (a ^ n) ^ -1, so every fraction is written as a single integer to the power of -1
+                return node(
+                    SymbolType::Operator(Operator::Exp),
+                    line_pos,
+                    vec![num(pow_num(value, -n as u64), line_pos), num(-1, line_pos)],
+                );
+            }
+        }
 
-    if args.len() == 1 {
-        //if multiplying by 0
-        //this is messy fix this logic later
-        node.children == args;
-        return;
+        if is_operator(&base, Operator::Exp) {
+            // (a ^ m) ^ n -> a ^ (m * n), which only holds in general for integer n
+            let children = base.0.borrow().children.clone();
+            return power(
+                children[0].clone(),
+                product(vec![children[1].clone(), exp], line_pos),
+            );
+        }
+
+        if is_operator(&base, Operator::Mult) {
+            // (a * b) ^ n -> a ^ n * b ^ n
+            let children = base.0.borrow().children.clone();
+            return product(
+                children
+                    .into_iter()
+                    .map(|factor| power(factor, exp.deep_clone()))
+                    .collect(),
+                line_pos,
+            );
+        }
     }
 
-    for (item, count) in arg_counts {
-        if count == 1 {
-            args.push(item);
+    node(
+        SymbolType::Operator(Operator::Exp),
+        line_pos,
+        vec![base, exp],
+    )
+}
+
+/// Replaces arguments that are themselves calls to `op` with their arguments, i.e. `a + (b + c)` -> `a + b + c`.
+fn flatten(args: Vec<Node>, op: Operator) -> Vec<Node> {
+    let mut flattened = vec![];
+    for arg in args {
+        if is_operator(&arg, op) {
+            flattened.extend(arg.0.borrow().children.iter().cloned());
         } else {
-            //if the same expr occurs multiple times, replace it w exponentiation
-            //a * a * a -> a ^ 3
-            let coeff = TreeNode::from(Symbol {
-                symbol_type: Num {
-                    value: CASNum::from(count),
-                },
-                line_pos: item.0.borrow().data.line_pos,
-            });
-            let exp = TreeNodeRef::new(Symbol {
-                symbol_type: Operator(Operator::Exp),
-                line_pos: item.0.borrow().data.line_pos,
-            });
-            exp.0.borrow_mut().children.push(item);
+            flattened.push(arg);
+        }
+    }
+    flattened
+}
 
-            exp.0.borrow_mut().add_children(vec![coeff]);
-            args.push(exp);
+/// Splits a canonical term into its numeric coefficient and the rest of the term, i.e. `3 * x * y` -> `(3, x * y)`.
+fn split_coefficient(term: &Node) -> (Number, Node) {
+    if is_operator(term, Operator::Mult) {
+        let children = term.0.borrow().children.clone();
+        if let Some(coefficient) = num_value(&children[0]) {
+            let line_pos = line_pos(term);
+            let mut rest = children[1..].to_vec();
+            let rest = if rest.len() == 1 {
+                rest.pop().unwrap()
+            } else {
+                node(SymbolType::Operator(Operator::Mult), line_pos, rest)
+            };
+            return (coefficient, rest);
         }
     }
+    (Number::from(1), term.clone())
+}
 
-    node.children = args;
+/// Splits a canonical factor into its base and exponent, i.e. `x ^ 2` -> `(x, 2)` and `x` -> `(x, 1)`.
+fn split_power(factor: &Node) -> (Node, Node) {
+    if is_operator(factor, Operator::Exp) {
+        let children = factor.0.borrow().children.clone();
+        return (children[0].clone(), children[1].clone());
+    }
+    (factor.clone(), num(1, line_pos(factor)))
 }
 
-fn simplify_div(node: &mut TreeNode<Symbol>) {}
+/// Returns the value of a node if it's an integer literal small enough to fit in an i64.
+pub(crate) fn integer_value(tree_node: &Node) -> Option<i64> {
+    i64::try_from(&num_value(tree_node)?).ok()
+}
+
+/// Raises a number to a non-negative integer power by repeated squaring.
+fn pow_num(mut base: Number, mut exp: u64) -> Number {
+    let mut result = Number::from(1);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result *= &base;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.clone() * base;
+        }
+    }
+    result
+}
+
+/// Compares two subtrees by their roots using [SymbolType::partial_cmp], then by their arguments from left to right.
+fn compare(lhs: &Node, rhs: &Node) -> Ordering {
+    let lhs = lhs.0.borrow();
+    let rhs = rhs.0.borrow();
+    lhs.data
+        .symbol_type
+        .partial_cmp(&rhs.data.symbol_type)
+        .unwrap_or(Ordering::Equal)
+        .then(lhs.children.len().cmp(&rhs.children.len()))
+        .then_with(|| {
+            lhs.children
+                .iter()
+                .zip(&rhs.children)
+                .map(|(lhs, rhs)| compare(lhs, rhs))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+/// Compares terms of a sum by everything other than their coefficients first, so `2 * x ^ 2` is still greater than `3 * x`.
+fn compare_terms(lhs: &Node, rhs: &Node) -> Ordering {
+    let (lhs_coefficient, lhs) = split_coefficient(lhs);
+    let (rhs_coefficient, rhs) = split_coefficient(rhs);
+    compare(&lhs, &rhs).then(
+        lhs_coefficient
+            .partial_cmp(&rhs_coefficient)
+            .unwrap_or(Ordering::Equal),
+    )
+}
+
+/// Compares factors of a product by their bases first, so `x ^ 2` comes before `y`.
+fn compare_factors(lhs: &Node, rhs: &Node) -> Ordering {
+    let (lhs_base, lhs_exp) = split_power(lhs);
+    let (rhs_base, rhs_exp) = split_power(rhs);
+    compare(&lhs_base, &rhs_base).then_with(|| compare(&lhs_exp, &rhs_exp))
+}
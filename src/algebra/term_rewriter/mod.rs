@@ -1,15 +1,536 @@
-struct Rule<'a> {
-    input: &'a str,
-    output: &'a str,
+//! Rewrites expressions using rules written in the CAS's own syntax. i.e.
+//! ```text
+//! a * 0 => 0
+//! a + a => 2 * a
+//! ```
+//! Every name in a rule is a pattern variable that matches any subexpression, and a name used more than once has to match equal subexpressions every time.
+//!
+//! Arguments of commutative operators can be matched in any order, and rules for associative operators can match just some of the arguments, so `a + 0 => a` rewrites `x + 0 + y` to `x + y`.
+//!
+//! Rules can also have a condition on the pattern variables, which is checked after the pattern matches.
+//! ```text
+//! a * (b / a) => b if a != 0
+//! ```
+
+use std::collections::HashMap;
+
+use super::build::{line_pos, node, num_value, Node};
+use super::simplify::simplify;
+use crate::{
+    parser::{parse_expr, trees::Tree, vars::VarTable},
+    scanner::tokenize,
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        symbol::{
+            operator::{associative, commutative, Operator},
+            Symbol, SymbolType,
+        },
+        token::TokenType,
+    },
+};
+
+/// Tests for pattern matching and rewriting.
+mod test;
+
+/// Number of rewrites [RuleSet::rewrite] makes by default before deciding the rules will never stop applying.
+pub(crate) const DEFAULT_STEP_LIMIT: usize = 1000;
+
+/// A rewrite rule as written, like `Rule { input: "a * 0", output: "0", condition: None }`.
+pub(crate) struct Rule<'a> {
+    /// Pattern to look for.
+    pub(crate) input: &'a str,
+    /// What to replace matches of the pattern with.
+    pub(crate) output: &'a str,
+    /// Comparison between pattern variables that has to hold for the rule to apply, like `a != 0`.
+    pub(crate) condition: Option<&'a str>,
 }
 
-//we need a tree as input and output, but include named parameters that we replace
+/// A rule with both sides parsed into expression trees.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RewriteRule {
+    /// Pattern, where every variable matches any subexpression.
+    pub(crate) lhs: Tree<Symbol>,
+    /// Replacement, where every variable is replaced by what it matched in `lhs`.
+    pub(crate) rhs: Tree<Symbol>,
+    /// Comparison checked after `lhs` matches, with its variables replaced the same way as in `rhs`.
+    pub(crate) condition: Option<Tree<Symbol>>,
+}
+
+/// Subexpressions matched by each pattern variable.
+type Bindings = HashMap<String, Node>;
+
+impl RewriteRule {
+    /// Parses both sides of a rule and its condition.
+    ///
+    /// Returns an error if the right hand side or condition uses a pattern variable that isn't in the pattern, since there would be nothing to replace it with.
+    pub(crate) fn new(rule: &Rule) -> Result<Self, CASError> {
+        Self::from_parts(
+            (rule.input, 0),
+            (rule.output, 0),
+            rule.condition.map(|code| (code, 0)),
+        )
+    }
+
+    /// Parses each part of a rule, given along with the position it starts at in the line it was written on.
+    fn from_parts(
+        lhs: (&str, usize),
+        rhs: (&str, usize),
+        condition: Option<(&str, usize)>,
+    ) -> Result<Self, CASError> {
+        let lhs = parse_pattern(lhs.0, lhs.1)?;
+        let rhs = parse_pattern(rhs.0, rhs.1)?;
+        let condition = condition
+            .map(|(code, offset)| parse_pattern(code, offset))
+            .transpose()?;
+
+        let mut bound: Vec<String> = vec![];
+        variables(&lhs.root, &mut bound);
+        check_bound(&rhs.root, &bound)?;
+        if let Some(condition) = &condition {
+            check_bound(&condition.root, &bound)?;
+        }
+
+        Ok(RewriteRule {
+            lhs,
+            rhs,
+            condition,
+        })
+    }
+
+    /// Parses a rule written as `lhs => rhs` or `lhs => rhs if condition`, keeping the positions of any errors relative to the start of the line.
+    fn parse_line(line: &str) -> Result<Self, CASError> {
+        let Some((lhs, rest)) = line.split_once("=>") else {
+            return Err(CASError {
+                line_pos: line.chars().count().saturating_sub(1),
+                kind: CASErrorKind::MalformedRule,
+            });
+        };
+
+        let rhs_start = lhs.len() + "=>".len();
+        let (rhs, condition) = match rest.split_once(" if ") {
+            Some((rhs, condition)) => {
+                let condition_start = rhs_start + rhs.len() + " if ".len();
+                (rhs, Some((condition, char_pos(line, condition_start))))
+            }
+            None => (rest, None),
+        };
+
+        Self::from_parts((lhs, 0), (rhs, char_pos(line, rhs_start)), condition)
+    }
+
+    /// Tries to match the pattern against the root of `subject`, and returns the replacement if it matches.
+    fn apply(&self, subject: &Node) -> Option<Node> {
+        let pattern = &self.lhs.root;
+        let line_pos = line_pos(subject);
+
+        match operator(pattern) {
+            Some(op) if associative(&op) && operator(subject) == Some(op) => {
+                //any arguments the pattern doesn't use are kept next to the replacement
+                let (bindings, mut leftover) = match_ac(
+                    op,
+                    &flatten(pattern, op),
+                    &flatten(subject, op),
+                    Bindings::new(),
+                    true,
+                )?;
+                if !self.condition_holds(&bindings) {
+                    return None;
+                }
+                let replacement = substitute(&self.rhs.root, &bindings, line_pos);
+                if leftover.is_empty() {
+                    return Some(replacement);
+                }
+
+                let mut args = flatten(&replacement, op);
+                args.append(&mut leftover);
+                Some(node(SymbolType::Operator(op), line_pos, args))
+            }
+            _ => {
+                let bindings = match_node(pattern, subject, Bindings::new())?;
+                if !self.condition_holds(&bindings) {
+                    return None;
+                }
+                Some(substitute(&self.rhs.root, &bindings, line_pos))
+            }
+        }
+    }
+
+    /// Checks the rule's condition after its pattern has matched.
+    ///
+    /// `==` and `!=` compare the canonical forms of both sides, so `a != 0` holds for anything that doesn't simplify to 0. The other comparisons only hold if both sides simplify to numbers, and any other condition never holds.
+    fn condition_holds(&self, bindings: &Bindings) -> bool {
+        let Some(condition) = &self.condition else {
+            return true;
+        };
 
-/*
+        let condition = substitute(&condition.root, bindings, 0);
+        let Some(op) = operator(&condition) else {
+            return false;
+        };
+        let args: Vec<Node> = condition.0.borrow().children.iter().map(simplify).collect();
 
-    *
-   / \
-  /   \     ==>  0
- a     0
+        match op {
+            Operator::Equal => args[0] == args[1],
+            Operator::NotEqual => args[0] != args[1],
+            Operator::Less | Operator::Greater | Operator::LessEqual | Operator::GreaterEqual => {
+                let (Some(lhs), Some(rhs)) = (num_value(&args[0]), num_value(&args[1])) else {
+                    return false;
+                };
+                match op {
+                    Operator::Less => lhs < rhs,
+                    Operator::Greater => lhs > rhs,
+                    Operator::LessEqual => lhs <= rhs,
+                    _ => lhs >= rhs,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A list of rules that are applied to an expression until none of them match.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RuleSet {
+    /// Rules in the order they are tried.
+    pub(crate) rules: Vec<RewriteRule>,
+    /// Number of rewrites made before giving up.
+    pub(crate) step_limit: usize,
+}
+
+impl Default for RuleSet {
+    /// An empty set of rules, which leaves every expression unchanged.
+    fn default() -> Self {
+        RuleSet {
+            rules: vec![],
+            step_limit: DEFAULT_STEP_LIMIT,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Parses every rule, keeping them in the same order.
+    pub(crate) fn new(rules: &[Rule]) -> Result<Self, CASError> {
+        Ok(RuleSet {
+            rules: rules
+                .iter()
+                .map(RewriteRule::new)
+                .collect::<Result<_, _>>()?,
+            step_limit: DEFAULT_STEP_LIMIT,
+        })
+    }
+
+    /// Parses a list of rules with one rule on each line, written as `lhs => rhs` or `lhs => rhs if condition`.
+    ///
+    /// Blank lines and lines starting with `#` are skipped. If any line can't be parsed, returns the error on every such line along with its line number (starting at 0).
+    pub(crate) fn parse(code: &str) -> Result<Self, Vec<(usize, CASError)>> {
+        let mut rules: Vec<RewriteRule> = vec![];
+        let mut errors: Vec<(usize, CASError)> = vec![];
+
+        for (line_num, line) in code.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            match RewriteRule::parse_line(line) {
+                Ok(rule) => rules.push(rule),
+                Err(err) => errors.push((line_num, err)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(RuleSet {
+            rules,
+            step_limit: DEFAULT_STEP_LIMIT,
+        })
+    }
+
+    /// Adds the rules of another set after the rules of this one.
+    pub(crate) fn extend(&mut self, other: RuleSet) {
+        self.rules.extend(other.rules);
+    }
+
+    /// Sets the number of rewrites [RuleSet::rewrite] makes before giving up.
+    pub(crate) fn with_step_limit(mut self, step_limit: usize) -> Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    /// Rewrites the expression one match at a time until no rule matches any part of it.
+    ///
+    /// Each step applies the first rule that matches the innermost, leftmost subexpression any rule matches. Returns an error if the expression is still changing after the step limit.
+    pub(crate) fn rewrite(&self, tree: &Tree<Symbol>) -> Result<Tree<Symbol>, CASError> {
+        let mut root = tree.root.deep_clone();
+        let mut steps = 0;
+
+        while let Some(new_root) = self.rewrite_once(&root) {
+            if steps == self.step_limit {
+                return Err(CASError {
+                    line_pos: line_pos(&tree.root),
+                    kind: CASErrorKind::StepLimitReached { steps },
+                });
+            }
+            root = new_root;
+            steps += 1;
+        }
+
+        Ok(Tree { root })
+    }
+
+    /// Makes a single rewrite somewhere in the subtree, or returns `None` if no rule matches any of it.
+    fn rewrite_once(&self, subject: &Node) -> Option<Node> {
+        let children = subject.0.borrow().children.clone();
+        for (idx, child) in children.iter().enumerate() {
+            if let Some(new_child) = self.rewrite_once(child) {
+                let mut children = children.clone();
+                children[idx] = new_child;
+                let data = subject.0.borrow().data.clone();
+                return Some(node(data.symbol_type, data.line_pos, children));
+            }
+        }
+
+        self.rules.iter().find_map(|rule| rule.apply(subject))
+    }
+}
 
-*/
+/// Parses part of a rule, treating every name in it as a pattern variable.
+///
+/// `offset` is added to the position of every symbol and error, for parts that don't start at the beginning of a line.
+fn parse_pattern(code: &str, offset: usize) -> Result<Tree<Symbol>, CASError> {
+    let mut tokens = tokenize(code).map_err(|mut errors| {
+        let mut err = errors.remove(0);
+        err.line_pos += offset;
+        err
+    })?;
+    let mut names: Vec<String> = vec![];
+    for token in &mut tokens {
+        token.line_pos += offset;
+        if let TokenType::Name(name) = &token.token_type {
+            if !names.contains(name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    parse_expr(tokens, &VarTable::new(), names).map_err(|mut err| {
+        if err.line_pos < offset {
+            err.line_pos += offset;
+            //errors that aren't tied to a token, like a missing expression, are at position 0 of the part
+        }
+        err
+    })
+}
+
+/// Converts a byte index in a line to the index of the character it's in.
+fn char_pos(line: &str, byte_idx: usize) -> usize {
+    line[..byte_idx].chars().count()
+}
+
+/// Adds the name of every variable in a subtree to `names`.
+fn variables(tree_node: &Node, names: &mut Vec<String>) {
+    let tree_node = tree_node.0.borrow();
+    if let SymbolType::Variable { name } = &tree_node.data.symbol_type {
+        if !names.contains(name) {
+            names.push(name.to_string());
+        }
+    }
+    for child in &tree_node.children {
+        variables(child, names);
+    }
+}
+
+/// Returns an error for the first variable in a subtree that isn't in `bound`.
+fn check_bound(tree_node: &Node, bound: &[String]) -> Result<(), CASError> {
+    let mut names: Vec<String> = vec![];
+    variables(tree_node, &mut names);
+    match names.into_iter().find(|name| !bound.contains(name)) {
+        Some(name) => Err(CASError {
+            line_pos: variable_pos(tree_node, &name).unwrap_or(0),
+            kind: CASErrorKind::UnboundPatternVariable { name },
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Finds the position of the first use of a variable in a subtree.
+fn variable_pos(tree_node: &Node, name: &str) -> Option<usize> {
+    let tree_node = tree_node.0.borrow();
+    match &tree_node.data.symbol_type {
+        SymbolType::Variable { name: var_name } if var_name == name => {
+            Some(tree_node.data.line_pos)
+        }
+        _ => tree_node
+            .children
+            .iter()
+            .find_map(|child| variable_pos(child, name)),
+    }
+}
+
+/// Matches a pattern against a subtree, extending the bindings of the pattern variables matched so far.
+fn match_node(pattern: &Node, subject: &Node, mut bindings: Bindings) -> Option<Bindings> {
+    let pattern_type = pattern.0.borrow().data.symbol_type.clone();
+
+    if let SymbolType::Variable { name } = pattern_type {
+        return match bindings.get(&name) {
+            Some(bound) => (bound == subject).then_some(bindings),
+            None => {
+                bindings.insert(name, subject.clone());
+                Some(bindings)
+            }
+        };
+    }
+
+    if pattern_type != subject.0.borrow().data.symbol_type {
+        return None;
+    }
+
+    let patterns = pattern.0.borrow().children.clone();
+    let subjects = subject.0.borrow().children.clone();
+    match pattern_type {
+        SymbolType::Operator(op) if associative(&op) => match_ac(
+            op,
+            &flatten(pattern, op),
+            &flatten(subject, op),
+            bindings,
+            false,
+        )
+        .map(|(bindings, _)| bindings),
+        SymbolType::Operator(op) if commutative(&op) => {
+            match_args(&patterns, &subjects, bindings.clone()).or_else(|| {
+                let swapped: Vec<Node> = subjects.iter().rev().cloned().collect();
+                match_args(&patterns, &swapped, bindings)
+            })
+        }
+        _ => match_args(&patterns, &subjects, bindings),
+    }
+}
+
+/// Matches patterns against subtrees in order.
+fn match_args(patterns: &[Node], subjects: &[Node], bindings: Bindings) -> Option<Bindings> {
+    if patterns.len() != subjects.len() {
+        return None;
+    }
+    patterns
+        .iter()
+        .zip(subjects)
+        .try_fold(bindings, |bindings, (pattern, subject)| {
+            match_node(pattern, subject, bindings)
+        })
+}
+
+/// Matches the arguments of an associative and commutative operator in any order.
+///
+/// If `allow_extra` is true, subject arguments that no pattern matched are returned alongside the bindings. Otherwise every subject argument has to be matched, and a variable that is the last pattern argument matches all of the remaining ones, i.e. `a + b` matches `x + y + z` with `b = y + z`.
+fn match_ac(
+    op: Operator,
+    patterns: &[Node],
+    subjects: &[Node],
+    bindings: Bindings,
+    allow_extra: bool,
+) -> Option<(Bindings, Vec<Node>)> {
+    let mut patterns = patterns.to_vec();
+    patterns.sort_by_key(is_variable);
+    //variables match anything, so matching everything else first narrows down what they can be
+
+    let mut used = vec![false; subjects.len()];
+    assign(op, &patterns, subjects, &mut used, bindings, allow_extra)
+}
+
+/// Matches the first pattern against each unused subject argument in turn, then the rest of the patterns against what's left, see [match_ac].
+fn assign(
+    op: Operator,
+    patterns: &[Node],
+    subjects: &[Node],
+    used: &mut Vec<bool>,
+    bindings: Bindings,
+    allow_extra: bool,
+) -> Option<(Bindings, Vec<Node>)> {
+    let Some((pattern, rest)) = patterns.split_first() else {
+        let leftover = unused(subjects, used);
+        return (allow_extra || leftover.is_empty()).then_some((bindings, leftover));
+    };
+
+    if rest.is_empty() && !allow_extra && is_variable(pattern) {
+        let mut leftover = unused(subjects, used);
+        let matched = match leftover.len() {
+            0 => return None,
+            1 => leftover.pop().unwrap(),
+            _ => node(SymbolType::Operator(op), line_pos(&leftover[0]), leftover),
+        };
+        return match_node(pattern, &matched, bindings).map(|bindings| (bindings, vec![]));
+    }
+
+    for idx in 0..subjects.len() {
+        if used[idx] {
+            continue;
+        }
+        if let Some(new_bindings) = match_node(pattern, &subjects[idx], bindings.clone()) {
+            used[idx] = true;
+            let result = assign(op, rest, subjects, used, new_bindings, allow_extra);
+            used[idx] = false;
+            if result.is_some() {
+                return result;
+            }
+        }
+    }
+    None
+}
+
+/// Returns the subject arguments that haven't been matched yet.
+fn unused(subjects: &[Node], used: &[bool]) -> Vec<Node> {
+    subjects
+        .iter()
+        .zip(used)
+        .filter(|(_, used)| !**used)
+        .map(|(subject, _)| subject.clone())
+        .collect()
+}
+
+/// Builds a copy of the right hand side of a rule with every pattern variable replaced by what it matched.
+fn substitute(template: &Node, bindings: &Bindings, line_pos: usize) -> Node {
+    let template = template.0.borrow();
+    if let SymbolType::Variable { name } = &template.data.symbol_type {
+        if let Some(bound) = bindings.get(name) {
+            return bound.deep_clone();
+        }
+    }
+
+    node(
+        template.data.symbol_type.clone(),
+        line_pos,
+        template
+            .children
+            .iter()
+            .map(|child| substitute(child, bindings, line_pos))
+            .collect(),
+    )
+}
+
+/// Returns the arguments of an associative operator, including the arguments of any nested uses of the same operator, i.e. `(a + b) + c` -> `[a, b, c]`.
+fn flatten(tree_node: &Node, op: Operator) -> Vec<Node> {
+    if operator(tree_node) != Some(op) {
+        return vec![tree_node.clone()];
+    }
+    tree_node
+        .0
+        .borrow()
+        .children
+        .iter()
+        .flat_map(|child| flatten(child, op))
+        .collect()
+}
+
+/// Returns the operator at the root of a node, if it is one.
+fn operator(tree_node: &Node) -> Option<Operator> {
+    match tree_node.0.borrow().data.symbol_type {
+        SymbolType::Operator(op) => Some(op),
+        _ => None,
+    }
+}
+
+/// Checks if a node of a pattern is a pattern variable.
+fn is_variable(tree_node: &Node) -> bool {
+    matches!(
+        tree_node.0.borrow().data.symbol_type,
+        SymbolType::Variable { .. }
+    )
+}
@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod test {
+    use crate::algebra::term_rewriter::{parse_pattern, Rule, RuleSet};
+    use crate::parser::trees::Tree;
+    use crate::types::cas_error::{CASError, CASErrorKind};
+    use crate::types::symbol::Symbol;
+
+    fn rule_set(rules: &[(&str, &str)]) -> RuleSet {
+        let rules: Vec<Rule> = rules
+            .iter()
+            .map(|(input, output)| Rule {
+                input,
+                output,
+                condition: None,
+            })
+            .collect();
+        RuleSet::new(&rules).unwrap()
+    }
+
+    fn rewrite(rules: &[(&str, &str)], expr: &str) -> Result<Tree<Symbol>, CASError> {
+        rule_set(rules).rewrite(&parse_pattern(expr, 0).unwrap())
+    }
+
+    fn test_rewrite(rules: &[(&str, &str)], expr: &str, desired_result: &str) {
+        assert_eq!(
+            rewrite(rules, expr).unwrap(),
+            parse_pattern(desired_result, 0).unwrap(),
+            "rewriting {}",
+            expr
+        );
+    }
+
+    #[test]
+    fn identities() {
+        let rules = [("a * 0", "0"), ("a * 1", "a"), ("a + 0", "a")];
+        test_rewrite(&rules, "x * 1 + y * 0", "x");
+        test_rewrite(&rules, "sin(x * 1)", "sin(x)");
+        test_rewrite(&rules, "x / 1", "x / 1");
+        test_rewrite(&[("a / 1", "a")], "1 / x", "1 / x");
+        test_rewrite(&[("a - 0", "a")], "0 - x", "0 - x");
+    }
+
+    #[test]
+    fn commutative_matching() {
+        test_rewrite(&[("0 * a", "0")], "x * 0", "0");
+        test_rewrite(&[("a == 0", "a")], "0 == x", "x");
+        test_rewrite(&[("a ^ 0", "1")], "0 ^ x", "0 ^ x");
+    }
+
+    #[test]
+    fn associative_matching() {
+        test_rewrite(&[("a + 0", "a")], "x + 0 + y", "x + y");
+        test_rewrite(&[("a * 0", "0")], "x * (y * 0)", "0");
+        test_rewrite(
+            &[("sin(a + b)", "sin(a) * cos(b) + cos(a) * sin(b)")],
+            "sin(x + y)",
+            "sin(x) * cos(y) + cos(x) * sin(y)",
+        );
+        test_rewrite(
+            &[("cos(a + b)", "log(a, b)")],
+            "cos(x + y + z)",
+            "log(x, y + z)",
+        );
+    }
+
+    #[test]
+    fn repeated_variables() {
+        let rules = [("a + a", "2 * a"), ("a * (b / a)", "b")];
+        test_rewrite(&rules, "sin(x) + sin(x)", "2 * sin(x)");
+        test_rewrite(&rules, "x + y", "x + y");
+        test_rewrite(&rules, "y * x * (z / x)", "z * y");
+    }
+
+    #[test]
+    fn step_limit() {
+        assert_eq!(
+            rule_set(&[("a + b", "b + a")])
+                .with_step_limit(10)
+                .rewrite(&parse_pattern("x + y", 0).unwrap()),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::StepLimitReached { steps: 10 }
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(RuleSet::new(&[Rule {
+            input: "a +",
+            output: "a",
+            condition: None
+        }])
+        .is_err());
+        assert!(RuleSet::new(&[Rule {
+            input: "a",
+            output: "(a",
+            condition: None
+        }])
+        .is_err());
+    }
+
+    #[test]
+    fn conditions() {
+        let rules =
+            RuleSet::parse("a / a => 1 if a != 0\na - b => 0 if a == b\na ^ n => 1 if n < 0")
+                .unwrap();
+        let rewrite = |expr: &str| rules.rewrite(&parse_pattern(expr, 0).unwrap()).unwrap();
+
+        assert_eq!(rewrite("x / x"), parse_pattern("1", 0).unwrap());
+        assert_eq!(rewrite("0 / 0"), parse_pattern("0 / 0", 0).unwrap());
+        assert_eq!(rewrite("(x + 1) - (1 + x)"), parse_pattern("0", 0).unwrap());
+        assert_eq!(rewrite("x - y"), parse_pattern("x - y", 0).unwrap());
+        assert_eq!(rewrite("x ^ (1 - 3)"), parse_pattern("1", 0).unwrap());
+        assert_eq!(rewrite("x ^ 2"), parse_pattern("x ^ 2", 0).unwrap());
+        assert_eq!(rewrite("x ^ y"), parse_pattern("x ^ y", 0).unwrap());
+    }
+
+    #[test]
+    fn rule_files() {
+        let rules =
+            RuleSet::parse("# comment\n\na * 0 => 0\n  # indented comment\na * 1 => a\n").unwrap();
+        assert_eq!(rules.rules.len(), 2);
+        assert_eq!(rules.rules[1].lhs, parse_pattern("a * 1", 0).unwrap());
+        assert_eq!(rules.rules[1].condition, None);
+
+        assert_eq!(
+            RuleSet::parse("a * 0 => 0\na * 1 => b\nno arrow\na => a if b == 0\na => (a"),
+            Err(vec![
+                (
+                    1,
+                    CASError {
+                        line_pos: 9,
+                        kind: CASErrorKind::UnboundPatternVariable {
+                            name: String::from("b")
+                        }
+                    }
+                ),
+                (
+                    2,
+                    CASError {
+                        line_pos: 7,
+                        kind: CASErrorKind::MalformedRule
+                    }
+                ),
+                (
+                    3,
+                    CASError {
+                        line_pos: 10,
+                        kind: CASErrorKind::UnboundPatternVariable {
+                            name: String::from("b")
+                        }
+                    }
+                ),
+                (
+                    4,
+                    CASError {
+                        line_pos: 4,
+                        kind: CASErrorKind::MismatchedParentheses
+                    }
+                ),
+            ])
+        );
+    }
+}
@@ -0,0 +1,1355 @@
+#[cfg(test)]
+
+mod test {
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+
+    use crate::parser::statement::{parse_statement, Statement};
+    use crate::parser::trees::Tree;
+    use crate::parser::vars::VarTable;
+    use crate::scanner::tokenize;
+    use crate::types::cas_error::{CASError, CASErrorKind};
+    use crate::types::cas_num::{CASComplex, CASNum, CASRational, Number};
+    use crate::types::symbol::{
+        constant::{self, ResConst},
+        function::{Func, ResFun},
+        operator::Operator::*,
+        Symbol,
+        SymbolType::*,
+    };
+
+    fn run(code: &str) -> Result<Tree<Symbol>, CASError> {
+        match parse_statement(tokenize(code).unwrap(), &HashMap::new())? {
+            Statement::Expression(tree) => Ok(tree),
+            Statement::Assignment { .. } => panic!("{} is not an expression", code),
+        }
+    }
+
+    fn test_derivative(expr: &str, wrt: &str, desired_result: &str) {
+        let der = run(&format!("der({}, {})", expr, wrt)).unwrap();
+        assert_eq!(der, run(desired_result).unwrap(), "d/d{} {}", wrt, expr);
+    }
+
+    fn simplified(code: &str) -> Tree<Symbol> {
+        let mut tree = run(code).unwrap();
+        tree.simplify();
+        let mut again = tree.clone();
+        again.simplify();
+        assert_eq!(tree, again, "simplifying {} twice changed it", code);
+        tree
+    }
+
+    fn test_equivalent(lhs: &str, rhs: &str) {
+        assert_eq!(simplified(lhs), simplified(rhs), "{} = {}", lhs, rhs);
+    }
+
+    fn test_integral(expr: &str, desired_result: &str) {
+        let mut integral = run(&format!("sym_int({}, x)", expr)).unwrap();
+        integral.simplify();
+        assert_eq!(
+            integral,
+            simplified(&format!("{} + C", desired_result)),
+            "∫ {} dx",
+            expr
+        );
+    }
+
+    fn test_unevaluated(expr: &str) {
+        let integral = run(&format!("sym_int({}, x)", expr)).unwrap();
+        assert_eq!(
+            integral.root.0.borrow().data.symbol_type,
+            Function(Func::ResFun {
+                fun: ResFun::SymInt,
+                num_args: 2
+            }),
+            "∫ {} dx",
+            expr
+        );
+    }
+
+    #[test]
+    fn polynomials() {
+        test_derivative("3", "x", "0");
+        test_derivative("x", "x", "1");
+        test_derivative("y", "x", "0");
+        test_derivative("x^2", "x", "2 * x");
+        test_derivative("x^3 + x", "x", "3 * x^2 + 1");
+        test_derivative("x^2 - y * x", "x", "2 * x - y");
+        test_derivative("x^2 * y", "y", "x^2");
+        test_derivative("-x", "x", "-1");
+    }
+
+    #[test]
+    fn product_and_quotient() {
+        test_derivative("x * sin(x)", "x", "sin(x) + x * cos(x)");
+        test_derivative("x / y", "x", "1 / y");
+        test_derivative("1 / x", "x", "-1 / x^2");
+        test_derivative("x / (x + 1)", "x", "(x + 1 - x) / (x + 1)^2");
+    }
+
+    #[test]
+    fn powers() {
+        test_derivative("2^x", "x", "2^x * ln(2)");
+        test_derivative("x^x", "x", "x^x * (ln(x) + x / x)");
+        test_derivative("x^y", "y", "x^y * ln(x)");
+    }
+
+    #[test]
+    fn chain_rule() {
+        test_derivative("sin(x^2)", "x", "cos(x^2) * (2 * x)");
+        test_derivative("ln(cos(x))", "x", "1 / cos(x) * -sin(x)");
+        test_derivative("sqrt(3 * x)", "x", "1 / (2 * sqrt(3 * x)) * 3");
+        test_derivative("log(x, 2)", "x", "1 / x / ln(2)");
+    }
+
+    #[test]
+    fn reserved_functions() {
+        test_derivative("cbrt(x)", "x", "1 / (3 * cbrt(x)^2)");
+        test_derivative("log2(x)", "x", "1 / (x * ln(2))");
+        test_derivative("log10(x)", "x", "1 / (x * ln(10))");
+        test_derivative("tan(x)", "x", "sec(x)^2");
+        test_derivative("csc(x)", "x", "-(csc(x) * cot(x))");
+        test_derivative("sec(x)", "x", "sec(x) * tan(x)");
+        test_derivative("cot(x)", "x", "-(csc(x)^2)");
+        test_derivative("asin(x)", "x", "1 / sqrt(1 - x^2)");
+        test_derivative("acos(x)", "x", "-1 / sqrt(1 - x^2)");
+        test_derivative("atan(x)", "x", "1 / (1 + x^2)");
+        test_derivative("acsc(x)", "x", "-1 / (x^2 * sqrt(1 - 1 / x^2))");
+        test_derivative("asec(x)", "x", "1 / (x^2 * sqrt(1 - 1 / x^2))");
+        test_derivative("acot(x)", "x", "-1 / (1 + x^2)");
+    }
+
+    #[test]
+    fn higher_order() {
+        test_derivative("x^3", "x, 2", "3 * (2 * x)");
+        test_derivative("x^3", "x, 0", "x^3");
+        test_derivative("sin(x)", "x, 4", "sin(x)");
+        test_derivative("x^2 * y", "x, y", "2 * x");
+        test_derivative("x^2 * y^2", "x, 2, y", "2 * (2 * y)");
+        test_derivative("x * y", "y, x", "1");
+
+        //like terms are collected between orders, so the result doesn't double in size with every order
+        test_equivalent(
+            "der(x * sin(x) * cos(x), x, 12)",
+            "4096 * x * sin(x) * cos(x) - 12288 * cos(x)^2 + 12288 * sin(x)^2",
+        );
+    }
+
+    #[test]
+    fn cached_derivatives_are_copies() {
+        let mut differentiator = crate::algebra::derivative::Differentiator::default();
+        let tree = run("sin(x ^ 2)").unwrap();
+        let first = differentiator.derivative(&tree.root, "x").unwrap();
+        let expected = first.deep_clone();
+
+        //changing a result in place doesn't change the one the cache gives next time
+        first.0.borrow_mut().children.clear();
+        let second = differentiator.derivative(&tree.root, "x").unwrap();
+        assert_eq!(second, expected);
+    }
+
+    #[test]
+    fn derivative_errors() {
+        assert_eq!(
+            run("der(x^2, 3)"),
+            Err(CASError {
+                line_pos: 9,
+                kind: CASErrorKind::ExpectedVariable {
+                    found: String::from("3")
+                }
+            })
+        );
+        assert_eq!(
+            run("der(x^2, x, 2, 3)"),
+            Err(CASError {
+                line_pos: 15,
+                kind: CASErrorKind::ExpectedVariable {
+                    found: String::from("3")
+                }
+            })
+        );
+        assert!(matches!(
+            run("der(x^2, x, 1.5)"),
+            Err(CASError {
+                line_pos: 14,
+                kind: CASErrorKind::InvalidArgument { .. },
+            })
+        ));
+        assert!(matches!(
+            run("der(x^2, x, y^2)"),
+            Err(CASError {
+                kind: CASErrorKind::InvalidArgument { .. },
+                ..
+            })
+        ));
+        assert!(matches!(
+            run("der(x < 2, x)"),
+            Err(CASError {
+                kind: CASErrorKind::CannotDifferentiate { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn symbol_ordering() {
+        let mut symbols = vec![
+            Operator(Add),
+            Variable {
+                name: String::from("y"),
+            },
+            Operator(Exp),
+            Function(Func::ResFun {
+                fun: ResFun::Sin,
+                num_args: 1,
+            }),
+            Variable {
+                name: String::from("x"),
+            },
+            Const(constant::Const::ResConst(ResConst::Pi)),
+            Num {
+                value: Number::from(2),
+            },
+            Operator(Mult),
+            Num {
+                value: Number::from(-3),
+            },
+        ];
+        symbols.sort_by(|lhs, rhs| lhs.partial_cmp(rhs).unwrap());
+
+        assert_eq!(
+            symbols,
+            vec![
+                Num {
+                    value: Number::from(-3),
+                },
+                Num {
+                    value: Number::from(2),
+                },
+                Const(constant::Const::ResConst(ResConst::Pi)),
+                Function(Func::ResFun {
+                    fun: ResFun::Sin,
+                    num_args: 1,
+                }),
+                Variable {
+                    name: String::from("x"),
+                },
+                Variable {
+                    name: String::from("y"),
+                },
+                Operator(Add),
+                Operator(Mult),
+                Operator(Exp),
+            ]
+        );
+        assert_eq!(
+            Operator(Add).partial_cmp(&Operator(Sub)),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn canonical_order() {
+        test_equivalent("x + y", "y + x");
+        test_equivalent("(x + y) + z", "x + (z + y)");
+        test_equivalent("x * y * z", "z * (y * x)");
+        test_equivalent("sin(x + y)", "sin(y + x)");
+        test_equivalent("3 * x ^ 2 + 2 * x + 5", "5 + x * 2 + x ^ 2 * 3");
+
+        let tree = simplified("1 + y * 3 * x + x ^ 2");
+        let root = tree.root.0.borrow();
+        assert_eq!(root.data.symbol_type, Operator(Add));
+        assert_eq!(root.children.len(), 3);
+        assert_eq!(root.children[0], simplified("x ^ 2").root);
+        assert_eq!(root.children[2], simplified("1").root);
+
+        let product = root.children[1].0.borrow();
+        assert_eq!(product.data.symbol_type, Operator(Mult));
+        assert_eq!(
+            product
+                .children
+                .iter()
+                .map(|child| child.0.borrow().data.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                Number::from(3).to_string(),
+                String::from("x"),
+                String::from("y")
+            ]
+        );
+    }
+
+    #[test]
+    fn constant_folding() {
+        test_equivalent("2 + 3 * 4", "14");
+        test_equivalent("2 ^ 10", "1024");
+        test_equivalent("x * 0 + 1", "1");
+        test_equivalent("1 ^ x", "1");
+        test_equivalent("x ^ 0", "1");
+        test_equivalent("x ^ 1 * 1 + 0", "x");
+        test_equivalent("2 * x * 3", "6 * x");
+    }
+
+    #[test]
+    fn like_terms() {
+        test_equivalent("x + x + x", "3 * x");
+        test_equivalent("2 * x + 3 * x", "5 * x");
+        test_equivalent("x - x", "0");
+        test_equivalent("x * y - y * x", "0");
+        test_equivalent("x * x ^ 2", "x ^ 3");
+        test_equivalent("x / x", "1");
+        test_equivalent("(x ^ 2) ^ 3", "x ^ 6");
+        test_equivalent("(2 * x * y) ^ 2", "4 * y ^ 2 * x ^ 2");
+        test_equivalent("3 * (x + 1) - 3", "3 * x");
+        test_equivalent("x ^ 2 * 3 + 2 * x + 5 - x", "x + 3 * x ^ 2 + 5");
+        test_equivalent("-(x - y)", "y - x");
+
+        assert_ne!(simplified("x ^ 2"), simplified("2 ^ x"));
+        assert_ne!(simplified("x + y"), simplified("x * y"));
+        assert_ne!(simplified("(x ^ y) ^ 2"), simplified("x ^ y ^ 2"));
+    }
+
+    #[test]
+    fn powers_of_i() {
+        test_equivalent("i * i", "-1");
+        test_equivalent("i ^ 3", "-1 * i");
+        test_equivalent("i ^ 4 * x", "x");
+        test_equivalent("i ^ -1", "-1 * i");
+        test_equivalent("sqrt(-1) * x", "i * x");
+        test_equivalent("i ^ 2 + 1", "0");
+    }
+
+    #[test]
+    fn fractions() {
+        test_equivalent("4 / 6", "2 / 3");
+        test_equivalent("2 * x / 2", "x");
+        test_equivalent("6 / 3", "2");
+        test_equivalent("x / 4 * 2", "x / 2");
+        test_equivalent("(-1) ^ -1", "-1");
+        test_equivalent("2 ^ -2", "1 / 4");
+        test_equivalent("1 / -2", "-1 / 2");
+    }
+
+    #[test]
+    fn exact_rationals() {
+        test_equivalent("1 / 3 + 1 / 6", "1 / 2");
+        test_equivalent("(2 / 3) ^ -2", "9 / 4");
+        test_equivalent("x / 4 + x / 4", "x / 2");
+        test_equivalent("3 ^ -40 * 3 ^ 41", "3");
+        test_equivalent("sqrt(4 / 9) * x", "2 / 3 * x");
+        test_equivalent("sqrt(2 ^ 130)", "2 ^ 65");
+        assert_ne!(simplified("sqrt(8)"), simplified("2 * sqrt(2)"));
+
+        let tree = simplified("2 / 6 - 1");
+        assert_eq!(
+            tree.root.0.borrow().data.symbol_type,
+            Num {
+                value: Number::from(CASRational::from(-2) * CASRational::from(3).recip().unwrap())
+            }
+        );
+        assert_eq!(tree.to_string(), simplified("-2 / 3").to_string());
+        assert_eq!(tree.root.0.borrow().data.to_string(), "-2/3");
+
+        //an inexact number makes the whole result inexact
+        let tree = simplified("1 / 3 + 0.5");
+        assert!(matches!(
+            tree.root.0.borrow().data.symbol_type,
+            Num {
+                value: Number::Inexact(_)
+            }
+        ));
+        assert_ne!(simplified("0.5 ^ -1"), simplified("2"));
+    }
+
+    #[test]
+    fn integral_polynomials() {
+        test_integral("3", "3 * x");
+        test_integral("y", "y * x");
+        test_integral("x", "x ^ 2 / 2");
+        test_integral("x ^ 2", "x ^ 3 / 3");
+        test_integral("3 * x ^ 2 + 2 * x + 1", "x ^ 3 + x ^ 2 + x");
+        test_integral("(x + 1) ^ 2", "(x + 1) ^ 3 / 3");
+        test_integral("1 / x ^ 2", "-1 / x");
+        test_integral("sqrt(x)", "2 / 3 * x ^ (3 / 2)");
+    }
+
+    #[test]
+    fn integral_table() {
+        test_integral("1 / x", "ln(x)");
+        test_integral("e ^ x", "e ^ x");
+        test_integral("2 ^ x", "2 ^ x / ln(2)");
+        test_integral("sin(x)", "-cos(x)");
+        test_integral("cos(x)", "sin(x)");
+        test_integral("ln(x)", "x * ln(x) - x");
+        test_integral("cos(2 * x + 1)", "sin(2 * x + 1) / 2");
+        test_integral("e ^ (3 * x)", "e ^ (3 * x) / 3");
+        test_integral("1 / (2 * x + 1)", "ln(2 * x + 1) / 2");
+        test_integral("atan(x)", "x * atan(x) - ln(1 + x ^ 2) / 2");
+    }
+
+    #[test]
+    fn integral_substitution() {
+        test_integral("2 * x * cos(x ^ 2)", "sin(x ^ 2)");
+        test_integral("x * e ^ (x ^ 2)", "e ^ (x ^ 2) / 2");
+        test_integral("sin(x) * cos(x)", "-1 / 2 * cos(x) ^ 2");
+        test_integral("cos(x) / sin(x)", "ln(sin(x))");
+    }
+
+    #[test]
+    fn integral_by_parts() {
+        test_integral("x * e ^ x", "x * e ^ x - e ^ x");
+        test_integral("x * sin(x)", "sin(x) - x * cos(x)");
+        test_integral("x * ln(x)", "x ^ 2 * ln(x) / 2 - x ^ 2 / 4");
+    }
+
+    #[test]
+    fn integral_rational() {
+        test_integral("1 / (x ^ 2 - 1)", "ln(x - 1) / 2 - ln(x + 1) / 2");
+        test_integral("1 / (x ^ 2 + 1)", "atan(x)");
+        test_integral(
+            "(x ^ 3 + 1) / (x - 1)",
+            "x ^ 3 / 3 + x ^ 2 / 2 + x + 2 * ln(x - 1)",
+        );
+        test_integral("x / (x ^ 2 + 4)", "ln(x ^ 2 + 4) / 2");
+    }
+
+    #[test]
+    fn integral_unevaluated() {
+        test_unevaluated("sin(x ^ 2)");
+        test_unevaluated("e ^ x * sin(x)");
+        test_unevaluated("x ^ x");
+        test_derivative("sym_int(sin(x ^ 2), x)", "x", "sin(x ^ 2)");
+
+        assert_eq!(
+            run("sym_int(x, 2)"),
+            Err(CASError {
+                line_pos: 11,
+                kind: CASErrorKind::ExpectedVariable {
+                    found: String::from("2")
+                }
+            })
+        );
+    }
+
+    fn test_definite_integral(expr: &str, lower: &str, upper: &str, desired_result: &str) {
+        let mut integral = run(&format!("def_int({}, x, {}, {})", expr, lower, upper)).unwrap();
+        integral.simplify();
+        assert_eq!(
+            integral,
+            simplified(desired_result),
+            "∫ {} dx from {} to {}",
+            expr,
+            lower,
+            upper
+        );
+    }
+
+    fn numeric_value(tree: &Tree<Symbol>) -> f64 {
+        match tree.root.0.borrow().data.symbol_type.clone() {
+            Num { value } => value.to_cas_num(2).into(),
+            other => panic!("{:?} is not a number", other),
+        }
+    }
+
+    #[test]
+    fn definite_integral_exact() {
+        test_definite_integral("x ^ 2", "0", "1", "1 / 3");
+        test_definite_integral("cos(x)", "0", "π", "0");
+        test_definite_integral("1 / x ^ 2", "1", "∞", "1");
+        test_definite_integral("e ^ -x", "0", "∞", "1");
+        test_definite_integral("x * e ^ -x", "0", "∞", "1");
+        test_definite_integral("1 / (x ^ 2 + 1)", "-∞", "∞", "π");
+        test_definite_integral("1 / x", "1", "∞", "∞");
+        test_definite_integral("sqrt(x)", "0", "1", "2 / 3");
+
+        //poles between the bounds make the integral diverge, even where the antiderivative has a value there
+        test_definite_integral("1 / x ^ 2", "-1", "1", "∞");
+        for code in ["def_int(-1 / x ^ 2, x, -1, 1)", "def_int(1 / x, x, 1, 0)"] {
+            assert_eq!(
+                run(code).unwrap().root.0.borrow().data.symbol_type,
+                Const(constant::Const::ResConst(ResConst::NegInf)),
+                "{}",
+                code
+            );
+        }
+        test_definite_integral("x ^ (-1 / 2)", "0", "1", "2");
+        test_definite_integral("1 / x ^ 2", "1", "2", "1 / 2");
+        for (expr, point) in [("1 / x", "x = 0"), ("1 / (x ^ 2 - 1)", "x = 1")] {
+            assert!(
+                matches!(
+                    run(&format!("def_int({}, x, -1 / 2, 2)", expr)),
+                    Err(CASError {
+                        kind: CASErrorKind::DivergentIntegral { point: ref pole },
+                        ..
+                    }) if pole == point
+                ),
+                "∫ {} dx from -1 / 2 to 2",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn definite_integral_numeric() {
+        //the value is given as the interval its error estimate allows, rounded outward to the default digits
+        let integral = run("def_int(1 / (x ^ 3 + 2), x, 0, 1)").unwrap();
+        let root = integral.root.0.borrow();
+        assert_eq!(
+            root.data.symbol_type,
+            Function(Func::ResFun {
+                fun: ResFun::Interval,
+                num_args: 2
+            })
+        );
+        let ends: Vec<String> = root
+            .children
+            .iter()
+            .map(|end| end.0.borrow().data.to_string())
+            .collect();
+        assert_eq!(
+            ends,
+            [
+                "0.450822129263754835907012857531",
+                "0.450822129263754835907012857532"
+            ]
+        );
+        let value = numeric_value(&Tree {
+            root: root.children[0].clone(),
+        });
+        assert!((value - 0.450_822_129_263_754_8).abs() < 1e-12, "{}", value);
+
+        let integrand = run("1 / (x ^ 3 + 2)").unwrap();
+        let integral = crate::algebra::integral::definite_integral(
+            &integrand.root,
+            "x",
+            &run("0").unwrap().root,
+            &run("1").unwrap().root,
+            30,
+        )
+        .unwrap();
+        let error: f64 = integral.error.unwrap().into();
+        assert!(error < 1e-20, "{}", error);
+    }
+
+    fn interval_ends(code: &str) -> Vec<String> {
+        let tree = run(code).unwrap();
+        let root = tree.root.0.borrow();
+        root.children
+            .iter()
+            .map(|end| end.0.borrow().data.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn improper_integral_numeric() {
+        // √π / 2 and √π
+        assert_eq!(
+            interval_ends("def_int(e ^ (0 - x ^ 2), x, 0, ∞)"),
+            [
+                "0.88622692545275801364908374167",
+                "0.886226925452758013649083741671"
+            ]
+        );
+        assert_eq!(
+            interval_ends("def_int(e ^ (0 - x ^ 2), x, -∞, ∞)"),
+            [
+                "1.77245385090551602729816748334",
+                "1.77245385090551602729816748335"
+            ]
+        );
+
+        //an antiderivative that oscillates forever has no limit, so there's no value to approximate
+        for expr in ["sin(x)", "cos(x)"] {
+            assert_eq!(
+                run(&format!("def_int({}, x, 0, ∞)", expr)),
+                Err(CASError {
+                    line_pos: 10,
+                    kind: CASErrorKind::NoLimit {
+                        point: String::from("∞")
+                    }
+                }),
+                "∫ {} dx from 0 to ∞",
+                expr
+            );
+        }
+        assert!(matches!(
+            run("def_int(x, x, -∞, ∞)"),
+            Err(CASError {
+                kind: CASErrorKind::DivergentIntegral { .. },
+                ..
+            })
+        ));
+        //no antiderivative, and too slowly decaying to reach the default digits within the sampling limit
+        assert!(matches!(
+            run("def_int(sin(x) / x, x, 1, ∞)"),
+            Err(CASError {
+                kind: CASErrorKind::IntegralDidNotConverge { digits: 30 },
+                ..
+            })
+        ));
+    }
+
+    fn calc(code: &str) -> CASNum {
+        let tree = run(&format!("calc({})", code)).unwrap();
+        let value = match tree.root.0.borrow().data.symbol_type.clone() {
+            Num {
+                value: Number::Inexact(value),
+            } => value,
+            other => panic!("calc({}) gave {:?}", code, other),
+        };
+        value
+    }
+
+    #[test]
+    fn calc_operators() {
+        assert_eq!(calc("2 * 3 + 1"), CASNum::from(7));
+        assert_eq!(calc("(1 + 2) ^ 10 - 9"), CASNum::from(59040));
+        assert_eq!(calc("1 / 4 - 2 ^ -3"), CASNum::from(0.125));
+        assert_eq!(calc("-(3 - 5) * 4"), CASNum::from(8));
+        assert_eq!(calc("2 < 3"), CASNum::from(1));
+        assert_eq!(calc("2 >= 3"), CASNum::from(0));
+
+        let third = f64::from(calc("1 / 3"));
+        assert!((third - 1.0 / 3.0).abs() < 1e-15, "{}", third);
+
+        //results are rounded to the nearest, so 2 / 3 is the truncated quotient rounded up
+        let context = crate::algebra::numeric::context_for_digits(50);
+        let truncated = CASNum::from(2).quotient(&CASNum::from(3), context.num_limbs);
+        assert!(calc("2 / 3, 50") > truncated);
+        assert_eq!(
+            calc("2 / 3, 50").to_string(),
+            format!("0.{}7", "6".repeat(49))
+        );
+    }
+
+    #[test]
+    fn calc_functions() {
+        let tolerance = CASNum::from(1e-25);
+        for (code, expected) in [
+            ("sin(1) ^ 2 + cos(1) ^ 2", 1.0),
+            ("atan(∞) * 2 - acos(-1)", 0.0),
+            ("ln(2 ^ 10) / ln(2)", 10.0),
+            ("2 ^ 0.5 * sqrt(2)", 2.0),
+            ("tan(0.5) * cot(0.5)", 1.0),
+        ] {
+            let error = (calc(code) - CASNum::from(expected)).abs();
+            assert!(error < tolerance, "calc({}) is off by {:?}", code, error);
+        }
+
+        assert_eq!(calc("log(8, 2)"), CASNum::from(3));
+        assert_eq!(calc("sqrt(16) + cbrt(-8)"), CASNum::from(2));
+        let value = calc("sin(∞)");
+        assert_ne!(value, value);
+    }
+
+    fn calc_complex(code: &str) -> CASComplex {
+        let tree = run(&format!("calc({})", code)).unwrap();
+        let context = crate::algebra::numeric::context_for_digits(30);
+        crate::algebra::numeric::evaluate_complex(&tree.root, &HashMap::new(), context).unwrap()
+    }
+
+    #[test]
+    fn calc_complex_values() {
+        let tolerance = CASNum::from(1e-25);
+        for (code, re, im) in [
+            ("sqrt(-4)", 0.0, 2.0),
+            ("(1 + 2 * i) * (3 - i)", 5.0, 5.0),
+            ("(5 + 5 * i) / (3 - i)", 1.0, 2.0),
+            ("i ^ 2", -1.0, 0.0),
+            ("ln(-1) / π", 0.0, 1.0),
+            ("e ^ (i * π)", -1.0, 0.0),
+            ("cos(i) - (e + 1 / e) / 2", 0.0, 0.0),
+            ("sin(asin(2))", 2.0, 0.0),
+        ] {
+            let value = calc_complex(code);
+            let error = (value.re.clone() - CASNum::from(re)).abs()
+                + (value.im.clone() - CASNum::from(im)).abs();
+            assert!(error < tolerance, "calc({}) is {}", code, value);
+        }
+        //parts that are only rounding error are dropped
+        assert_eq!(calc("e ^ (i * π)"), CASNum::from(-1));
+        assert_eq!(calc_complex("e ^ (i * π / 2)"), CASComplex::i());
+        let small = f64::from(calc_complex("1 + 10 ^ -20 * i").im);
+        assert!((small - 1e-20).abs() < 1e-35, "{}", small);
+        //real arguments keep their real values where there are any
+        assert_eq!(calc("cbrt(-8)"), CASNum::from(-2));
+        assert_eq!(calc("sqrt(-1) ^ 2"), CASNum::from(-1));
+    }
+
+    #[test]
+    fn calc_constants() {
+        let tolerance = CASNum::from(1e-25);
+        for (code, expected) in [
+            ("ln(e ^ 2)", 2.0),
+            ("ϕ ^ 2 - ϕ - 1", 0.0),
+            ("sin(π / 6)", 0.5),
+            ("tau / pi", 2.0),
+            ("τ - 2 * π", 0.0),
+        ] {
+            let error = (calc(code) - CASNum::from(expected)).abs();
+            assert!(error < tolerance, "calc({}) is off by {:?}", code, error);
+        }
+
+        //constants are found to the precision asked for, not just to f64 precision
+        let error = (calc("π, 60") - calc("4 * atan(1), 60")).abs();
+        assert!(error < CASNum::from(1e-55), "π is off by {:?}", error);
+    }
+
+    #[test]
+    fn calc_infinities() {
+        assert_eq!(calc("1 / 0"), calc("∞"));
+        assert_eq!(calc("-1 / 0"), calc("-∞"));
+        assert_eq!(calc("∞ + 1"), calc("∞"));
+        assert_eq!(calc("2 ^ ∞"), calc("∞"));
+        assert_eq!(calc("0.5 ^ ∞"), CASNum::from(0));
+        assert_eq!(calc("2 ^ -∞"), CASNum::from(0));
+
+        //indeterminate values aren't equal to anything, not even themselves
+        for code in ["∞ - ∞", "0 / 0", "1 ^ ∞", "(0 / 0) < 1"] {
+            let value = calc(code);
+            assert_ne!(value, value, "calc({})", code);
+        }
+    }
+
+    #[test]
+    fn calc_precision() {
+        let default = calc("1 / 3");
+        let precise = calc("1 / 3, 60");
+        let difference = f64::from(precise.clone() - default);
+        assert!(
+            difference != 0.0 && difference.abs() < 1e-30,
+            "{}",
+            difference
+        );
+
+        //the third is rounded to 60 digits, so three of it are off by 3 in the 61st digit
+        let error = f64::from(precise * CASNum::from(3) - CASNum::from(1));
+        assert!(error.abs() < 2e-60, "{}", error);
+    }
+
+    #[test]
+    fn calc_digits() {
+        //results are written with the significant digits asked for, or 30 by default
+        for (code, written) in [
+            ("π, 3", "3.14"),
+            ("1 / 3, 5", "0.33333"),
+            (
+                "π, 50",
+                "3.1415926535897932384626433832795028841971693993751",
+            ),
+            ("1 / 3", "0.333333333333333333333333333333"),
+            ("-2 / 3, 4", "-0.6667"),
+            ("10 ^ 30 / 7, 5", "1.4286e29"),
+            ("2.5, 5", "2.5"),
+        ] {
+            assert_eq!(calc(code).to_string(), written, "calc({})", code);
+        }
+    }
+
+    #[test]
+    fn calc_variables() {
+        let mut var_table: VarTable = HashMap::new();
+        for code in ["x = 3", "f(y) = y ^ 2 + x"] {
+            match parse_statement(tokenize(code).unwrap(), &var_table).unwrap() {
+                Statement::Assignment { name, var } => var_table.insert(name, var),
+                Statement::Expression(_) => panic!("{} is not an assignment", code),
+            };
+        }
+
+        let tree = match parse_statement(tokenize("calc(f(x) * 2)").unwrap(), &var_table) {
+            Ok(Statement::Expression(tree)) => tree,
+            other => panic!("calc(f(x) * 2) gave {:?}", other),
+        };
+        assert_eq!(
+            tree.root.0.borrow().data.symbol_type,
+            Num {
+                value: Number::from(24)
+            }
+        );
+    }
+
+    #[test]
+    fn calc_errors() {
+        assert_eq!(
+            run("calc(y + 1)"),
+            Err(CASError {
+                line_pos: 5,
+                kind: CASErrorKind::CannotEvaluate {
+                    symbol: String::from("y")
+                }
+            })
+        );
+        assert_eq!(
+            run("calc(1, 0)"),
+            Err(CASError {
+                line_pos: 8,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("0"),
+                    func_name: ResFun::Calc.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("calc(1, 2, 3)"),
+            Err(CASError {
+                line_pos: 3,
+                kind: CASErrorKind::WrongNumberOfArgs {
+                    args_given: 3,
+                    args_needed: 1,
+                    func_name: ResFun::Calc.to_string()
+                }
+            })
+        );
+    }
+
+    fn calc_interval(code: &str) -> (CASNum, CASNum) {
+        let tree = run(&format!("calc({})", code)).unwrap();
+        let root = tree.root.0.borrow();
+        assert_eq!(
+            root.data.symbol_type,
+            Function(Func::ResFun {
+                fun: ResFun::Interval,
+                num_args: 2
+            }),
+            "calc({})",
+            code
+        );
+        let end = |idx: usize| match root.children[idx].0.borrow().data.symbol_type.clone() {
+            Num {
+                value: Number::Inexact(value),
+            } => value,
+            other => panic!("calc({}) has an end {:?}", code, other),
+        };
+        (end(0), end(1))
+    }
+
+    #[test]
+    fn calc_intervals() {
+        let (lo, hi) = calc_interval("interval(1, 2) * 3 - 1");
+        assert_eq!((lo, hi), (CASNum::from(2), CASNum::from(5)));
+        let (lo, hi) = calc_interval("fact(5) * interval(1)");
+        assert_eq!((lo, hi), (CASNum::from(120), CASNum::from(120)));
+
+        //the exact value is always inside, and the ends are within a unit or two of the last digit asked for
+        let pi_squared = CASNum::pi(10) * CASNum::pi(10);
+        let (lo, hi) = calc_interval("interval(π) ^ 2, 50");
+        assert!(lo < pi_squared && pi_squared < hi);
+        assert!(hi - lo < CASNum::from(3e-49));
+
+        //the ends are rounded outward to the digits asked for, so the written bounds still hold the exact value
+        for (code, ends, exact) in [
+            (
+                "sqrt(interval(2, 2)), 20",
+                ("1.4142135623730950488", "1.4142135623730950489"),
+                CASNum::from(2).sqrt(10),
+            ),
+            (
+                "interval(2, 2) / 3, 20",
+                ("0.66666666666666666666", "0.66666666666666666667"),
+                CASNum::from(2).quotient(&CASNum::from(3), 10),
+            ),
+            (
+                "-interval(2, 2) / 3, 5",
+                ("-0.66667", "-0.66666"),
+                CASNum::from(-2).quotient(&CASNum::from(3), 10),
+            ),
+        ] {
+            let (lo, hi) = calc_interval(code);
+            assert_eq!((lo.to_string().as_str(), hi.to_string().as_str()), ends);
+            let written =
+                |end: &str| match run(end).unwrap().root.0.borrow().data.symbol_type.clone() {
+                    Num { value } => value.to_cas_num(10),
+                    other => panic!("{} gave {:?}", end, other),
+                };
+            assert!(
+                written(ends.0) < exact && exact < written(ends.1),
+                "calc({})",
+                code
+            );
+        }
+        let (lo, hi) = calc_interval("sin(interval(0, 2))");
+        assert_eq!(hi, CASNum::from(1));
+        assert!(lo <= CASNum::from(0));
+
+        //comparisons are 1 or 0 only if they hold everywhere or nowhere
+        let truth = |code: &str| {
+            let (lo, hi) = calc_interval(code);
+            (f64::from(lo), f64::from(hi))
+        };
+        assert_eq!(truth("interval(sqrt(2)) < 1.4143"), (1.0, 1.0));
+        assert_eq!(truth("interval(sqrt(2)) >= 1.4143"), (0.0, 0.0));
+        assert_eq!(truth("interval(1, 2) < 1.5"), (0.0, 1.0));
+        assert_eq!(truth("interval(3) == 3"), (1.0, 1.0));
+
+        assert_eq!(
+            run("calc(interval(1, 2) + i)"),
+            Err(CASError {
+                line_pos: 22,
+                kind: CASErrorKind::CannotEvaluate {
+                    symbol: ResConst::I.to_string()
+                }
+            })
+        );
+    }
+
+    fn integer_value(code: &str) -> Number {
+        match run(code).unwrap().root.0.borrow().data.symbol_type.clone() {
+            Num { value } => value,
+            other => panic!("{} gave {}", code, other),
+        }
+    }
+
+    /// The numbers in the vector or matrix `code` gives, as they are written, row by row.
+    fn written_entries(code: &str) -> Vec<String> {
+        fn collect(node: &crate::parser::trees::TreeNodeRef<Symbol>, entries: &mut Vec<String>) {
+            let node = node.0.borrow();
+            match &node.data.symbol_type {
+                Num { value } => entries.push(value.to_string()),
+                _ => node
+                    .children
+                    .iter()
+                    .for_each(|child| collect(child, entries)),
+            }
+        }
+        let mut entries = Vec::new();
+        collect(&run(code).unwrap().root, &mut entries);
+        entries
+    }
+
+    /// The primes and powers in the product `factor` gives.
+    fn factors(code: &str) -> Vec<(i64, i64)> {
+        let tree = run(code).unwrap();
+        let root = tree.root.0.borrow();
+        let factors = match root.data.symbol_type {
+            Operator(Mult) => root.children.clone(),
+            _ => vec![tree.root.clone()],
+        };
+        let value = |node: &crate::parser::trees::TreeNodeRef<Symbol>| match &node
+            .0
+            .borrow()
+            .data
+            .symbol_type
+        {
+            Num { value } => i64::try_from(value).unwrap(),
+            other => panic!("{} gave {} as a factor", code, other),
+        };
+        factors
+            .iter()
+            .map(|factor| match factor.0.borrow().data.symbol_type {
+                Operator(Exp) => {
+                    let children = &factor.0.borrow().children;
+                    (value(&children[0]), value(&children[1]))
+                }
+                _ => (value(factor), 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn integer_functions() {
+        assert_eq!(integer_value("gcd(12, 18)"), Number::from(6));
+        assert_eq!(integer_value("gcd(-4, 0)"), Number::from(4));
+        assert_eq!(integer_value("lcm(4, 6)"), Number::from(12));
+        assert_eq!(integer_value("mod(-7, 3)"), Number::from(2));
+        assert_eq!(integer_value("mod(2 + 1, 100, 7)"), Number::from(4));
+        assert_eq!(integer_value("fact(5)"), Number::from(120));
+        assert_eq!(integer_value("binom(10, 3)"), Number::from(120));
+        assert_eq!(integer_value("binom(3, 10)"), Number::from(0));
+        assert_eq!(integer_value("isprime(97)"), Number::from(1));
+        assert_eq!(integer_value("isprime(91)"), Number::from(0));
+
+        assert_eq!(factors("factor(360)"), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factors("factor(-7)"), [(-1, 1), (7, 1)]);
+        assert_eq!(factors("factor(1)"), [(1, 1)]);
+        //exact integers keep every digit, so 25! / 23! can be factored
+        assert_eq!(
+            factors("factor(fact(25) / fact(23))"),
+            [(2, 3), (3, 1), (5, 2)]
+        );
+        //calls with variables are left as they are
+        assert_eq!(run("gcd(x, 4)").unwrap(), run("gcd(x, 4)").unwrap());
+    }
+
+    #[test]
+    fn integer_function_errors() {
+        assert_eq!(
+            run("fact(2.5)"),
+            Err(CASError {
+                line_pos: 7,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("2.5"),
+                    func_name: ResFun::Fact.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("mod(5, 0)"),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("5, 0"),
+                    func_name: ResFun::Mod.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("factor(0)"),
+            Err(CASError {
+                line_pos: 5,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("0"),
+                    func_name: ResFun::Factor.to_string()
+                }
+            })
+        );
+    }
+
+    fn test_vector(code: &str, desired_result: &str) {
+        assert_eq!(run(code).unwrap(), run(desired_result).unwrap(), "{}", code);
+    }
+
+    #[test]
+    fn vector_arithmetic() {
+        test_vector("[1, 2, 3] + [4, 5, 6]", "[5, 7, 9]");
+        test_vector("[1, 2] - [x, 2]", "[1 - x, 0]");
+        test_vector("[1, 2] * [3, 4]", "[3, 8]");
+        test_vector("-[1, x]", "[-1, -x]");
+
+        //scalars apply to every element
+        test_vector("2 * [x, y]", "[2 * x, 2 * y]");
+        test_vector("[x, y] / 2", "[x / 2, y / 2]");
+        test_vector("[x, 3] ^ 2", "[x ^ 2, 3 ^ 2]");
+        test_vector("[1, 2] + x", "[1 + x, 2 + x]");
+
+        //vectors of vectors are combined all the way down
+        test_vector("[[1, 2], [3, 4]] + [[1, 1], [1, 1]]", "[[2, 3], [4, 5]]");
+        test_vector("[[1, 2], 3] * 2", "[[2, 4], 6]");
+    }
+
+    #[test]
+    fn vector_products() {
+        test_vector("dot([1, 2, 3], [4, 5, 6])", "32");
+        test_vector("dot([x, y], [y, x])", "x * y + y * x");
+        test_vector("cross([1, 0, 0], [0, 1, 0])", "[0, 0, 1]");
+        test_vector(
+            "cross([x, y, z], [1, 2, 3])",
+            "[y * 3 - z * 2, z - x * 3, x * 2 - y]",
+        );
+        test_vector("norm([3, 4])", "5");
+        test_vector("norm([3 / 5, 4 / 5])", "1");
+        test_vector("norm([1, 1])", "sqrt(2)");
+        assert_eq!(calc("norm([3, 4])"), CASNum::from(5));
+
+        let tree = run("calc([1 / 2, π])").unwrap();
+        let root = tree.root.0.borrow();
+        assert_eq!(root.data.symbol_type, Vector { dim: 2 });
+        assert_eq!(
+            root.children[0].0.borrow().data.symbol_type,
+            Num {
+                value: Number::from(CASNum::from(0.5))
+            }
+        );
+    }
+
+    #[test]
+    fn vector_errors() {
+        assert_eq!(
+            run("[1, 2] + [1, 2, 3]"),
+            Err(CASError {
+                line_pos: 7,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 2,
+                    found: 3
+                }
+            })
+        );
+        assert_eq!(
+            run("cross([1, 2], [3, 4])"),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 3,
+                    found: 2
+                }
+            })
+        );
+        assert_eq!(
+            run("dot(1, [1])"),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("1"),
+                    func_name: ResFun::Dot.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("[1, 2] < [3, 4]"),
+            Err(CASError {
+                line_pos: 7,
+                kind: CASErrorKind::TypeError
+            })
+        );
+    }
+
+    #[test]
+    fn vector_calculus() {
+        test_vector(
+            "grad(x ^ 2 * y, [x, y])",
+            "[der(x ^ 2 * y, x), der(x ^ 2 * y, y)]",
+        );
+        test_vector("grad(x * y, [y, x, z])", "[x, y, 0]");
+        test_vector("div([x, y, z], [x, y, z])", "3");
+        test_vector(
+            "div([x * y, sin(y)], [x, y])",
+            "der(x * y, x) + der(sin(y), y)",
+        );
+        test_vector("curl([-y, x, 0], [x, y, z])", "[0, 0, 2]");
+        test_vector(
+            "curl([0, 0, x * y], [x, y, z])",
+            "[der(x * y, y), -der(x * y, x), 0]",
+        );
+        test_vector(
+            "jacob([x * y, x + y, 3], [x, y])",
+            "[[der(x * y, x), der(x * y, y)], [1, 1], [0, 0]]",
+        );
+    }
+
+    #[test]
+    fn vector_calculus_errors() {
+        assert_eq!(
+            run("curl([x, y], [x, y])"),
+            Err(CASError {
+                line_pos: 3,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 3,
+                    found: 2
+                }
+            })
+        );
+        assert_eq!(
+            run("div([x, y, z], [x, y])"),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 2,
+                    found: 3
+                }
+            })
+        );
+        assert_eq!(
+            run("grad(x, y)"),
+            Err(CASError {
+                line_pos: 8,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("y"),
+                    func_name: ResFun::Grad.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("div(x, [x])"),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("x"),
+                    func_name: ResFun::Div.to_string()
+                }
+            })
+        );
+        assert_eq!(
+            run("grad(x, [x, 2])"),
+            Err(CASError {
+                line_pos: 12,
+                kind: CASErrorKind::ExpectedVariable {
+                    found: String::from("2")
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn matrix_products() {
+        test_vector(
+            "[[1, 2], [3, 4]] * [[5, 6], [7, 8]]",
+            "[[19, 22], [43, 50]]",
+        );
+        test_vector("[[1, 2], [3, 4]] * [1, 1]", "[3, 7]");
+        test_vector("[1, 1] * [[1, 2], [3, 4]]", "[4, 6]");
+        test_vector(
+            "[[x, 0], [0, y]] * [[a, b], [c, d]]",
+            "[[x * a, x * b], [y * c, y * d]]",
+        );
+        test_vector("2 * [[1, 2], [3, 4]]", "[[2, 4], [6, 8]]");
+        test_vector(
+            "transpose([[1, 2, 3], [4, 5, 6]])",
+            "[[1, 4], [2, 5], [3, 6]]",
+        );
+    }
+
+    #[test]
+    fn determinants() {
+        test_vector("det([[1, 2], [3, 4]])", "-2");
+        test_vector("det([[2, 0, 1], [1, 3, 2], [1, 1, 2]])", "6");
+        test_vector("det([[0, 1], [1, 0]])", "-1");
+        test_vector("det([[1, 2], [2, 4]])", "0");
+        assert_eq!(
+            run("det([[1 / 2, 1], [1, 3]])").unwrap(),
+            simplified("1 / 2")
+        );
+        assert_eq!(integer_value("det([[0.5, 1], [1, 3]])"), Number::from(0.5));
+        //inexact entries are rounded as they're eliminated, so the result is written at the default digits
+        for (code, written) in [
+            ("det([[0.1, 0.2], [0.3, 0.4]])", "-0.02"),
+            (
+                "det([[0.1, 0.2, 0.3], [0.4, 0.5, 0.7], [0.7, 0.8, 0.8]])",
+                "0.009",
+            ),
+            ("det(inv([[0.1, 0.2], [0.3, 0.4]]))", "-50"),
+        ] {
+            assert_eq!(integer_value(code).to_string(), written, "{}", code);
+        }
+
+        //symbolic determinants are multiplied out, with Bareiss' divisions cancelled
+        assert_eq!(
+            run("det([[a, b], [c, d]])").unwrap(),
+            simplified("a * d - b * c")
+        );
+        assert_eq!(
+            run("det([[a, b, c], [p, q, r], [u, v, w]])").unwrap(),
+            simplified("a * q * w - a * r * v - b * p * w + b * r * u + c * p * v - c * q * u")
+        );
+        assert_eq!(
+            run("det([[0, x, 1], [x, 0, 1], [1, 1, 0]])").unwrap(),
+            simplified("2 * x")
+        );
+        test_vector("det([[x, x], [1, 1]])", "0");
+        assert_eq!(
+            run("det([[a, b, 0, 0], [c, d, 0, 0], [0, 0, p, q], [0, 0, r, s]])").unwrap(),
+            simplified("a * d * p * s - a * d * q * r - b * c * p * s + b * c * q * r")
+        );
+        assert_eq!(
+            run("det([[a, 1, 1, 1], [1, b, 1, 1], [1, 1, c, 1], [1, 1, 1, d]])").unwrap(),
+            simplified("a * b * c * d - a * b - a * c - a * d - b * c - b * d - c * d + 2 * a + 2 * b + 2 * c + 2 * d - 3")
+        );
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(
+            run("inv([[1, 2], [3, 4]])").unwrap(),
+            simplified("[[-2, 1], [3 / 2, -1 / 2]]")
+        );
+        test_vector(
+            "[[2, 1], [1, 1]] * inv([[2, 1], [1, 1]])",
+            "[[1, 0], [0, 1]]",
+        );
+        assert_eq!(
+            run("inv([[a, 0], [0, b]])").unwrap(),
+            simplified("[[a ^ -1, 0], [0, b ^ -1]]")
+        );
+        assert_eq!(
+            run("inv([[x, 1], [0, 1]])").unwrap(),
+            simplified("[[x ^ -1, -x ^ -1], [0, 1]]")
+        );
+        assert_eq!(
+            written_entries("inv([[0.1, 0.2], [0.3, 0.4]])"),
+            ["-20", "10", "15", "-5"]
+        );
+    }
+
+    #[test]
+    fn linear_systems() {
+        assert_eq!(
+            run("solve([[2, 1], [1, 3]], [3, 5])").unwrap(),
+            simplified("[4 / 5, 7 / 5]")
+        );
+        assert_eq!(
+            run("solve([[0, 1, 1], [1, 0, 1], [1, 1, 0]], [2, 3, 5])").unwrap(),
+            simplified("[3, 2, 0]")
+        );
+        assert_eq!(
+            run("solve([[1, 1], [1, -1]], [x, y])").unwrap(),
+            simplified("[x / 2 + y / 2, x / 2 - y / 2]")
+        );
+        assert_eq!(
+            written_entries("solve([[0.1, 0.2], [0.3, 0.4]], [1, 2])"),
+            ["0", "5"]
+        );
+
+        test_vector("rank([[1, 2], [2, 4]])", "1");
+        test_vector("rank([[1, 2, 3], [4, 5, 6], [7, 8, 9]])", "2");
+        test_vector("rank([[1, 2, 3], [4, 5, 7]])", "2");
+        test_vector("rank([[x, 1], [1, 1]])", "2");
+        test_vector("rank([[x, y], [2 * x, 2 * y]])", "1");
+    }
+
+    #[test]
+    fn matrix_errors() {
+        assert_eq!(
+            run("det([[1, 2, 3], [4, 5, 6]])"),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::NotSquare { rows: 2, cols: 3 }
+            })
+        );
+        assert_eq!(
+            run("inv([[1, 2, 3]])"),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::NotSquare { rows: 1, cols: 3 }
+            })
+        );
+        assert_eq!(
+            run("det([[1, 2], [3]])"),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 2,
+                    found: 1
+                }
+            })
+        );
+        assert_eq!(
+            run("[[1, 2], [3, 4]] * [[1, 2, 3]]"),
+            Err(CASError {
+                line_pos: 17,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 2,
+                    found: 1
+                }
+            })
+        );
+        assert_eq!(
+            run("solve([[1, 2], [3, 4]], [1, 2, 3])"),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::DimensionMismatch {
+                    expected: 2,
+                    found: 3
+                }
+            })
+        );
+        for code in ["inv([[1, 2], [2, 4]])", "inv([[x, x], [1, 1]])"] {
+            assert_eq!(
+                run(code),
+                Err(CASError {
+                    line_pos: 2,
+                    kind: CASErrorKind::SingularMatrix
+                }),
+                "{}",
+                code
+            );
+        }
+        assert_eq!(
+            run("det([1, 2])"),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::InvalidArgument {
+                    arg: String::from("[]"),
+                    func_name: ResFun::Det.to_string()
+                }
+            })
+        );
+    }
+}
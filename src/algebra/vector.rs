@@ -0,0 +1,166 @@
+//! Arithmetic on vectors of expressions.
+//!
+//! Operators work element by element, with scalars applied to every element, so `[1, 2] + [3, 4]` is `[4, 6]` and `2 * [x, y]` is `[2 * x, 2 * y]`. The one exception is multiplying a matrix, which is a vector of rows, by another matrix or a vector, which is the matrix product.
+
+use crate::{
+    parser::trees::Tree,
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        cas_vec::CASVec,
+        symbol::{function::ResFun, operator::Operator, Symbol, SymbolType},
+    },
+};
+
+use super::build::{self, call, node, num, Node};
+use super::matrix;
+use super::simplify::simplify;
+
+/// Applies an operator to its arguments element by element if any of them are vectors, or returns None if none of them are.
+///
+/// Vector arguments must all have the same number of elements, and only arithmetic operators apply to vectors, so `[1, 2] < [3, 4]` is a type error. Multiplying a matrix by a matrix or vector is the matrix product, see [matrix::product].
+pub(crate) fn operate(
+    op: Operator,
+    args: &[Node],
+    line_pos: usize,
+) -> Result<Option<Node>, CASError> {
+    if !args.iter().any(is_vector) {
+        return Ok(None);
+    }
+    if !matches!(
+        op,
+        Operator::Add
+            | Operator::Sub
+            | Operator::Mult
+            | Operator::Div
+            | Operator::Exp
+            | Operator::Neg
+    ) {
+        return Err(CASError {
+            line_pos,
+            kind: CASErrorKind::TypeError,
+        });
+    }
+    if let (Operator::Mult, [lhs, rhs]) = (op, args) {
+        if let Some(product) = matrix::product(lhs, rhs, line_pos)? {
+            return Ok(Some(product));
+        }
+    }
+    elementwise(op, args.to_vec(), line_pos).map(Some)
+}
+
+/// Dot product of two vectors with the same number of elements.
+pub(crate) fn dot(lhs: &CASVec, rhs: &CASVec, line_pos: usize) -> Result<Node, CASError> {
+    let products = lhs.zip_with(rhs, line_pos, |a, b| {
+        element(Operator::Mult, vec![a, b], line_pos)
+    })?;
+    products
+        .elements
+        .into_iter()
+        .try_fold(num(0, line_pos), |sum, product| {
+            elementwise(Operator::Add, vec![sum, product.root], line_pos)
+        })
+}
+
+/// Cross product of two vectors with 3 elements.
+pub(crate) fn cross(lhs: &CASVec, rhs: &CASVec, line_pos: usize) -> Result<Node, CASError> {
+    lhs.check_dim(3, line_pos)?;
+    rhs.check_dim(3, line_pos)?;
+    let (a, b) = (&lhs.elements, &rhs.elements);
+
+    // a × b -> [a2 * b3 - a3 * b2, a3 * b1 - a1 * b3, a1 * b2 - a2 * b1]
+    let elements = [(1, 2), (2, 0), (0, 1)]
+        .into_iter()
+        .map(|(j, k)| {
+            let lhs = element(Operator::Mult, vec![&a[j], &b[k]], line_pos)?;
+            let rhs = element(Operator::Mult, vec![&a[k], &b[j]], line_pos)?;
+            Ok(Tree {
+                root: elementwise(Operator::Sub, vec![lhs.root, rhs.root], line_pos)?,
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(elements).into_node(line_pos))
+}
+
+/// Euclidean norm of a vector, `sqrt(dot(v, v))`, simplified so exact roots are found, like `norm([3, 4]) = 5`.
+pub(crate) fn norm(vector: &CASVec, line_pos: usize) -> Result<Node, CASError> {
+    let square = dot(vector, vector, line_pos)?;
+    if is_vector(&square) {
+        //the elements were vectors themselves, which have no single norm
+        return Err(CASError {
+            line_pos,
+            kind: CASErrorKind::TypeError,
+        });
+    }
+    Ok(simplify(&call(ResFun::Sqrt, vec![square])))
+}
+
+/// Checks if the root of a node is a vector.
+pub(crate) fn is_vector(node: &Node) -> bool {
+    matches!(node.0.borrow().data.symbol_type, SymbolType::Vector { .. })
+}
+
+/// Applies an arithmetic operator to elements of vectors, see [elementwise].
+fn element(
+    op: Operator,
+    args: Vec<&Tree<Symbol>>,
+    line_pos: usize,
+) -> Result<Tree<Symbol>, CASError> {
+    let args = args.into_iter().map(|arg| arg.root.clone()).collect();
+    Ok(Tree {
+        root: elementwise(op, args, line_pos)?,
+    })
+}
+
+/// Applies an arithmetic operator to arguments which may be vectors, going into vectors of vectors as deep as they go.
+pub(crate) fn elementwise(
+    op: Operator,
+    args: Vec<Node>,
+    line_pos: usize,
+) -> Result<Node, CASError> {
+    let vectors: Vec<Option<CASVec>> = args.iter().map(CASVec::from_node).collect();
+    let Some(dim) = vectors.iter().flatten().map(CASVec::dim).next() else {
+        return Ok(apply(op, args, line_pos));
+    };
+    for vector in vectors.iter().flatten() {
+        vector.check_dim(dim, line_pos)?;
+    }
+
+    let elements = (0..dim)
+        .map(|idx| {
+            let args = args
+                .iter()
+                .zip(&vectors)
+                .map(|(arg, vector)| match vector {
+                    Some(vector) => vector.elements[idx].root.clone(),
+                    None => arg.clone(),
+                })
+                .collect();
+            Ok(Tree {
+                root: elementwise(op, args, line_pos)?,
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(elements).into_node(line_pos))
+}
+
+/// Applies an arithmetic operator to arguments that aren't vectors, folding away trivial cases like `x * 1`.
+fn apply(op: Operator, mut args: Vec<Node>, line_pos: usize) -> Node {
+    let rhs = args.pop().unwrap();
+    if op == Operator::Neg {
+        return build::neg(rhs);
+    }
+    let lhs = match args.len() {
+        1 => args.pop().unwrap(),
+        //sums and products of more than two arguments only come from simplifying, and are folded from the left
+        _ => apply(op, args, line_pos),
+    };
+
+    match op {
+        Operator::Add => build::add(lhs, rhs),
+        Operator::Sub => build::sub(lhs, rhs),
+        Operator::Mult => build::mult(lhs, rhs),
+        Operator::Div => build::div(lhs, rhs),
+        Operator::Exp => build::pow(lhs, rhs),
+        _ => node(SymbolType::Operator(op), line_pos, vec![lhs, rhs]),
+    }
+}
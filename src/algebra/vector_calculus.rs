@@ -0,0 +1,98 @@
+//! Gradients, divergences, curls and jacobians, with every partial derivative found by the derivative engine.
+//!
+//! The variables are given explicitly as a vector like `[x, y, z]`, which fixes the order of the elements of the result.
+
+use crate::{
+    parser::trees::Tree,
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        cas_vec::CASVec,
+    },
+};
+
+use super::build::{add, num, sub, Node};
+use super::derivative::Differentiator;
+
+/// Gradient of a scalar field, the vector of its partial derivatives with respect to each of `vars`.
+pub(crate) fn gradient(
+    differentiator: &mut Differentiator,
+    field: &Node,
+    vars: &[String],
+    line_pos: usize,
+) -> Result<Node, CASError> {
+    let elements = vars
+        .iter()
+        .map(|wrt| {
+            Ok(Tree {
+                root: differentiator.derivative(field, wrt)?,
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(elements).into_node(line_pos))
+}
+
+/// Divergence of a vector field, the sum of the partial derivatives of each element with respect to the matching one of `vars`.
+pub(crate) fn divergence(
+    differentiator: &mut Differentiator,
+    field: &CASVec,
+    vars: &[String],
+    line_pos: usize,
+) -> Result<Node, CASError> {
+    field.check_dim(vars.len(), line_pos)?;
+    let mut sum = num(0, line_pos);
+    for (element, wrt) in field.elements.iter().zip(vars) {
+        sum = add(sum, differentiator.derivative(&element.root, wrt)?);
+    }
+    Ok(sum)
+}
+
+/// Curl of a vector field, which is only defined in 3 dimensions.
+pub(crate) fn curl(
+    differentiator: &mut Differentiator,
+    field: &CASVec,
+    vars: &[String],
+    line_pos: usize,
+) -> Result<Node, CASError> {
+    if vars.len() != 3 {
+        return Err(CASError {
+            line_pos,
+            kind: CASErrorKind::DimensionMismatch {
+                expected: 3,
+                found: vars.len(),
+            },
+        });
+    }
+    field.check_dim(3, line_pos)?;
+    let mut partial =
+        |idx: usize, wrt: usize| differentiator.derivative(&field.elements[idx].root, &vars[wrt]);
+
+    // ∇ × F -> [∂F3/∂y - ∂F2/∂z, ∂F1/∂z - ∂F3/∂x, ∂F2/∂x - ∂F1/∂y]
+    let elements = [(1, 2), (2, 0), (0, 1)]
+        .into_iter()
+        .map(|(j, k)| {
+            Ok(Tree {
+                root: sub(partial(k, j)?, partial(j, k)?),
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(elements).into_node(line_pos))
+}
+
+/// Jacobian of a vector field, the matrix whose rows are the gradients of its elements.
+pub(crate) fn jacobian(
+    differentiator: &mut Differentiator,
+    field: &CASVec,
+    vars: &[String],
+    line_pos: usize,
+) -> Result<Node, CASError> {
+    let rows = field
+        .elements
+        .iter()
+        .map(|element| {
+            Ok(Tree {
+                root: gradient(differentiator, &element.root, vars, line_pos)?,
+            })
+        })
+        .collect::<Result<_, CASError>>()?;
+    Ok(CASVec::new(rows).into_node(line_pos))
+}
@@ -0,0 +1,246 @@
+//! Writes expression trees on one line, in the same syntax they're typed in, like `2 * x ^ 2 - sin(x) / 3`.
+
+use crate::parser::trees::{Tree, TreeNode, TreeNodeRef};
+use crate::types::cas_num::Number;
+use crate::types::symbol::{
+    function::{Func, RESERVED_FUNCTIONS},
+    operator::{left_associative, precedence, Operator},
+    Symbol, SymbolType,
+};
+
+/// Precedence of numbers, variables, constants, calls and vectors, which never need parentheses around them.
+const ATOM: u8 = 9;
+
+/// Writes an expression on one line, with only the parentheses it needs to be read back with the same value.
+///
+/// Canonical forms from the simplifier are written the way they'd usually be typed, so `x + -1 * y` is written `x - y`, `x * y ^ -1` is written `x / y` and `-1 * x` is written `-x`.
+pub(super) fn linear(tree: &Tree<Symbol>) -> String {
+    write(&tree.root).0
+}
+
+/// Writes a subtree, along with the precedence of the operator at its root, which decides if it needs parentheses as an operand.
+fn write(tree_node: &TreeNodeRef<Symbol>) -> (String, u8) {
+    let node = tree_node.0.borrow();
+    let args = &node.children;
+
+    match &node.data.symbol_type {
+        SymbolType::Num { value } => {
+            let value = value.to_string();
+            //-2 is read as -(2) and 22/7 as 22 / 7
+            let prec = match (value.starts_with('-'), value.contains('/')) {
+                (_, true) => precedence(&Operator::Div),
+                (true, false) => precedence(&Operator::Neg),
+                (false, false) => ATOM,
+            };
+            (value, prec)
+        }
+        SymbolType::Const(constant) => {
+            let constant = constant.to_string();
+            let prec = match constant.starts_with('-') {
+                true => precedence(&Operator::Neg),
+                false => ATOM,
+            };
+            (constant, prec)
+        }
+        SymbolType::Variable { name } => (name.clone(), ATOM),
+        SymbolType::Function(Func::ResFun { fun, .. }) => {
+            let name = RESERVED_FUNCTIONS
+                .entries()
+                .find(|(_, other)| *other == fun)
+                .map_or(String::new(), |(name, _)| name.to_string());
+            (format!("{}({})", name, list(args)), ATOM)
+        }
+        SymbolType::Function(Func::Function { name, .. }) => {
+            (format!("{}({})", name, list(args)), ATOM)
+        }
+        SymbolType::Vector { .. } => (format!("[{}]", list(args)), ATOM),
+        SymbolType::Operator(Operator::Add) => sum(args),
+        SymbolType::Operator(Operator::Mult) => product(args),
+        SymbolType::Operator(Operator::Neg) => negation(&args[0]),
+        SymbolType::Operator(op) => {
+            let prec = precedence(op);
+            //an operand with the same precedence only goes without parentheses on the side the operator groups towards, and ^ always puts them around a negative base
+            let (lhs, rhs) = match left_associative(op) {
+                true => (prec, prec + 1),
+                false => (ATOM, prec),
+            };
+            (
+                format!(
+                    "{} {} {}",
+                    operand(&args[0], lhs),
+                    op,
+                    operand(&args[1], rhs)
+                ),
+                prec,
+            )
+        }
+    }
+}
+
+/// Writes a subtree as an operand, in parentheses if its precedence is below `min_prec`.
+fn operand(tree_node: &TreeNodeRef<Symbol>, min_prec: u8) -> String {
+    let (code, prec) = write(tree_node);
+    match prec < min_prec {
+        true => format!("({})", code),
+        false => code,
+    }
+}
+
+/// Writes the arguments of a call or the elements of a vector, separated by commas.
+fn list(args: &[TreeNodeRef<Symbol>]) -> String {
+    args.iter()
+        .map(|arg| write(arg).0)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes a sum, where terms with a negative coefficient after the first are subtracted instead.
+///
+/// The first term without a negative coefficient is written first, so `-y + x` is written `x - y`.
+fn sum(terms: &[TreeNodeRef<Symbol>]) -> (String, u8) {
+    let prec = precedence(&Operator::Add);
+    let mut terms = terms.to_vec();
+    if let Some(idx) = terms.iter().position(|term| negated(term).is_none()) {
+        let term = terms.remove(idx);
+        terms.insert(0, term);
+    }
+    let mut code = String::new();
+    for (idx, term) in terms.iter().enumerate() {
+        if idx == 0 {
+            code += &operand(term, prec);
+            continue;
+        }
+        match negated(term) {
+            Some(term) => code += &format!(" - {}", operand(&term, prec + 1)),
+            None => code += &format!(" + {}", operand(term, prec + 1)),
+        }
+    }
+    (code, prec)
+}
+
+/// Writes a product, with factors raised to negative numbers moved into a denominator.
+fn product(factors: &[TreeNodeRef<Symbol>]) -> (String, u8) {
+    if let [coefficient, rest @ ..] = factors {
+        if !rest.is_empty() && is_num(coefficient, &Number::from(-1)) {
+            return negation(&new_node(
+                SymbolType::Operator(Operator::Mult),
+                rest.to_vec(),
+            ));
+        }
+    }
+
+    let mut numerator = vec![];
+    let mut denominator = vec![];
+    for factor in factors {
+        match reciprocal(factor) {
+            Some(factor) => denominator.push(factor),
+            None => numerator.push(factor.clone()),
+        }
+    }
+
+    let prec = precedence(&Operator::Mult);
+    let join = |factors: &[TreeNodeRef<Symbol>]| {
+        factors
+            .iter()
+            .enumerate()
+            .map(|(idx, factor)| operand(factor, if idx == 0 { prec } else { prec + 1 }))
+            .collect::<Vec<_>>()
+            .join(" * ")
+    };
+    match (numerator.len(), denominator.len()) {
+        (1, 0) => write(&numerator[0]),
+        (_, 0) => (join(&numerator), prec),
+        (numerator_len, denominator_len) => {
+            let numerator = match numerator_len {
+                0 => String::from("1"),
+                _ => join(&numerator),
+            };
+            let denominator = match denominator_len {
+                1 => operand(&denominator[0], precedence(&Operator::Div) + 1),
+                _ => format!("({})", join(&denominator)),
+            };
+            (
+                format!("{} / {}", numerator, denominator),
+                precedence(&Operator::Div),
+            )
+        }
+    }
+}
+
+/// Writes `-` in front of an operand, which needs parentheses unless it's an atom since `-x ^ 2` is read as `(-x) ^ 2`.
+fn negation(tree_node: &TreeNodeRef<Symbol>) -> (String, u8) {
+    (
+        format!("-{}", operand(tree_node, ATOM)),
+        precedence(&Operator::Neg),
+    )
+}
+
+/// The opposite of a negative number or of a product whose coefficient is negative, like `2 * x` for `-2 * x`, or None for anything else.
+fn negated(tree_node: &TreeNodeRef<Symbol>) -> Option<TreeNodeRef<Symbol>> {
+    let node = tree_node.0.borrow();
+    match &node.data.symbol_type {
+        SymbolType::Num { value } if *value < Number::from(0) => Some(new_node(
+            SymbolType::Num {
+                value: -value.clone(),
+            },
+            vec![],
+        )),
+        SymbolType::Operator(Operator::Mult) => {
+            let (coefficient, rest) = node.children.split_first()?;
+            let opposite = negated(coefficient)?;
+            if rest.is_empty() {
+                return Some(opposite);
+            }
+            let factors = match is_num(&opposite, &Number::from(1)) {
+                true => rest.to_vec(),
+                false => [vec![opposite], rest.to_vec()].concat(),
+            };
+            match factors.len() {
+                1 => Some(factors[0].clone()),
+                _ => Some(new_node(SymbolType::Operator(Operator::Mult), factors)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The reciprocal of a power with a negative number as its exponent, like `x ^ 2` for `x ^ -2`, or None for anything else.
+fn reciprocal(tree_node: &TreeNodeRef<Symbol>) -> Option<TreeNodeRef<Symbol>> {
+    let node = tree_node.0.borrow();
+    let [base, exp] = node.children.as_slice() else {
+        return None;
+    };
+    if node.data.symbol_type != SymbolType::Operator(Operator::Exp) {
+        return None;
+    }
+    let exp = match &exp.0.borrow().data.symbol_type {
+        SymbolType::Num { value } if *value < Number::from(0) => -value.clone(),
+        _ => return None,
+    };
+    match exp == Number::from(1) {
+        true => Some(base.clone()),
+        false => Some(new_node(
+            SymbolType::Operator(Operator::Exp),
+            vec![
+                base.clone(),
+                new_node(SymbolType::Num { value: exp }, vec![]),
+            ],
+        )),
+    }
+}
+
+/// Checks if a node is the number `value`.
+fn is_num(tree_node: &TreeNodeRef<Symbol>, value: &Number) -> bool {
+    matches!(&tree_node.0.borrow().data.symbol_type, SymbolType::Num { value: other } if other == value)
+}
+
+/// Creates a node for an expression that's only written, never parsed, so it has no position on the line.
+fn new_node(symbol_type: SymbolType, children: Vec<TreeNodeRef<Symbol>>) -> TreeNodeRef<Symbol> {
+    TreeNodeRef::new_from_node(TreeNode {
+        data: Symbol {
+            symbol_type,
+            line_pos: 0,
+        },
+        children,
+    })
+}
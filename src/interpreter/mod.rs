@@ -0,0 +1,247 @@
+//! Drives the scanner and parser over user input, either interactively or from a script file.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::algebra::term_rewriter::RuleSet;
+use crate::parser::{
+    statement::{parse_statement, Statement},
+    trees::Tree,
+    vars::VarTable,
+};
+use crate::scanner::tokenize;
+use crate::types::cas_error::{print_error, CASError};
+use crate::types::symbol::{Symbol, SymbolType};
+use linear::linear;
+
+/// Writing results on one line.
+mod linear;
+/// Tests for input handling in interpreter sessions.
+mod test;
+
+/// Prompt printed when the interpreter is waiting for a new line of input.
+const PROMPT: &str = ">> ";
+/// Prompt printed when the previous line left parentheses or brackets open.
+const CONTINUATION_PROMPT: &str = ".. ";
+/// Command that loads a rule file, like `:rules trig.txt`.
+const RULES_COMMAND: &str = ":rules";
+
+/// State that persists across lines of an interpreter session.
+///
+/// The variable table is shared by every line, so names defined on one line can be used by any later line.
+#[derive(Default)]
+pub struct Session {
+    /// Variables and functions visible to every line of the session.
+    var_table: VarTable<'static>,
+    /// Every complete input entered so far, in order.
+    history: Vec<String>,
+    /// Rules loaded with `:rules`, which are applied to every expression and definition after it's parsed.
+    rules: RuleSet,
+}
+
+impl Session {
+    /// Creates a session with an empty variable table, no history and no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tokenizes and parses a single line of input, then either prints the value of the expression or stores the assignment it contains.
+    ///
+    /// Commands like `der` are run as the line is parsed, and the result is simplified and printed on one line, so `2 + 3` prints `5`.
+    ///
+    /// A line like `:rules file.txt` loads the rules in that file instead. Errors are printed to stderr with `line_num` (starting at 0) as the line they occurred on. Returns false if the line produced an error.
+    pub fn run_line(&mut self, line: &str, line_num: usize) -> bool {
+        if let Some(path) = line.trim().strip_prefix(RULES_COMMAND) {
+            return self.load_rules(path.trim());
+        }
+
+        let tokens = match tokenize(line) {
+            Ok(tokens) => tokens,
+            Err(errors) => {
+                for err in errors {
+                    print_error(err, line, line_num);
+                }
+                return false;
+            }
+        };
+
+        let result = parse_statement(tokens, &self.var_table).and_then(|statement| {
+            Ok(match statement {
+                Statement::Expression(tree) => {
+                    let mut tree = self.rules.rewrite(&tree)?;
+                    tree.simplify();
+                    Statement::Expression(tree)
+                }
+                Statement::Assignment { name, mut var } => {
+                    var.expr = self.rewrite_definition(&var.expr)?;
+                    Statement::Assignment { name, var }
+                }
+            })
+        });
+
+        match result {
+            Ok(Statement::Expression(tree)) => {
+                println!("{}", linear(&tree));
+                true
+            }
+            Ok(Statement::Assignment { name, var }) => {
+                println!("{}", linear(&with_positions(&var.expr)));
+                self.var_table.insert(name, var);
+                true
+            }
+            Err(err) => {
+                print_error(err, line, line_num);
+                false
+            }
+        }
+    }
+
+    /// Runs an interactive session on stdin until EOF is reached.
+    ///
+    /// Lines with more opening than closing parentheses or brackets are joined with the following lines before being run.
+    pub fn run_prompt(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        let mut pending = String::new();
+
+        loop {
+            print!(
+                "{}",
+                if pending.is_empty() {
+                    PROMPT
+                } else {
+                    CONTINUATION_PROMPT
+                }
+            );
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                //EOF, run whatever was left unfinished so its errors are still reported
+                println!();
+                if !pending.trim().is_empty() {
+                    self.submit(&pending);
+                }
+                return Ok(());
+            }
+
+            if !pending.is_empty() {
+                pending.push(' ');
+            }
+            pending += line.trim_end_matches(['\n', '\r']);
+
+            if open_brackets(&pending) > 0 {
+                continue;
+            }
+
+            let code = std::mem::take(&mut pending);
+            if code.trim().is_empty() {
+                continue;
+            }
+
+            if code.trim() == ":history" {
+                for (line_num, line) in self.history.iter().enumerate() {
+                    println!("{:>4}  {}", line_num + 1, line);
+                }
+                continue;
+            }
+
+            self.submit(&code);
+        }
+    }
+
+    /// Runs every line of the file at `path` in order, sharing this session's variable table between them.
+    ///
+    /// Lines with more opening than closing parentheses or brackets are joined with the following lines, and any errors are reported on the line the statement started on. Returns the line numbers (starting at 1, as they're printed) of every statement that produced an error.
+    pub fn run_file(&mut self, path: &Path) -> io::Result<Vec<usize>> {
+        let code = fs::read_to_string(path)?;
+
+        let mut failed = vec![];
+        let mut pending = String::new();
+        let mut start_line = 0;
+
+        for (line_num, line) in code.lines().enumerate() {
+            if pending.is_empty() {
+                start_line = line_num;
+            } else {
+                pending.push(' ');
+            }
+            pending += line;
+
+            if open_brackets(&pending) > 0 {
+                continue;
+            }
+
+            let statement = std::mem::take(&mut pending);
+            if !statement.trim().is_empty() && !self.run_line(&statement, start_line) {
+                failed.push(start_line + 1);
+            }
+        }
+
+        if !pending.trim().is_empty() && !self.run_line(&pending, start_line) {
+            //file ended with unclosed parentheses
+            failed.push(start_line + 1);
+        }
+
+        Ok(failed)
+    }
+
+    /// Reads a rule file and adds its rules after the ones already loaded.
+    ///
+    /// If any rule in the file is invalid, none of them are added and every error is printed. Returns false if the file couldn't be read or had errors.
+    fn load_rules(&mut self, path: &str) -> bool {
+        let code = match fs::read_to_string(path) {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("could not read rule file {}: {}", path, err);
+                return false;
+            }
+        };
+
+        match RuleSet::parse(&code) {
+            Ok(rules) => {
+                self.rules.extend(rules);
+                true
+            }
+            Err(errors) => {
+                for (line_num, err) in errors {
+                    eprintln!("In rule file {}:", path);
+                    print_error(err, code.lines().nth(line_num).unwrap_or(""), line_num);
+                }
+                false
+            }
+        }
+    }
+
+    /// Applies the loaded rules to the expression of a definition, which is stored without line positions.
+    fn rewrite_definition(&self, expr: &Tree<SymbolType>) -> Result<Tree<SymbolType>, CASError> {
+        Ok(self
+            .rules
+            .rewrite(&with_positions(expr))?
+            .map(&|symbol: &Symbol| symbol.symbol_type.clone()))
+    }
+
+    /// Records a complete input in the history and runs it, numbering it after the inputs before it.
+    fn submit(&mut self, code: &str) -> bool {
+        self.history.push(code.to_string());
+        self.run_line(code, self.history.len() - 1)
+    }
+}
+
+/// Gives every node of a stored definition the line position 0, so it can be used like an expression that was just parsed.
+fn with_positions(expr: &Tree<SymbolType>) -> Tree<Symbol> {
+    expr.map(&|symbol_type: &SymbolType| Symbol {
+        symbol_type: symbol_type.clone(),
+        line_pos: 0,
+    })
+}
+
+/// Returns how many more opening parentheses and brackets there are than closing ones.
+fn open_brackets(code: &str) -> isize {
+    code.chars().fold(0, |depth, chr| match chr {
+        '(' | '[' => depth + 1,
+        ')' | ']' => depth - 1,
+        _ => depth,
+    })
+}
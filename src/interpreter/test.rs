@@ -0,0 +1,117 @@
+#[cfg(test)]
+
+mod test {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::super::linear::linear;
+    use super::super::{open_brackets, Session};
+    use crate::parser::statement::{parse_statement, Statement};
+    use crate::parser::trees::Tree;
+    use crate::parser::vars::VarTable;
+    use crate::scanner::tokenize;
+    use crate::types::cas_num::Number;
+    use crate::types::symbol::SymbolType;
+
+    fn write_script(name: &str, code: &str) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, code).unwrap();
+        path
+    }
+
+    /// The line [Session::run_line] prints for an expression.
+    fn result(code: &str) -> String {
+        match parse_statement(tokenize(code).unwrap(), &VarTable::new()).unwrap() {
+            Statement::Expression(mut tree) => {
+                tree.simplify();
+                linear(&tree)
+            }
+            Statement::Assignment { .. } => panic!("{} is not an expression", code),
+        }
+    }
+
+    #[test]
+    fn linear_results() {
+        assert_eq!(result("2 + 3"), "5");
+        assert_eq!(result("x - y"), "x - y");
+        assert_eq!(result("x - 2 * y ^ -1"), "x - 2 / y");
+        assert_eq!(result("3 - x"), "3 - x");
+        assert_eq!(result("0 - x - 2"), "-x - 2");
+        assert_eq!(result("x ^ -2 * y"), "y / x ^ 2");
+        assert_eq!(result("1 / (x * y)"), "1 / (x * y)");
+        assert_eq!(result("2 * x / 3"), "2/3 * x");
+        assert_eq!(result("(2 / 3) ^ x"), "(2/3) ^ x");
+        assert_eq!(result("x ^ (1 / 2)"), "x ^ (1/2)");
+        assert_eq!(result("(a ^ b) ^ c"), "(a ^ b) ^ c");
+        assert_eq!(result("a ^ b ^ c"), "a ^ b ^ c");
+        assert_eq!(result("[1 - x, 0]"), "[1 - x, 0]");
+        assert_eq!(result("sym_int(x * e ^ x, x)"), "e ^ x * x - e ^ x + C");
+        assert_eq!(result("der(sin(x), x)"), "cos(x)");
+        assert_eq!(result("calc(1 / 4)"), "0.25");
+    }
+
+    #[test]
+    fn bracket_balance() {
+        assert_eq!(open_brackets("2 + 3"), 0);
+        assert_eq!(open_brackets("f(2, (3"), 2);
+        assert_eq!(open_brackets("[1, 2] * (3"), 1);
+        assert_eq!(open_brackets("2 +)"), -1);
+    }
+
+    #[test]
+    fn run_file() {
+        let path = write_script("cas_run_file_ok.cas", "2 + 2\n\n(1 +\n 2) * 3\n");
+        assert_eq!(Session::new().run_file(&path).unwrap(), vec![]);
+
+        let path = write_script("cas_run_file_vars.cas", "x = 2\nf(a) = a * x\nf(3)\n");
+        assert_eq!(Session::new().run_file(&path).unwrap(), vec![]);
+
+        //errors are reported on the line they're on, counting from 1
+        let path = write_script("cas_run_file_err.cas", "2 + 2\n2 +)\n3 * 3\n");
+        assert_eq!(Session::new().run_file(&path).unwrap(), vec![2]);
+
+        //and statements spanning several lines are reported on the line they start on
+        let path = write_script(
+            "cas_run_file_continued.cas",
+            "2 + 2\n\n(1 +\n 2 +) * 3\n4 +\n",
+        );
+        assert_eq!(Session::new().run_file(&path).unwrap(), vec![3, 5]);
+
+        let path = write_script("cas_run_file_unclosed.cas", "2 + 2\n(3 *\n");
+        assert_eq!(Session::new().run_file(&path).unwrap(), vec![2]);
+
+        assert!(Session::new()
+            .run_file(&env::temp_dir().join("cas_run_file_missing.cas"))
+            .is_err());
+    }
+
+    #[test]
+    fn rule_files() {
+        let rules = write_script("cas_rules_ok.txt", "# identities\n\na * 0 => 0\n");
+        let mut session = Session::new();
+        assert!(session.run_line(&format!(":rules {}", rules.display()), 0));
+        assert!(session.run_line("y = sin(x) * 0", 1));
+        assert_eq!(
+            session.var_table["y"].expr,
+            Tree::from(SymbolType::Num {
+                value: Number::from(0)
+            })
+        );
+
+        let rules = write_script("cas_rules_err.txt", "a * 0 => 0\na * 1 => b\n");
+        let mut session = Session::new();
+        assert!(!session.run_line(&format!(":rules {}", rules.display()), 0));
+        assert!(session.rules.rules.is_empty());
+
+        let script = write_script(
+            "cas_run_file_rules.cas",
+            &format!(
+                ":rules {}\nx * 0 + 1\n",
+                write_script("cas_rules_script.txt", "a + 0 => a").display()
+            ),
+        );
+        assert_eq!(Session::new().run_file(&script).unwrap(), vec![]);
+        assert!(!Session::new().run_line(":rules cas_rules_missing.txt", 0));
+    }
+}
@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 #![warn(clippy::missing_docs_in_private_items)]
 mod algebra;
+pub mod interpreter;
 mod parser;
 mod scanner;
 pub(crate) mod types;
@@ -1,45 +1,33 @@
-// use std::env;
-// use std::fs::File;
-// use std::io;
-// use std::io::prelude::*;
-// use std::path::Path;
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
-fn main() {
-    //cli stuff
-    // let args: Vec<String> = env::args().collect();
+use cas::interpreter::Session;
 
-    // if args.len() > 2 {
-    //     writeln!(io::stderr(), "Usage: {} <filename> for compilation, or run without any arguments to start an interpreter session.", args[0]).unwrap();
-    //     return;
-    // }
+fn main() -> ExitCode {
+    //cli stuff
+    let args: Vec<String> = env::args().collect();
+
+    match args.len() {
+        1 => {
+            if let Err(err) = Session::new().run_prompt() {
+                writeln!(io::stderr(), "could not read input: {}", err).unwrap();
+                return ExitCode::FAILURE;
+            }
+            ExitCode::SUCCESS
+        }
+        2 => match Session::new().run_file(Path::new(&args[1])) {
+            Ok(failed) if failed.is_empty() => ExitCode::SUCCESS,
+            Ok(_) => ExitCode::FAILURE,
+            Err(err) => {
+                writeln!(io::stderr(), "could not read {}: {}", args[1], err).unwrap();
+                ExitCode::FAILURE
+            }
+        },
+        _ => {
+            writeln!(io::stderr(), "Usage: {} <filename> to run a script, or run without any arguments to start an interpreter session.", args[0]).unwrap();
+            ExitCode::FAILURE
+        }
+    }
 }
-
-// fn run(code: String) {
-//     //TODO: change this to iterate over each line of input, that way we can print the whole line out in case of an error
-//     //and just add the tokens to a growing vec<token> for each line
-
-//     let mut tokens: Vec<TokenItem> = vec![];
-
-//     for (line_num, line) in code.lines().enumerate() {
-//         process_line(line, &mut tokens, line_num);
-//     }
-// }
-
-// for (expr, var_table) in zip(&expressions, var_tables) {
-//         let scan = tokenize(&expr);
-
-//         if let Ok(tokens) = scan {
-//             let hash_table = match var_table {
-//                 Some(x) => x,
-//                 None => HashMap::new(),
-//             };
-
-//             let parse = parse_expr(&tokens, &hash_table, vec![]);
-
-//             if let Ok(tree) = parse {
-//                 println!("{}", tree);
-//             } else {
-//                 print_error(parse.unwrap_err(), &expr, 1);
-//             }
-//         }
-//     }
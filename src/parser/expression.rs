@@ -8,6 +8,7 @@ use super::vars::{Var, VarTable};
 pub(crate) type PostFix<'a> = Result<VecDeque<Symbol>, CASError>;
 
 use crate::types::cas_error::{CASError, CASErrorKind};
+use crate::types::cas_num::Number;
 use crate::types::symbol::constant::Const;
 use crate::types::symbol::function::Func;
 use crate::types::symbol::operator::{
@@ -35,6 +36,8 @@ pub(crate) fn into_postfix<'a>(
     let mut output_queue: VecDeque<Symbol> = VecDeque::new();
     let mut operator_stack: VecDeque<Symbol> = VecDeque::new();
     let mut last_token: Option<&TokenType> = None;
+    let mut arg_counts: Vec<usize> = vec![];
+    //number of arguments seen so far inside each open parenthesis, used to check function calls
 
     for Token {
         token_type,
@@ -74,7 +77,10 @@ pub(crate) fn into_postfix<'a>(
                 }
             }
             ResFun(name) => operator_stack.push_back(Symbol {
-                symbol_type: SymbolType::Function(Func::ResFun(*name)),
+                symbol_type: SymbolType::Function(Func::ResFun {
+                    fun: *name,
+                    num_args: name.num_args(),
+                }),
                 line_pos: *line_pos,
             }),
 
@@ -91,21 +97,38 @@ pub(crate) fn into_postfix<'a>(
                     }
                 }
 
-                LeftParen | LeftBracket => operator_stack.push_back(Symbol {
-                    symbol_type: SymbolType::Operator(*o1),
-                    line_pos: *line_pos,
-                }),
+                LeftParen | LeftBracket => {
+                    arg_counts.push(1);
+                    operator_stack.push_back(Symbol {
+                        symbol_type: SymbolType::Operator(*o1),
+                        line_pos: *line_pos,
+                    })
+                }
 
                 RightParen | RightBracket => {
-                    if let Some(value) =
-                        parse_right_paren(&mut operator_stack, &mut output_queue, *line_pos)
-                    {
+                    let num_args = match (arg_counts.pop(), last_token) {
+                        (_, Some(Operator(LeftParen)) | Some(Operator(LeftBracket))) => 0,
+                        //f() has no arguments
+                        (count, _) => count.unwrap_or(0),
+                    };
+                    if let Some(value) = parse_right_paren(
+                        &mut operator_stack,
+                        &mut output_queue,
+                        o1,
+                        *line_pos,
+                        num_args,
+                    ) {
                         return Err(value);
                     }
                 }
                 Comma => {
+                    if let Some(count) = arg_counts.last_mut() {
+                        *count += 1;
+                    }
                     while let Some(o2) = operator_stack.pop_back() {
-                        if o2.symbol_type == SymbolType::Operator(Operator::LeftParen) {
+                        if o2.symbol_type == SymbolType::Operator(Operator::LeftParen)
+                            || o2.symbol_type == SymbolType::Operator(Operator::LeftBracket)
+                        {
                             operator_stack.push_back(o2);
                             break;
                         }
@@ -127,10 +150,15 @@ pub(crate) fn into_postfix<'a>(
                     | Some(Const(_))
                     | Some(Operator(RightBracket))
                     | Some(Operator(RightParen)) => {
-                        operator_stack.push_back(Symbol {
-                            symbol_type: SymbolType::Operator(Sub),
-                            line_pos: *line_pos,
-                        });
+                        //binary minus follows the same precedence rules as the other operators
+                        if let Some(value) = parse_numeric_operator(
+                            &mut operator_stack,
+                            &Sub,
+                            &mut output_queue,
+                            *line_pos,
+                        ) {
+                            return Err(value);
+                        }
                     }
                     Some(Eof) => {
                         return Err(CASError {
@@ -184,7 +212,7 @@ pub(crate) fn into_postfix<'a>(
 fn parse_num(
     operator_stack: &mut VecDeque<Symbol>,
     output_queue: &mut VecDeque<Symbol>,
-    number: &super::CASNum,
+    number: &Number,
     line_pos: &usize,
 ) {
     if let Some(Symbol {
@@ -225,6 +253,8 @@ pub(crate) fn shunting_yard(output_queue: &mut VecDeque<Symbol>) -> Result<Tree<
                 });
             }
         }
+        //arguments come off the stack last first, so we flip them back into the order they were written in
+        args.reverse();
         tree_stack.push(TreeNodeRef::new_from_node(TreeNode {
             data: symbol,
             children: args,
@@ -259,10 +289,15 @@ pub(crate) fn shunting_yard(output_queue: &mut VecDeque<Symbol>) -> Result<Tree<
     };
 }
 
+/// Pops operators until the parenthesis or bracket matching `closing`, then finishes the function call or vector literal it closes.
+///
+/// A bracket that isn't part of a function call is a vector literal, so `[1, 2, 3]` becomes a vector with 3 elements.
 fn parse_right_paren(
     operator_stack: &mut VecDeque<Symbol>,
     output_queue: &mut VecDeque<Symbol>,
+    closing: &Operator,
     line_pos: usize,
+    num_args: usize,
 ) -> Option<CASError> {
     loop {
         let top_of_stack: Option<&Symbol> = operator_stack.back();
@@ -300,8 +335,9 @@ fn parse_right_paren(
         }
     }
     match operator_stack.back() {
-        Some(symbol) => match symbol.symbol_type {
-            SymbolType::Operator(LeftParen) | SymbolType::Operator(LeftBracket) => {
+        Some(symbol) => match (&symbol.symbol_type, closing) {
+            (SymbolType::Operator(LeftParen), RightParen)
+            | (SymbolType::Operator(LeftBracket), RightBracket) => {
                 //{assert there is a left parenthesis at the top of the operator stack}
             }
             _ => {
@@ -320,20 +356,61 @@ fn parse_right_paren(
         }
     }
 
-    operator_stack.pop_back();
+    let opening = operator_stack.pop_back()?;
     //  pop the left parenthesis from the operator stack and discard it
+    if *closing == RightBracket {
+        output_queue.push_back(Symbol {
+            symbol_type: SymbolType::Vector { dim: num_args },
+            line_pos: opening.line_pos,
+        });
+        return None;
+    }
     if let Some(Symbol {
         symbol_type: SymbolType::Function { .. },
         ..
     }) = operator_stack.back()
     {
-        output_queue.push_back(operator_stack.pop_back()?);
+        let mut function = operator_stack.pop_back()?;
+        if let Some(err) = set_num_args(&mut function, num_args) {
+            return Some(err);
+        }
+        output_queue.push_back(function);
     }
     // if there is a function token at the top of the operator stack, then:
     //pop the function from the operator stack into the output queue
     None
 }
 
+/// Checks that a function was called with a number of arguments it accepts, and records that number for variadic functions.
+fn set_num_args(function: &mut Symbol, args_given: usize) -> Option<CASError> {
+    let (args_needed, func_name) = match &mut function.symbol_type {
+        SymbolType::Function(Func::ResFun { fun, num_args }) => {
+            let within_max = fun.max_args().is_none_or(|max| args_given <= max);
+            if args_given >= fun.num_args() && within_max {
+                *num_args = args_given;
+                return None;
+            }
+            (fun.num_args(), fun.to_string())
+        }
+        SymbolType::Function(Func::Function { num_args, name }) => {
+            if args_given == *num_args {
+                return None;
+            }
+            (*num_args, name.to_string())
+        }
+        _ => return None,
+    };
+
+    Some(CASError {
+        line_pos: function.line_pos,
+        kind: CASErrorKind::WrongNumberOfArgs {
+            args_given,
+            args_needed,
+            func_name,
+        },
+    })
+}
+
 fn parse_numeric_operator(
     operator_stack: &mut VecDeque<Symbol>,
     o1: &Operator,
@@ -343,7 +420,7 @@ fn parse_numeric_operator(
     while let Some(sym) = operator_stack.back() {
         match &sym.symbol_type {
             SymbolType::Operator(o2) => {
-                if *o2 == Operator::LeftParen {
+                if *o2 == Operator::LeftParen || *o2 == Operator::LeftBracket {
                     break;
                 }
                 //while there is an operator at the top of the stack which is not a left paren
@@ -2,9 +2,11 @@ use expression::{into_postfix, shunting_yard};
 use trees::Tree;
 use vars::VarTable;
 
-use crate::types::{cas_error::CASError, cas_num::CASNum, symbol::Symbol, token::Token};
+use crate::types::{cas_error::CASError, symbol::Symbol, token::Token};
 
 mod expression;
+/// Parsing of assignments and other top level statements.
+pub(crate) mod statement;
 mod test;
 pub(crate) mod trees;
 pub(crate) mod vars;
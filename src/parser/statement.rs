@@ -0,0 +1,144 @@
+//! Parses top level statements, which are either assignments or plain expressions. i.e.
+//! ```text
+//! x = 3
+//! f(x, y) = x^2 + y
+//! f(2, x) * 3
+//! ```
+
+use super::parse_expr;
+use crate::algebra::eval_commands;
+use super::trees::Tree;
+use super::vars::{expand, Var, VarTable};
+
+use crate::types::cas_error::{CASError, CASErrorKind};
+use crate::types::symbol::Symbol;
+use crate::types::token::Token;
+use crate::types::token::TokenType::{Name, Operator};
+
+use crate::types::symbol::operator::Operator::{Assign, Comma, LeftParen, RightParen};
+
+#[derive(Debug, PartialEq)]
+/// A single line of input after parsing.
+pub(crate) enum Statement {
+    /// An expression to be displayed, with every variable and function from the variable table substituted in and every command like `der` carried out.
+    Expression(Tree<Symbol>),
+    /// A definition of a variable (if `var.args` is empty) or function that should be added to the variable table.
+    Assignment {
+        /// Name the definition is stored under.
+        name: String,
+        /// The expression assigned, along with the names of its arguments.
+        var: Var,
+    },
+}
+
+/// Parses a line of tokens into either an assignment or an expression.
+///
+/// The right hand side of an assignment is expanded with the definitions in `var_table` when it is defined, so redefining a name later does not change anything that was defined using it. Names that aren't defined anywhere are kept as symbolic variables.
+pub(crate) fn parse_statement(
+    tokens: Vec<Token>,
+    var_table: &VarTable,
+) -> Result<Statement, CASError> {
+    match assignment_target(&tokens)? {
+        Some((name, args, rhs_start)) => {
+            let rhs = tokens[rhs_start..].to_vec();
+            let mut names = args.clone();
+            names.extend(free_variables(&rhs, var_table, &args));
+
+            let expr = parse_expr(rhs, var_table, names)?;
+            let expr = eval_commands(&expand(&expr, var_table, &args)?)?;
+
+            Ok(Statement::Assignment {
+                name,
+                var: Var {
+                    expr: expr.map(&|symbol: &Symbol| symbol.symbol_type.clone()),
+                    args,
+                },
+            })
+        }
+        None => {
+            let names = free_variables(&tokens, var_table, &[]);
+            let expr = parse_expr(tokens, var_table, names)?;
+            Ok(Statement::Expression(eval_commands(&expand(
+                &expr, var_table, &[],
+            )?)?))
+        }
+    }
+}
+
+/// Finds the names used as variables that are not defined anywhere, which are treated as symbolic variables like the `x` in `der(x^2, x)`.
+///
+/// Names followed by a parenthesis are function calls, so they are left for the parser to report if they're unknown.
+fn free_variables(tokens: &[Token], var_table: &VarTable, args: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        if let Name(name) = &token.token_type {
+            let is_call = matches!(
+                tokens.get(idx + 1).map(|token| &token.token_type),
+                Some(Operator(LeftParen))
+            );
+            if !is_call
+                && !var_table.contains_key(name)
+                && !args.contains(name)
+                && !names.contains(name)
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Checks if the tokens start with `name =` or `name(arg, arg, ...) =`.
+///
+/// If they do, returns the name being assigned to, the names of the arguments, and the index of the first token after the `=`. Anything else is treated as an expression, so a misplaced `=` is reported by the expression parser.
+fn assignment_target(tokens: &[Token]) -> Result<Option<(String, Vec<String>, usize)>, CASError> {
+    let name = match tokens.first() {
+        Some(Token {
+            token_type: Name(name),
+            ..
+        }) => name.to_string(),
+        _ => return Ok(None),
+    };
+
+    match tokens.get(1).map(|token| &token.token_type) {
+        Some(Operator(Assign)) => return Ok(Some((name, vec![], 2))),
+        Some(Operator(LeftParen)) => {}
+        _ => return Ok(None),
+    }
+
+    let mut args: Vec<String> = vec![];
+    let mut repeated: Option<CASError> = None;
+    //only an error if this turns out to be a definition and not a call like f(x, x)
+    let mut idx = 2;
+    loop {
+        match tokens.get(idx) {
+            Some(Token {
+                token_type: Name(arg),
+                line_pos,
+            }) => {
+                if args.contains(arg) && repeated.is_none() {
+                    repeated = Some(CASError {
+                        line_pos: *line_pos,
+                        kind: CASErrorKind::RepeatedArgument {
+                            name: arg.to_string(),
+                        },
+                    });
+                }
+                args.push(arg.to_string());
+            }
+            _ => return Ok(None),
+        }
+
+        match tokens.get(idx + 1).map(|token| &token.token_type) {
+            Some(Operator(Comma)) => idx += 2,
+            Some(Operator(RightParen)) => break,
+            _ => return Ok(None),
+        }
+    }
+
+    match (tokens.get(idx + 2).map(|token| &token.token_type), repeated) {
+        (Some(Operator(Assign)), Some(err)) => Err(err),
+        (Some(Operator(Assign)), None) => Ok(Some((name, args, idx + 3))),
+        _ => Ok(None), //a call like f(x, y) + 2
+    }
+}
@@ -7,14 +7,16 @@ mod test {
     use crate::{
         parser::{
             expression::{into_postfix, PostFix},
+            parse_expr,
+            statement::{parse_statement, Statement},
             trees::Tree,
             vars::{Var, VarTable},
-            CASNum,
         },
         types::{
             cas_error::{print_error, CASError, CASErrorKind},
+            cas_num::{parse_lit, Number},
             symbol::{
-                function::Func,
+                function::{Func, ResFun},
                 operator::Operator::*,
                 Symbol,
                 SymbolType::{self, *},
@@ -24,6 +26,13 @@ mod test {
 
     use crate::scanner::tokenize;
 
+    /// The number a numeric literal stands for.
+    fn literal(lit: &str) -> Number {
+        let mut iter = lit.chars().enumerate().peekable();
+        let first_char = iter.next().unwrap().1;
+        parse_lit(first_char, &mut iter, &mut 0).unwrap().unwrap()
+    }
+
     fn symbols_to_postfix(symbols: VecDeque<(SymbolType, usize)>) -> VecDeque<Symbol> {
         return symbols
             .iter()
@@ -66,13 +75,13 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 0,
             ),
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 4,
             ),
@@ -120,6 +129,59 @@ mod test {
         });
 
         test_parser("[][][]][", err, None);
+
+        let err = Err(CASError {
+            kind: CASErrorKind::MismatchedParentheses,
+            line_pos: 5,
+        });
+
+        test_parser("(1, 2]", err, None);
+    }
+
+    #[test]
+    fn vectors() {
+        let symbols = VecDeque::from([
+            (
+                Num {
+                    value: Number::from(1),
+                },
+                1,
+            ),
+            (
+                Num {
+                    value: Number::from(2),
+                },
+                4,
+            ),
+            (Vector { dim: 2 }, 0),
+            (
+                Num {
+                    value: Number::from(3),
+                },
+                9,
+            ),
+            (Operator(Add), 7),
+        ]);
+
+        test_parser("[1, 2] + 3", Ok(symbols_to_postfix(symbols)), None);
+
+        let tree = parse_expr(
+            tokenize("[1 + 2, [3], []]").unwrap(),
+            &HashMap::new(),
+            vec![],
+        )
+        .unwrap();
+        let root = tree.root.0.borrow();
+        assert_eq!(root.data.symbol_type, Vector { dim: 3 });
+        let children: Vec<SymbolType> = root
+            .children
+            .iter()
+            .map(|child| child.0.borrow().data.symbol_type.clone())
+            .collect();
+        assert_eq!(
+            children,
+            vec![Operator(Add), Vector { dim: 1 }, Vector { dim: 0 }]
+        );
     }
 
     #[test]
@@ -136,7 +198,7 @@ mod test {
                 String::from("x").to_string(),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -158,7 +220,7 @@ mod test {
                 String::from(String::from("y")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
                     args: vec![],
                 },
@@ -179,7 +241,7 @@ mod test {
                 String::from(String::from("x")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
                     args: vec![],
                 },
@@ -192,19 +254,19 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 0,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 4,
             ),
             (
                 Num {
-                    value: CASNum::from(5.05),
+                    value: literal("5.05"),
                 },
                 11,
             ),
@@ -217,19 +279,19 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(230),
+                    value: Number::from(230),
                 },
                 2,
             ),
             (
                 Num {
-                    value: CASNum::from(0.012),
+                    value: literal("0.012"),
                 },
                 10,
             ),
             (
                 Num {
-                    value: CASNum::from(23.2),
+                    value: literal("23.2"),
                 },
                 17,
             ),
@@ -247,19 +309,19 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 0,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 5,
             ),
             (
                 Num {
-                    value: CASNum::from(5.05),
+                    value: literal("5.05"),
                 },
                 12,
             ),
@@ -272,20 +334,20 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 1,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 5,
             ),
             (Operator(Add), 3),
             (
                 Num {
-                    value: CASNum::from(5.05),
+                    value: literal("5.05"),
                 },
                 13,
             ),
@@ -299,7 +361,7 @@ mod test {
     fn minus_sign() {
         let symbols = VecDeque::from([(
             Num {
-                value: CASNum::from(-2),
+                value: Number::from(-2),
             },
             1,
         )]);
@@ -308,7 +370,7 @@ mod test {
 
         let symbols = VecDeque::from([(
             Num {
-                value: CASNum::from(-2),
+                value: Number::from(-2),
             },
             2,
         )]);
@@ -318,13 +380,13 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(-2),
+                    value: Number::from(-2),
                 },
                 2,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 6,
             ),
@@ -347,7 +409,7 @@ mod test {
             String::from("x").to_string(),
             Var {
                 expr: Tree::from(SymbolType::Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 }),
                 args: vec![],
             },
@@ -377,7 +439,7 @@ mod test {
             (Operator(Neg), 0),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 6,
             ),
@@ -393,13 +455,13 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(-2),
+                    value: Number::from(-2),
                 },
                 2,
             ),
             (
                 Num {
-                    value: CASNum::from(-3),
+                    value: Number::from(-3),
                 },
                 7,
             ),
@@ -411,13 +473,13 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(-2),
+                    value: Number::from(-2),
                 },
                 2,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 6,
             ),
@@ -442,7 +504,7 @@ mod test {
             String::from(String::from("f")),
             Var {
                 expr: Tree::from(SymbolType::Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 }),
 
                 args: vec![
@@ -456,19 +518,19 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 2,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 5,
             ),
             (
                 Num {
-                    value: CASNum::from(4),
+                    value: Number::from(4),
                 },
                 8,
             ),
@@ -490,19 +552,19 @@ mod test {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 4,
             ),
             (
                 Num {
-                    value: CASNum::from(3),
+                    value: Number::from(3),
                 },
                 7,
             ),
             (
                 Num {
-                    value: CASNum::from(4),
+                    value: Number::from(4),
                 },
                 10,
             ),
@@ -519,7 +581,7 @@ mod test {
             String::from(String::from("foo")),
             Var {
                 expr: Tree::from(SymbolType::Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 }),
 
                 args: vec![
@@ -577,7 +639,7 @@ mod test {
                 String::from(String::from("foo")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![
@@ -590,7 +652,7 @@ mod test {
                 String::from(String::from("bar")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from(String::from("a"))],
@@ -600,7 +662,7 @@ mod test {
                 String::from(String::from("baz")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from("a")],
@@ -610,7 +672,7 @@ mod test {
                 String::from(String::from("x")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -620,7 +682,7 @@ mod test {
                 String::from(String::from("y")),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -639,7 +701,7 @@ mod test {
                 String::from("foo"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![String::from("a"), String::from("b")],
@@ -649,7 +711,7 @@ mod test {
                 String::from("bar"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from("a")],
@@ -659,7 +721,7 @@ mod test {
                 String::from("baz"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from("a")],
@@ -669,7 +731,7 @@ mod test {
                 String::from("x"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -716,7 +778,7 @@ mod test {
                 String::from("foo"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![String::from("a"), String::from("b")],
@@ -726,7 +788,7 @@ mod test {
                 String::from("bar"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from("a")],
@@ -736,7 +798,7 @@ mod test {
                 String::from("baz"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(1),
+                        value: Number::from(1),
                     }),
 
                     args: vec![String::from("a")],
@@ -746,7 +808,7 @@ mod test {
                 String::from("x"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -756,7 +818,7 @@ mod test {
                 String::from("y"),
                 Var {
                     expr: Tree::from(SymbolType::Num {
-                        value: CASNum::from(2),
+                        value: Number::from(2),
                     }),
 
                     args: vec![],
@@ -771,24 +833,302 @@ mod test {
         );
     }
 
+    #[test]
+    fn subtraction_precedence() {
+        let symbols = VecDeque::from([
+            (
+                Num {
+                    value: Number::from(2),
+                },
+                0,
+            ),
+            (
+                Num {
+                    value: Number::from(3),
+                },
+                4,
+            ),
+            (Operator(Exp), 2),
+            (
+                Num {
+                    value: Number::from(1),
+                },
+                8,
+            ),
+            (Operator(Sub), 6),
+        ]);
+
+        test_parser("2 ^ 3 - 1", Ok(symbols_to_postfix(symbols)), None);
+    }
+
+    #[test]
+    fn tree_argument_order() {
+        let tree = parse_expr(tokenize("5 - 2").unwrap(), &HashMap::new(), vec![]).unwrap();
+        let root = tree.root.0.borrow();
+        assert_eq!(root.data.symbol_type, Operator(Sub));
+
+        let children: Vec<SymbolType> = root
+            .children
+            .iter()
+            .map(|child| child.0.borrow().data.symbol_type.clone())
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                Num {
+                    value: Number::from(5)
+                },
+                Num {
+                    value: Number::from(2)
+                }
+            ]
+        );
+    }
+
+    fn run_statement(code: &str, var_table: &mut VarTable) -> Result<Statement, CASError> {
+        let statement = parse_statement(tokenize(code).unwrap(), var_table)?;
+        if let Statement::Assignment { name, var } = &statement {
+            var_table.insert(name.clone(), var.clone());
+        }
+        Ok(statement)
+    }
+
+    fn expression(code: &str, var_table: &VarTable) -> Tree<Symbol> {
+        parse_expr(tokenize(code).unwrap(), var_table, vec![]).unwrap()
+    }
+
+    #[test]
+    fn assignments() {
+        let mut var_table: VarTable = HashMap::new();
+
+        let statement = run_statement("x = 3", &mut var_table);
+        assert_eq!(
+            statement,
+            Ok(Statement::Assignment {
+                name: String::from("x"),
+                var: Var {
+                    expr: Tree::from(SymbolType::Num {
+                        value: Number::from(3)
+                    }),
+                    args: vec![],
+                }
+            })
+        );
+
+        let statement = run_statement("f(a, b) = a^2 + b * x", &mut var_table);
+        assert_eq!(
+            statement,
+            Ok(Statement::Assignment {
+                name: String::from("f"),
+                var: Var {
+                    expr: parse_expr(
+                        tokenize("a^2 + b * 3").unwrap(),
+                        &HashMap::new(),
+                        vec![String::from("a"), String::from("b")]
+                    )
+                    .unwrap()
+                    .map(&|symbol: &Symbol| symbol.symbol_type.clone()),
+                    args: vec![String::from("a"), String::from("b")],
+                }
+            })
+        );
+
+        let empty = HashMap::new();
+        assert_eq!(
+            run_statement("f(2, x) + 1", &mut var_table),
+            Ok(Statement::Expression(expression(
+                "(2^2 + 3 * 3) + 1",
+                &empty
+            )))
+        );
+
+        //redefining x doesn't change f, since f was expanded when it was defined
+        run_statement("x = 10", &mut var_table).unwrap();
+        assert_eq!(
+            run_statement("f(1, x)", &mut var_table),
+            Ok(Statement::Expression(expression("1^2 + 10 * 3", &empty)))
+        );
+
+        //arguments shadow variables
+        run_statement("g(x) = f(x, x + 1)", &mut var_table).unwrap();
+        assert_eq!(
+            run_statement("g(5)", &mut var_table),
+            Ok(Statement::Expression(expression(
+                "5^2 + (5 + 1) * 3",
+                &empty
+            )))
+        );
+    }
+
+    #[test]
+    fn invalid_assignments() {
+        let mut var_table: VarTable = HashMap::new();
+
+        assert_eq!(
+            run_statement("f(x, y, x) = 2", &mut var_table),
+            Err(CASError {
+                line_pos: 8,
+                kind: CASErrorKind::RepeatedArgument {
+                    name: String::from("x")
+                },
+            })
+        );
+
+        assert_eq!(
+            run_statement("2 = 3", &mut var_table),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::AssignmentInExpression,
+            })
+        );
+
+        //names that aren't defined are symbolic variables, see undefined_names, but calls to undefined functions are errors
+        assert_eq!(
+            run_statement("y = z(2)", &mut var_table),
+            Err(CASError {
+                line_pos: 4,
+                kind: CASErrorKind::UnknownSymbol {
+                    symbol: String::from("z")
+                },
+            })
+        );
+        assert!(var_table.is_empty());
+    }
+
+    #[test]
+    fn undefined_names() {
+        let mut var_table: VarTable = HashMap::new();
+
+        //an undefined name is kept as a symbolic variable, so it can be used like the x in der(x^2, x)
+        assert_eq!(
+            run_statement("y = z", &mut var_table),
+            Ok(Statement::Assignment {
+                name: String::from("y"),
+                var: Var {
+                    expr: Tree::from(SymbolType::Variable {
+                        name: String::from("z")
+                    }),
+                    args: vec![],
+                }
+            })
+        );
+        assert_eq!(
+            run_statement("y + 1", &mut var_table),
+            run_statement("z + 1", &mut HashMap::new())
+        );
+    }
+
+    #[test]
+    fn local_variables() {
+        let mut var_table: VarTable = HashMap::new();
+        run_statement("x = 3", &mut var_table).unwrap();
+        run_statement("y = 2", &mut var_table).unwrap();
+
+        //the variables of commands are local to the call, so they aren't replaced by definitions of the same name
+        for (code, desired_result) in [
+            ("der(x^2, x)", "der(x^2, x)"),
+            ("der(x^2 * y, x, y)", "der(x^2 * y, x, y)"),
+            ("der(x^3, x, 2) + x", "der(x^3, x, 2) + 3"),
+            ("sym_int(x * y, x)", "sym_int(x * 2, x)"),
+            ("grad(x * y, [x, y])", "grad(x * y, [x, y])"),
+            ("jacob([x * y, x], [x, y])", "jacob([x * y, x], [x, y])"),
+            ("div([x, y], [x, y])", "div([x, y], [x, y])"),
+        ] {
+            assert_eq!(
+                run_statement(code, &mut var_table),
+                run_statement(desired_result, &mut HashMap::new()),
+                "{}",
+                code
+            );
+        }
+
+        //but the bounds of a definite integral aren't part of the call's variables
+        assert_eq!(
+            run_statement("def_int(x, x, 0, x)", &mut var_table),
+            run_statement("def_int(x, x, 0, 3)", &mut HashMap::new())
+        );
+
+        //and arguments of functions being defined still shadow variables
+        run_statement("f(a) = der(a * x, x)", &mut var_table).unwrap();
+        assert_eq!(
+            run_statement("f(y)", &mut var_table),
+            run_statement("2", &mut HashMap::new())
+        );
+    }
+
+    #[test]
+    fn argument_counts() {
+        let mut var_table: VarTable = HashMap::new();
+        run_statement("f(a, b) = a * b", &mut var_table).unwrap();
+
+        assert_eq!(
+            run_statement("sin(x, y)", &mut var_table),
+            Err(CASError {
+                line_pos: 2,
+                kind: CASErrorKind::WrongNumberOfArgs {
+                    args_given: 2,
+                    args_needed: 1,
+                    func_name: ResFun::Sin.to_string()
+                },
+            })
+        );
+        assert_eq!(
+            run_statement("2 + der(x)", &mut var_table),
+            Err(CASError {
+                line_pos: 6,
+                kind: CASErrorKind::WrongNumberOfArgs {
+                    args_given: 1,
+                    args_needed: 2,
+                    func_name: ResFun::Der.to_string()
+                },
+            })
+        );
+        assert_eq!(
+            run_statement("f(1, 2, 3)", &mut var_table),
+            Err(CASError {
+                line_pos: 0,
+                kind: CASErrorKind::WrongNumberOfArgs {
+                    args_given: 3,
+                    args_needed: 2,
+                    func_name: String::from("f")
+                },
+            })
+        );
+        assert!(run_statement("f(sin(x), 2)", &mut var_table).is_ok());
+
+        let tree = parse_expr(
+            tokenize("der(x^2, x, 2, y)").unwrap(),
+            &var_table,
+            vec![String::from("x"), String::from("y")],
+        )
+        .unwrap();
+        assert_eq!(
+            tree.root.0.borrow().data.symbol_type,
+            Function(Func::ResFun {
+                fun: ResFun::Der,
+                num_args: 4
+            })
+        );
+    }
+
     #[test]
     fn stress_test() {
         let symbols = VecDeque::from([
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 0,
             ),
             (
                 Num {
-                    value: CASNum::from(1),
+                    value: Number::from(1),
                 },
                 4,
             ),
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 6,
             ),
@@ -796,25 +1136,25 @@ mod test {
             (Operator(Neg), 2),
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 11,
             ),
             (
                 Num {
-                    value: CASNum::from(5),
+                    value: Number::from(5),
                 },
                 13,
             ),
             (
                 Num {
-                    value: CASNum::from(2),
+                    value: Number::from(2),
                 },
                 17,
             ),
             (
                 Num {
-                    value: CASNum::from(400),
+                    value: Number::from(400),
                 },
                 21,
             ),
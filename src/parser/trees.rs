@@ -18,6 +18,22 @@ impl<T> TreeNodeRef<T> {
     pub fn new_from_node(node: TreeNode<T>) -> Self {
         TreeNodeRef(Rc::from(RefCell::from(node)))
     }
+
+    /// Builds a new subtree with the same shape whose data is `f` applied to the data of each node.
+    pub(crate) fn map<U>(&self, f: &impl Fn(&T) -> U) -> TreeNodeRef<U> {
+        let node = self.0.borrow();
+        TreeNodeRef::new_from_node(TreeNode {
+            data: f(&node.data),
+            children: node.children.iter().map(|child| child.map(f)).collect(),
+        })
+    }
+}
+
+impl<T: Clone> TreeNodeRef<T> {
+    /// Copies the whole subtree, unlike `clone` which only copies the reference to it.
+    pub(crate) fn deep_clone(&self) -> Self {
+        self.map(&T::clone)
+    }
 }
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub(crate) struct TreeNode<T> {
@@ -31,6 +47,24 @@ pub(crate) struct Tree<T> {
     pub(crate) root: TreeNodeRef<T>,
 }
 
+impl<T> Tree<T> {
+    /// Builds a new tree with the same shape whose data is `f` applied to the data of each node.
+    pub(crate) fn map<U>(&self, f: &impl Fn(&T) -> U) -> Tree<U> {
+        Tree {
+            root: self.root.map(f),
+        }
+    }
+}
+
+impl<T: Clone> Clone for Tree<T> {
+    //trees share their nodes through Rc, so a derived clone would let changes to the copy show up in the original
+    fn clone(&self) -> Self {
+        Tree {
+            root: self.root.deep_clone(),
+        }
+    }
+}
+
 impl Hash for TreeNode<Symbol> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.data.hash(state);
@@ -1,10 +1,17 @@
 use std::{collections::HashMap, iter::zip};
 
-use crate::types::{cas_error::CASErrorKind, cas_num::CASNum, symbol::SymbolType};
+use crate::types::{
+    cas_error::{CASError, CASErrorKind},
+    cas_num::CASNum,
+    symbol::{
+        function::{Func, ResFun},
+        Symbol, SymbolType,
+    },
+};
 
-use super::trees::{Tree, TreeNodeRef};
+use super::trees::{Tree, TreeNode, TreeNodeRef};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub(crate) struct Var {
     pub(crate) expr: Tree<SymbolType>,
     pub(crate) args: Vec<String>, //if args is empty it is a numeric or symbolic variable, 2, 3, pi, x, etc.
@@ -17,7 +24,7 @@ impl<'a> Var {
     pub(crate) fn apply<'b>(
         mut self,
         func_name: String,
-        arg_vals: Box<[CASNum]>,
+        arg_vals: Box<[Tree<SymbolType>]>,
     ) -> Result<Tree<SymbolType>, CASErrorKind>
     where
         'a: 'b,
@@ -30,7 +37,7 @@ impl<'a> Var {
             });
         }
 
-        let mut args_map: HashMap<String, CASNum> = HashMap::new();
+        let mut args_map: HashMap<String, Tree<SymbolType>> = HashMap::new();
         for (name, value) in zip(self.args, arg_vals) {
             args_map.insert(name, value);
         }
@@ -42,16 +49,16 @@ impl<'a> Var {
     }
 }
 
-fn apply(expr: &mut TreeNodeRef<SymbolType>, args: &HashMap<String, CASNum>) {
+fn apply(expr: &mut TreeNodeRef<SymbolType>, args: &HashMap<String, Tree<SymbolType>>) {
     //replaces variables in expression with values given in args
     if expr.0.borrow().children.is_empty() {
-        if let SymbolType::Variable { name } = &expr.0.borrow().data {
-            if let Some(value) = args.get(name) {
-                expr.0.borrow_mut().data = SymbolType::Num {
-                    value: value.clone(),
-                    //TODO: get rid of this clone
-                };
-            }
+        let value = match &expr.0.borrow().data {
+            SymbolType::Variable { name } => args.get(name),
+            _ => None,
+        };
+        if let Some(value) = value {
+            *expr = value.root.deep_clone();
+            //every use of an argument gets its own copy
         }
     } else {
         for child in &mut expr.0.borrow_mut().children {
@@ -59,3 +66,113 @@ fn apply(expr: &mut TreeNodeRef<SymbolType>, args: &HashMap<String, CASNum>) {
         }
     }
 }
+
+/// Replaces every variable and user defined function call in `tree` with its definition from `var_table`.
+///
+/// Names in `bound` are the arguments of a function being defined, so they are left alone even if the table has a variable with the same name. The variables a command like `der(x^2, x)` works with are bound the same way inside the call, see [local_variables].
+pub(crate) fn expand(
+    tree: &Tree<Symbol>,
+    var_table: &VarTable,
+    bound: &[String],
+) -> Result<Tree<Symbol>, CASError> {
+    Ok(Tree {
+        root: expand_node(&tree.root, var_table, bound)?,
+    })
+}
+
+/// Expands a single node of a tree after expanding its children, see [expand].
+fn expand_node(
+    node: &TreeNodeRef<Symbol>,
+    var_table: &VarTable,
+    bound: &[String],
+) -> Result<TreeNodeRef<Symbol>, CASError> {
+    let node = node.0.borrow();
+    let (locals, scope) = local_variables(&node);
+    let mut inner_bound = bound.to_vec();
+    inner_bound.extend(locals);
+    let children = node
+        .children
+        .iter()
+        .enumerate()
+        .map(|(idx, child)| match scope.contains(&idx) {
+            true => expand_node(child, var_table, &inner_bound),
+            false => expand_node(child, var_table, bound),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let line_pos = node.data.line_pos;
+
+    let definition = match &node.data.symbol_type {
+        SymbolType::Variable { name } if !bound.contains(name) => {
+            var_table.get(name).map(|var| (name, var))
+        }
+        SymbolType::Function(Func::Function { name, .. }) => {
+            var_table.get(name).map(|var| (name, var))
+        }
+        _ => None,
+    };
+
+    match definition {
+        None => Ok(TreeNodeRef::new_from_node(TreeNode {
+            data: node.data.clone(),
+            children,
+        })),
+        Some((name, var)) => {
+            let arg_vals: Box<[Tree<SymbolType>]> = children
+                .iter()
+                .map(|child| child.map(&|symbol: &Symbol| symbol.symbol_type.clone()))
+                .map(|root| Tree { root })
+                .collect();
+
+            let expr = var
+                .clone()
+                .apply(name.clone(), arg_vals)
+                .map_err(|kind| CASError { line_pos, kind })?;
+
+            Ok(expr.root.map(&|symbol_type: &SymbolType| Symbol {
+                symbol_type: symbol_type.clone(),
+                line_pos,
+            }))
+        }
+    }
+}
+
+/// Finds the variables a command works with, like the `x` in `der(x^2, x)`, which are local to the call and not replaced by definitions of the same name.
+///
+/// Returns the names along with the indices of the arguments they're bound in. They're bound in the expression the command works on and in the arguments naming them, but not in the others, so the bounds of `def_int(x^2, x, 0, x)` still use the definition of `x`. Every argument of `der` after the expression that's a single name is a variable to differentiate with respect to, even if it's defined as a number.
+fn local_variables(node: &TreeNode<Symbol>) -> (Vec<String>, Vec<usize>) {
+    let var_args = match &node.data.symbol_type {
+        SymbolType::Function(Func::ResFun {
+            fun: ResFun::Der, ..
+        }) => 1..node.children.len(),
+        SymbolType::Function(Func::ResFun {
+            fun:
+                ResFun::SymInt
+                | ResFun::DefInt
+                | ResFun::Grad
+                | ResFun::Div
+                | ResFun::Curl
+                | ResFun::Jacob,
+            ..
+        }) => 1..2,
+        _ => return (vec![], vec![]),
+    };
+
+    let mut names: Vec<String> = vec![];
+    let mut scope = vec![0];
+    for idx in var_args {
+        let arg = node.children[idx].0.borrow();
+        match &arg.data.symbol_type {
+            SymbolType::Variable { name } => names.push(name.to_string()),
+            //a vector of variables, like the [x, y, z] in grad(f, [x, y, z])
+            SymbolType::Vector { .. } => names.extend(arg.children.iter().filter_map(|element| {
+                match &element.0.borrow().data.symbol_type {
+                    SymbolType::Variable { name } => Some(name.to_string()),
+                    _ => None,
+                }
+            })),
+            _ => continue,
+        }
+        scope.push(idx);
+    }
+    (names, scope)
+}
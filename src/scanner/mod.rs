@@ -2,6 +2,7 @@ use std::iter::{Enumerate, Peekable};
 use std::str;
 
 use crate::types::cas_error::{CASError, CASErrorKind};
+use crate::types::cas_num::parse_lit;
 use crate::types::symbol::constant::RESERVED_CONSTANTS;
 use crate::types::symbol::function::RESERVED_FUNCTIONS;
 use crate::types::symbol::operator::*;
@@ -103,80 +104,13 @@ fn parse_number(
     iter: &mut Peekable<Enumerate<str::Chars>>,
     line_pos: &mut usize,
 ) -> Option<Result<Token, CASError>> {
-    //parses numerical literals like 3.4, 1234, -1523
+    //parses numerical literals like 3.4, 1234, 1_000, 2.5e-3 and 0xFF
 
-    //minus 1 since peek is following char
-
-    if next_char.is_numeric() || next_char == '.' {
-        let token_type = match get_next_number(next_char, iter, line_pos) {
-            //check if its a float, int, or something that cant be either
-            Ok(Token {
-                token_type: Num(number),
-                ..
-            }) => Num(number),
-
-            Err(lit) => {
-                return Some(Err(CASError {
-                    line_pos: *line_pos,
-                    kind: CASErrorKind::MalformedNumericLiteral { lit },
-                }))
-            }
-            _ => {
-                return Some(Err(CASError {
-                    line_pos: *line_pos,
-                    kind: CASErrorKind::SyntaxError,
-                }))
-            }
-        };
-        return Some(Ok(Token {
-            token_type,
-            line_pos: *line_pos,
-        }));
-    }
-    None
-}
-
-fn get_next_number(
-    chr: char,
-    iter: &mut Peekable<Enumerate<str::Chars>>,
-    line_pos: &mut usize,
-) -> Result<Token, String> {
-    let mut num: String = chr.to_string();
-
-    while let Some(&(_, chr)) = iter.peek() {
-        if !chr.is_numeric() && chr != '.' {
-            break;
-        }
-        num.push(chr);
-        iter.next();
-        *line_pos += 1;
-    }
-    let int_parse = num.parse::<i64>();
-    if let Ok(int) = int_parse {
-        return Ok(Token {
-            token_type: Num(int.into()),
-            line_pos: *line_pos,
-        });
-    }
-    let float_parse = num.parse::<f64>();
-    match float_parse {
-        Ok(float) => Ok(Token {
-            token_type: Num(float.into()),
-            line_pos: *line_pos,
-        }),
-        Err(_) => Err(num),
-    }
-    /*
-    let parse : Result<CASNum, Error> = num_lit::parse_lit(num);
-    match parse {
-        Ok(x) => {
-            return Ok(Token {
-                token_type: Num(x),
-                line_pos: *line_pos,
-            })
-        }
-        Err(_) => return Err(num),
-    } */
+    let number = parse_lit(next_char, iter, line_pos)?;
+    Some(number.map(|number| Token {
+        token_type: Num(number),
+        line_pos: *line_pos,
+    }))
 }
 
 fn skip_over_whitespace(
@@ -230,9 +164,9 @@ fn parse_names(
     iter: &mut Peekable<Enumerate<str::Chars>>,
     line_pos: &mut usize,
 ) -> Option<Token> {
-    //parses variable or function names or constants (alphabetic chars)
+    //parses variable or function names or constants (alphabetic chars, or ∞)
 
-    if next_char.is_alphabetic() || next_char == '_' {
+    if next_char.is_alphabetic() || next_char == '_' || next_char == '∞' {
         let mut token_type: TokenType = Eof;
         let word: String = next_char.to_string() + &get_next_word(iter, line_pos);
         if let Some(func) = RESERVED_FUNCTIONS.get(&word) {
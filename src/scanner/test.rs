@@ -6,7 +6,7 @@ mod test {
 
     use crate::types::cas_error::{CASError, CASErrorKind};
 
-    use crate::types::cas_num::CASNum;
+    use crate::types::cas_num::{parse_lit, Number};
     use crate::types::symbol::function::ResFun;
     use crate::types::symbol::operator::Operator::*;
     use crate::types::token::Token;
@@ -29,6 +29,13 @@ mod test {
         }
     }
 
+    /// The number a numeric literal stands for.
+    fn literal(lit: &str) -> Number {
+        let mut iter = lit.chars().enumerate().peekable();
+        let first_char = iter.next().unwrap().1;
+        parse_lit(first_char, &mut iter, &mut 0).unwrap().unwrap()
+    }
+
     fn make_token(token_type: TokenType, line_pos: usize) -> Token {
         Token {
             token_type,
@@ -43,7 +50,7 @@ mod test {
             Ok(vec![
                 (Name("x".to_string()), 0),
                 (Operator(Assign), 2),
-                (Num(CASNum::from(2)), 4),
+                (Num(Number::from(2)), 4),
             ]),
         );
     }
@@ -60,11 +67,11 @@ mod test {
                 (Name("y".to_string()), 4),
                 (Operator(RightParen), 5),
                 (Operator(Assign), 7),
-                (Num(CASNum::from(2)), 9),
+                (Num(Number::from(2)), 9),
                 (Operator(Mult), 11),
                 (Name("x".to_string()), 13),
                 (Operator(Add), 15),
-                (Num(CASNum::from(3)), 17),
+                (Num(Number::from(3)), 17),
                 (Operator(Mult), 19),
                 (Name("y".to_string()), 21),
             ]),
@@ -77,11 +84,11 @@ mod test {
             "calc 3 * x - 5",
             Ok(vec![
                 (ResFun(ResFun::Calc), 3),
-                (Num(CASNum::from(3)), 5),
+                (Num(Number::from(3)), 5),
                 (Operator(Mult), 7),
                 (Name("x".to_string()), 9),
                 (Operator(Sub), 11),
-                (Num(CASNum::from(5)), 13),
+                (Num(Number::from(5)), 13),
             ]),
         );
 
@@ -89,11 +96,11 @@ mod test {
             "der 3 * x - 5, x",
             Ok(vec![
                 (ResFun(ResFun::Der), 2),
-                (Num(CASNum::from(3)), 4),
+                (Num(Number::from(3)), 4),
                 (Operator(Mult), 6),
                 (Name("x".to_string()), 8),
                 (Operator(Sub), 10),
-                (Num(CASNum::from(5)), 12),
+                (Num(Number::from(5)), 12),
                 (Operator(Comma), 13),
                 (Name("x".to_string()), 15),
             ]),
@@ -108,14 +115,14 @@ mod test {
                 (Name("x-y_z".to_string()), 4),
                 (Operator(Assign), 6),
                 (Operator(Sub), 8),
-                (Num(CASNum::from(5)), 9),
+                (Num(Number::from(5)), 9),
                 (Operator(Add), 11),
-                (Num(CASNum::from(3)), 13),
+                (Num(Number::from(3)), 13),
                 (Operator(Sub), 15),
-                (Num(CASNum::from(2)), 17),
+                (Num(Number::from(2)), 17),
                 (Operator(Sub), 19),
                 (Operator(Sub), 21),
-                (Num(CASNum::from(4)), 22),
+                (Num(Number::from(4)), 22),
             ]),
         );
     }
@@ -128,7 +135,7 @@ mod test {
                 (Operator(Sub), 0),
                 (Name("x".to_string()), 1),
                 (Operator(Assign), 3),
-                (Num(CASNum::from(2)), 5),
+                (Num(Number::from(2)), 5),
             ]),
         );
     }
@@ -141,7 +148,7 @@ mod test {
                 (Name("y".to_string()), 0),
                 (Operator(Assign), 2),
                 (Operator(Sub), 4),
-                (Num(CASNum::from(102342.0)), 11),
+                (Num(literal("102342.")), 11),
             ]),
         );
 
@@ -150,7 +157,7 @@ mod test {
             Ok(vec![
                 (Name("x".to_string()), 0),
                 (Operator(Assign), 2),
-                (Num(CASNum::from(3.3343)), 9),
+                (Num(literal("3.3343")), 9),
             ]),
         );
 
@@ -159,7 +166,7 @@ mod test {
             Ok(vec![
                 (Name("y".to_string()), 0),
                 (Operator(Assign), 2),
-                (Num(CASNum::from(0.102342)), 10),
+                (Num(literal("0.102342")), 10),
             ]),
         );
 
@@ -174,6 +181,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn integer_literals() {
+        run_test(
+            "0xFF + 1_000_000 * 0b101",
+            Ok(vec![
+                (Num(Number::from(255)), 3),
+                (Operator(Add), 5),
+                (Num(Number::from(1_000_000)), 15),
+                (Operator(Mult), 17),
+                (Num(Number::from(5)), 23),
+            ]),
+        );
+
+        run_test(
+            "123456789012345678901234567890 - 2.5e-3",
+            Ok(vec![
+                (Num(Number::from(123456789012345678901234567890_i128)), 29),
+                (Operator(Sub), 31),
+                (Num(literal("0.0025")), 38),
+            ]),
+        );
+
+        run_test(
+            "x = 0b12",
+            Err(vec![CASError {
+                line_pos: 8,
+                kind: CASErrorKind::MalformedNumericLiteral {
+                    lit: "0b12".to_string(),
+                },
+            }]),
+        );
+    }
+
     #[test]
     fn comparison() {
         run_test(
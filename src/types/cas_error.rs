@@ -14,6 +14,18 @@ pub(crate) enum CASErrorKind {
         name:  String
     },
     AssignmentInExpression,
+    /// A function definition uses the same argument name twice, like `f(x, x) = x`.
+    RepeatedArgument{
+        /// The argument name that was repeated.
+        name: String
+    },
+    /// A line of a rule file isn't written as `lhs => rhs` or `lhs => rhs if condition`.
+    MalformedRule,
+    /// The right hand side or condition of a rewrite rule uses a pattern variable that the left hand side doesn't, like `a * 0 => b`.
+    UnboundPatternVariable{
+        /// Name of the variable.
+        name: String
+    },
     UnknownSymbol{
         symbol: String
     },
@@ -30,6 +42,64 @@ pub(crate) enum CASErrorKind {
     CommandInExpression{
         command: Token
     },
+    /// A function like `der` was given something other than a variable where it needed one, like `der(x^2, 3)`.
+    ExpectedVariable{
+        /// The symbol given instead of a variable.
+        found: String
+    },
+    /// A function was given an argument with the right type but an unusable value, like a negative order in `der(x^2, x, -1)`.
+    InvalidArgument{
+        /// The argument that was given.
+        arg: String,
+        /// The function it was given to.
+        func_name: String
+    },
+    /// Rewriting an expression didn't reach a point where no more rules apply, usually because two rules undo each other.
+    StepLimitReached{
+        /// How many rewrites were made before giving up.
+        steps: usize
+    },
+    /// An expression contains a function or operator with no derivative, like `x < 3`.
+    CannotDifferentiate{
+        /// The symbol that could not be differentiated.
+        symbol: String
+    },
+    /// An expression contains a symbol that has no numeric value, like an undefined variable or `x < 3`.
+    CannotEvaluate{
+        /// The symbol that could not be evaluated.
+        symbol: String
+    },
+    /// Vectors with different numbers of elements were combined, like `[1, 2] + [1, 2, 3]`.
+    DimensionMismatch{
+        /// The number of elements that was needed.
+        expected: usize,
+        /// The number of elements that was given.
+        found: usize
+    },
+    /// A matrix with no inverse was inverted, or used to solve a system of equations with no unique solution.
+    SingularMatrix,
+    /// A matrix that doesn't have as many columns as rows was given to a function of square matrices, like `det`.
+    NotSquare{
+        /// Number of rows of the matrix.
+        rows: usize,
+        /// Number of columns of the matrix.
+        cols: usize
+    },
+    /// A definite integral crosses a pole where it diverges to ∞ on one side and -∞ on the other, like `def_int(1 / x, x, -1, 1)`.
+    DivergentIntegral{
+        /// Where the integrand has the pole.
+        point: String
+    },
+    /// An improper integral's antiderivative has no limit that could be found at an infinite bound, like `def_int(sin(x), x, 0, ∞)`.
+    NoLimit{
+        /// The bound without a limit.
+        point: String
+    },
+    /// A definite integral couldn't be approximated to the requested precision, usually because it diverges or oscillates too much.
+    IntegralDidNotConverge{
+        /// The number of significant digits asked for.
+        digits: usize
+    },
 }
 
 impl Display for CASErrorKind {
@@ -37,11 +107,11 @@ impl Display for CASErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             CASErrorKind::NoError => "No Error",
-            CASErrorKind::TypeError => "Type Error",
+            CASErrorKind::TypeError | CASErrorKind::ExpectedVariable{..} | CASErrorKind::InvalidArgument{..} => "Type Error",
             CASErrorKind::SyntaxError
             | CASErrorKind::MalformedNumericLiteral{..}
-            | CASErrorKind::MalformedVariableName{..} | CASErrorKind::AssignmentInExpression | CASErrorKind::UnknownSymbol{..} | CASErrorKind::MismatchedParentheses | CASErrorKind::NoExpressionGiven | CASErrorKind::InvalidCharacter{..} | CASErrorKind::CommandInExpression { .. } => "Syntax Error",
-            CASErrorKind::WrongNumberOfArgs{..} => "Runtime Error",
+            | CASErrorKind::MalformedVariableName{..} | CASErrorKind::AssignmentInExpression | CASErrorKind::RepeatedArgument{..} | CASErrorKind::MalformedRule | CASErrorKind::UnboundPatternVariable{..} | CASErrorKind::UnknownSymbol{..} | CASErrorKind::MismatchedParentheses | CASErrorKind::NoExpressionGiven | CASErrorKind::InvalidCharacter{..} | CASErrorKind::CommandInExpression { .. } => "Syntax Error",
+            CASErrorKind::WrongNumberOfArgs{..} | CASErrorKind::CannotDifferentiate{..} | CASErrorKind::CannotEvaluate{..} | CASErrorKind::StepLimitReached{..} | CASErrorKind::DimensionMismatch{..} | CASErrorKind::SingularMatrix | CASErrorKind::NotSquare{..} | CASErrorKind::DivergentIntegral{..} | CASErrorKind::NoLimit{..} | CASErrorKind::IntegralDidNotConverge{..} => "Runtime Error",
         })
     }
 }
@@ -63,12 +133,26 @@ impl CASErrorKind{
             } => format!("malformed numerical literal {}.", lit),
             CASErrorKind::MalformedVariableName{name} => format!("malformed variable name {}. variable names must begin with an alphabetic character, and must only contain alphanumeric characters, _, or -.", name),
             CASErrorKind::AssignmentInExpression => String::from("variable or function assignments cannot be made inside expressions. Perhaps you meant to use the equality operator '=='?"),
+            CASErrorKind::RepeatedArgument{name} => format!("argument {} appears more than once in the function definition.", name),
             CASErrorKind::UnknownSymbol{symbol} => format!("use of unknown variable or function {}.", symbol),
             CASErrorKind::MismatchedParentheses => String::from("expression contains mismatched parentheses."),
             CASErrorKind::NoExpressionGiven => String::from("a variable or command was given an empty expression."),
             CASErrorKind::WrongNumberOfArgs{args_given, args_needed, func_name} => format!("function {} requires {} arguments, but was given {}.", func_name, args_needed, args_given),
             CASErrorKind::InvalidCharacter{chr} => format!("an invalid character {} was entered.", chr),
             CASErrorKind::CommandInExpression { command } => format!("the {} command is not allowed within an expression.", command),
+            CASErrorKind::ExpectedVariable{found} => format!("expected a variable but found {}.", found),
+            CASErrorKind::InvalidArgument{arg, func_name} => format!("{} is not a valid argument for {}.", arg, func_name),
+            CASErrorKind::MalformedRule => String::from("rules must be written as lhs => rhs, optionally followed by if and a condition."),
+            CASErrorKind::UnboundPatternVariable{name} => format!("{} is not in the pattern on the left hand side of the rule, so there is nothing to replace it with.", name),
+            CASErrorKind::StepLimitReached{steps} => format!("gave up after rewriting the expression {} times. Some of the rules might be undoing each other.", steps),
+            CASErrorKind::CannotDifferentiate{symbol} => format!("cannot find the derivative of {}.", symbol),
+            CASErrorKind::CannotEvaluate{symbol} => format!("cannot find the numeric value of {}.", symbol),
+            CASErrorKind::DimensionMismatch{expected, found} => format!("expected a vector with {} elements, but found one with {}.", expected, found),
+            CASErrorKind::SingularMatrix => String::from("the matrix is singular, so it has no inverse."),
+            CASErrorKind::NotSquare{rows, cols} => format!("expected a square matrix, but found a {}x{} one.", rows, cols),
+            CASErrorKind::DivergentIntegral{point} => format!("the integral diverges to both ∞ and -∞ at {}, so it has no value.", point),
+            CASErrorKind::NoLimit{point} => format!("cannot find a limit of the antiderivative at {}, so the integral can't be evaluated.", point),
+            CASErrorKind::IntegralDidNotConverge{digits} => format!("could not approximate the integral to {} significant digits. It might diverge or oscillate too much.", digits),
             
         }
     }
@@ -0,0 +1,117 @@
+//! Matrices of expressions, like `[[1, 2], [3, 4]]`.
+//!
+//! In an expression tree a matrix is a vector whose elements are its rows, which are vectors with the same number of elements.
+
+use crate::{
+    parser::trees::{Tree, TreeNodeRef},
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        cas_vec::CASVec,
+        symbol::Symbol,
+    },
+};
+
+/// A matrix whose entries are expressions.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CASMatrix {
+    /// The entries, row by row.
+    pub(crate) rows: Vec<Vec<Tree<Symbol>>>,
+    /// Number of columns, which is the number of entries in every row.
+    pub(crate) num_cols: usize,
+}
+
+impl CASMatrix {
+    /// The matrix with the given rows, which must all have `num_cols` entries.
+    pub(crate) fn new(rows: Vec<Vec<Tree<Symbol>>>, num_cols: usize) -> Self {
+        debug_assert!(rows.iter().all(|row| row.len() == num_cols));
+        CASMatrix { rows, num_cols }
+    }
+
+    /// The matrix with a single column holding the elements of a vector.
+    pub(crate) fn column(vector: CASVec) -> Self {
+        let rows = vector
+            .elements
+            .into_iter()
+            .map(|element| vec![element])
+            .collect();
+        CASMatrix::new(rows, 1)
+    }
+
+    /// The matrix with a single row holding the elements of a vector.
+    pub(crate) fn row(vector: CASVec) -> Self {
+        let num_cols = vector.dim();
+        CASMatrix::new(vec![vector.elements], num_cols)
+    }
+
+    /// Reads a vector of rows as a matrix, or returns None if any of its elements isn't a vector.
+    ///
+    /// Vectors with no elements aren't matrices, since they have no rows to tell the number of columns from. Rows with different numbers of elements are a [CASErrorKind::DimensionMismatch] error at `line_pos`.
+    pub(crate) fn from_vector(vector: &CASVec, line_pos: usize) -> Result<Option<Self>, CASError> {
+        let Some(rows) = vector
+            .elements
+            .iter()
+            .map(|element| CASVec::from_node(&element.root))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(None);
+        };
+        let Some(num_cols) = rows.first().map(CASVec::dim) else {
+            return Ok(None);
+        };
+        for row in &rows {
+            row.check_dim(num_cols, line_pos)?;
+        }
+        let rows = rows.into_iter().map(|row| row.elements).collect();
+        Ok(Some(CASMatrix::new(rows, num_cols)))
+    }
+
+    /// Reads the matrix at the root of a node, or returns None if the node isn't a vector of vectors, see [CASMatrix::from_vector].
+    pub(crate) fn from_node(
+        node: &TreeNodeRef<Symbol>,
+        line_pos: usize,
+    ) -> Result<Option<Self>, CASError> {
+        match CASVec::from_node(node) {
+            Some(vector) => CASMatrix::from_vector(&vector, line_pos),
+            None => Ok(None),
+        }
+    }
+
+    /// Builds the node for the matrix, a vector of rows at `line_pos`.
+    pub(crate) fn into_node(self, line_pos: usize) -> TreeNodeRef<Symbol> {
+        let rows = self
+            .rows
+            .into_iter()
+            .map(|row| Tree {
+                root: CASVec::new(row).into_node(line_pos),
+            })
+            .collect();
+        CASVec::new(rows).into_node(line_pos)
+    }
+
+    /// Number of rows.
+    pub(crate) fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The matrix with the rows and columns swapped.
+    pub(crate) fn transpose(&self) -> Self {
+        let rows = (0..self.num_cols)
+            .map(|col| self.rows.iter().map(|row| row[col].clone()).collect())
+            .collect();
+        CASMatrix::new(rows, self.num_rows())
+    }
+
+    /// Returns a [CASErrorKind::NotSquare] error at `line_pos` unless the matrix has as many columns as rows.
+    pub(crate) fn check_square(&self, line_pos: usize) -> Result<(), CASError> {
+        if self.num_cols == self.num_rows() {
+            return Ok(());
+        }
+        Err(CASError {
+            line_pos,
+            kind: CASErrorKind::NotSquare {
+                rows: self.num_rows(),
+                cols: self.num_cols,
+            },
+        })
+    }
+}
@@ -0,0 +1,513 @@
+//complex numbers with a CASNum for each of the real and imaginary parts
+//elementary functions are found from the real ones with the usual identities, on their principal branches, with guard limbs before rounding
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::elementary::mult;
+use super::{CASNum, CASValue, Context, RoundingMode, INDETERMINATE, ZERO};
+
+/// Extra limbs the elementary functions work with before rounding to the number of limbs asked for.
+const GUARD_LIMBS: usize = 2;
+
+/// Largest exponent that powers are found for by repeated multiplication, rather than with `exp` and `ln`, so powers of exact values like `i ^ 2` are exact.
+const MAX_INTEGER_EXPONENT: u64 = 1 << 32;
+
+/// A complex number.
+///
+/// Results of functions that aren't defined at an argument, like `ln(0)` or anything of an infinite or indeterminate number, have indeterminate parts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CASComplex {
+    /// The real part.
+    pub(crate) re: CASNum,
+    /// The imaginary part.
+    pub(crate) im: CASNum,
+}
+
+/// A complex number whose parts are both indeterminate.
+const COMPLEX_INDETERMINATE: CASComplex = CASComplex {
+    re: INDETERMINATE,
+    im: INDETERMINATE,
+};
+
+impl CASComplex {
+    /// The complex number `re + im * i`.
+    pub(crate) fn new(re: CASNum, im: CASNum) -> Self {
+        CASComplex { re, im }
+    }
+
+    /// The imaginary unit, whose square is -1.
+    pub(crate) fn i() -> Self {
+        CASComplex::new(ZERO, CASNum::from(1))
+    }
+
+    /// The complex number with magnitude `r` at the angle `theta` in radians from the positive real axis, `r * (cos(theta) + sin(theta) * i)`.
+    pub(crate) fn from_polar(r: &CASNum, theta: &CASNum, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        CASComplex::new(
+            mult(r, &theta.cos(work), work).rounded(num_limbs),
+            mult(r, &theta.sin(work), work).rounded(num_limbs),
+        )
+    }
+
+    /// The magnitude and angle of the number, see [CASComplex::abs] and [CASComplex::arg].
+    pub(crate) fn to_polar(&self, num_limbs: usize) -> (CASNum, CASNum) {
+        (self.abs(num_limbs), self.arg(num_limbs))
+    }
+
+    /// The real part, or None if the imaginary part isn't zero.
+    pub(crate) fn to_real(&self) -> Option<CASNum> {
+        self.im.is_zero().then(|| self.re.clone())
+    }
+
+    /// Checks if both parts are finite.
+    pub(crate) fn is_finite(&self) -> bool {
+        self.re.value.is_finite() && self.im.value.is_finite()
+    }
+
+    /// Checks if either part is indeterminate.
+    pub(crate) fn is_indeterminate(&self) -> bool {
+        self.re.value.is_indeterminate() || self.im.value.is_indeterminate()
+    }
+
+    /// Checks if the number is zero.
+    fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// The complex conjugate, with the sign of the imaginary part flipped.
+    pub(crate) fn conj(&self) -> Self {
+        CASComplex::new(self.re.clone(), -self.im.clone())
+    }
+
+    /// Rounds both parts to the context's precision.
+    pub(crate) fn round(self, context: Context) -> Self {
+        CASComplex::new(self.re.round(context), self.im.round(context))
+    }
+
+    /// The sum of two numbers, with each part rounded to the context's precision.
+    pub(crate) fn add_with(&self, rhs: &CASComplex, context: Context) -> Self {
+        (self.clone() + rhs.clone()).round(context)
+    }
+
+    /// The difference of two numbers, with each part rounded to the context's precision.
+    pub(crate) fn sub_with(&self, rhs: &CASComplex, context: Context) -> Self {
+        (self.clone() - rhs.clone()).round(context)
+    }
+
+    /// The product of two numbers, with each part of the exact product rounded once to the context's precision.
+    pub(crate) fn mul_with(&self, rhs: &CASComplex, context: Context) -> Self {
+        (self.clone() * rhs.clone()).round(context)
+    }
+
+    /// The quotient of two numbers, with each part rounded once to the context's precision.
+    ///
+    /// Dividing by a real number divides each part by it, so dividing by zero gives infinite or indeterminate parts like [CASNum] division does.
+    pub(crate) fn div_with(&self, rhs: &CASComplex, context: Context) -> Self {
+        if let Some(divisor) = rhs.to_real() {
+            return CASComplex::new(
+                self.re.div_with(&divisor, context),
+                self.im.div_with(&divisor, context),
+            );
+        }
+        // (a + bi) / (c + di) = ((ac + bd) + (bc - ad)i) / (c ^ 2 + d ^ 2)
+        let numerator = self.clone() * rhs.conj();
+        let denominator = rhs.re.clone() * rhs.re.clone() + rhs.im.clone() * rhs.im.clone();
+        CASComplex::new(
+            numerator.re.div_with(&denominator, context),
+            numerator.im.div_with(&denominator, context),
+        )
+    }
+
+    /// 1 divided by the number.
+    fn recip(&self, num_limbs: usize) -> Self {
+        CASComplex::from(CASNum::from(1)).div_with(self, nearest(num_limbs))
+    }
+
+    /// The magnitude, `sqrt(re ^ 2 + im ^ 2)`.
+    pub(crate) fn abs(&self, num_limbs: usize) -> CASNum {
+        if self.im.is_zero() {
+            return self.re.abs();
+        }
+        (self.re.clone() * self.re.clone() + self.im.clone() * self.im.clone()).sqrt(num_limbs)
+    }
+
+    /// The angle from the positive real axis in radians, between -π and π. Negative real numbers have an angle of π and zero has an angle of 0.
+    pub(crate) fn arg(&self, num_limbs: usize) -> CASNum {
+        if !self.is_finite() {
+            return INDETERMINATE;
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        let half_pi = || CASNum::pi(work).times_power_of_two(-1);
+        let angle = match (self.re.partial_cmp(&ZERO), self.im.partial_cmp(&ZERO)) {
+            (Some(Ordering::Equal), Some(Ordering::Equal)) => ZERO,
+            (Some(Ordering::Equal), Some(Ordering::Greater)) => half_pi(),
+            (Some(Ordering::Equal), _) => -half_pi(),
+            (Some(Ordering::Greater), _) => self.im.quotient(&self.re, work).atan(work),
+            //left of the imaginary axis atan is off by π, and the negative real axis is on the upper side of the branch cut
+            (_, Some(Ordering::Less)) => {
+                self.im.quotient(&self.re, work).atan(work) - CASNum::pi(work)
+            }
+            _ => self.im.quotient(&self.re, work).atan(work) + CASNum::pi(work),
+        };
+        angle.rounded(num_limbs)
+    }
+
+    /// Square root, on the principal branch where the real part is never negative.
+    pub(crate) fn sqrt(&self, num_limbs: usize) -> Self {
+        if !self.is_finite() {
+            return COMPLEX_INDETERMINATE;
+        }
+        if self.is_zero() {
+            return CASComplex::from(ZERO);
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        let context = nearest(work);
+        let magnitude = self.abs(work);
+        //the larger part is sqrt((|z| + |re|) / 2), found without cancellation, and the other is im divided by twice it
+        let larger = (magnitude + self.re.abs())
+            .times_power_of_two(-1)
+            .sqrt(work);
+        let smaller = self
+            .im
+            .abs()
+            .div_with(&larger.times_power_of_two(1), context);
+        let im_sign = if self.im.partial_cmp(&ZERO) == Some(Ordering::Less) {
+            -CASNum::from(1)
+        } else {
+            CASNum::from(1)
+        };
+        let root = if self.re.partial_cmp(&ZERO) == Some(Ordering::Less) {
+            //the negative real axis goes to the positive imaginary axis
+            CASComplex::new(smaller, larger * im_sign)
+        } else {
+            CASComplex::new(larger, smaller * im_sign)
+        };
+        root.rounded(num_limbs)
+    }
+
+    /// Cube root, on the principal branch `exp(ln(z) / 3)`, so the cube root of a negative real number isn't real.
+    pub(crate) fn cbrt(&self, num_limbs: usize) -> Self {
+        if self.is_zero() {
+            return CASComplex::from(ZERO);
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        let third = self
+            .ln(work)
+            .div_with(&CASComplex::from(CASNum::from(3)), nearest(work));
+        third.exp(num_limbs)
+    }
+
+    /// e raised to this number, `e ^ re * (cos(im) + sin(im) * i)`.
+    pub(crate) fn exp(&self, num_limbs: usize) -> Self {
+        if !self.is_finite() {
+            return COMPLEX_INDETERMINATE;
+        }
+        CASComplex::from_polar(&self.re.exp(num_limbs + GUARD_LIMBS), &self.im, num_limbs)
+    }
+
+    /// Natural logarithm on the principal branch, `ln(|z|) + arg(z) * i`, whose imaginary part is between -π and π.
+    pub(crate) fn ln(&self, num_limbs: usize) -> Self {
+        if !self.is_finite() || self.is_zero() {
+            //ln(0) = -∞, which is only real for a real 0
+            return match self.to_real() {
+                Some(real) => CASComplex::from(real.ln(num_limbs)),
+                None => COMPLEX_INDETERMINATE,
+            };
+        }
+        // ln(|z|) = ln(re ^ 2 + im ^ 2) / 2, which doesn't need the square root
+        let square = self.re.clone() * self.re.clone() + self.im.clone() * self.im.clone();
+        let ln_abs = square.ln(num_limbs + GUARD_LIMBS).times_power_of_two(-1);
+        CASComplex::new(ln_abs.rounded(num_limbs), self.arg(num_limbs))
+    }
+
+    /// Logarithm with base `base`, `ln(z) / ln(base)`.
+    pub(crate) fn log(&self, base: &CASComplex, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        let ln_base = base.ln(work);
+        if ln_base.is_zero() {
+            //there's no logarithm with base 1
+            return COMPLEX_INDETERMINATE;
+        }
+        self.ln(work)
+            .div_with(&ln_base, nearest(work))
+            .rounded(num_limbs)
+    }
+
+    /// Logarithm with base 2.
+    pub(crate) fn log2(&self, num_limbs: usize) -> Self {
+        self.log(&CASComplex::from(CASNum::from(2)), num_limbs)
+    }
+
+    /// Logarithm with base 10.
+    pub(crate) fn log10(&self, num_limbs: usize) -> Self {
+        self.log(&CASComplex::from(CASNum::from(10)), num_limbs)
+    }
+
+    /// Raises this number to a power, on the principal branch `exp(exp * ln(z))`.
+    ///
+    /// Integer powers are found by repeated multiplication, so they're exact for numbers like `i` and defined for 0. 0 raised to a power with a positive real part is 0.
+    pub(crate) fn pow(&self, exp: &CASComplex, num_limbs: usize) -> Self {
+        if !self.is_finite() || !exp.is_finite() {
+            return COMPLEX_INDETERMINATE;
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        if let Some(n) = exp
+            .to_real()
+            .and_then(|exp| u64::try_from(&exp.abs()).ok())
+            .filter(|n| *n <= MAX_INTEGER_EXPONENT)
+        {
+            let power = self.integer_power(n, work);
+            let power = if exp.re.partial_cmp(&ZERO) == Some(Ordering::Less) {
+                power.recip(work)
+            } else {
+                power
+            };
+            return power.rounded(num_limbs);
+        }
+        if self.is_zero() {
+            return match exp.re.partial_cmp(&ZERO) {
+                Some(Ordering::Greater) => CASComplex::from(ZERO),
+                _ => COMPLEX_INDETERMINATE,
+            };
+        }
+        //the error in exp * ln(z) is multiplied by the size of the result, so a limb is added for each limb of the exponent's size
+        let extra_limbs = exp.abs(1).value.exp().unwrap_or(0).max(0) as usize;
+        let exponent = exp.mul_with(&self.ln(work + extra_limbs), nearest(work + extra_limbs));
+        exponent.exp(num_limbs)
+    }
+
+    /// Raises this number to a non-negative integer power by repeated squaring, keeping `num_limbs` limbs.
+    fn integer_power(&self, mut n: u64, num_limbs: usize) -> Self {
+        let context = nearest(num_limbs);
+        let mut power = CASComplex::from(CASNum::from(1));
+        let mut square = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                power = power.mul_with(&square, context);
+            }
+            n >>= 1;
+            if n > 0 {
+                square = square.mul_with(&square, context);
+            }
+        }
+        power
+    }
+
+    /// Sine of an angle in radians, `sin(re) * cosh(im) + cos(re) * sinh(im) * i`.
+    pub(crate) fn sin(&self, num_limbs: usize) -> Self {
+        if !self.is_finite() {
+            return COMPLEX_INDETERMINATE;
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        let (sinh, cosh) = sinh_cosh(&self.im, work);
+        CASComplex::new(
+            mult(&self.re.sin(work), &cosh, work),
+            mult(&self.re.cos(work), &sinh, work),
+        )
+        .rounded(num_limbs)
+    }
+
+    /// Cosine of an angle in radians, `cos(re) * cosh(im) - sin(re) * sinh(im) * i`.
+    pub(crate) fn cos(&self, num_limbs: usize) -> Self {
+        if !self.is_finite() {
+            return COMPLEX_INDETERMINATE;
+        }
+        let work = num_limbs + GUARD_LIMBS;
+        let (sinh, cosh) = sinh_cosh(&self.im, work);
+        CASComplex::new(
+            mult(&self.re.cos(work), &cosh, work),
+            -mult(&self.re.sin(work), &sinh, work),
+        )
+        .rounded(num_limbs)
+    }
+
+    /// Tangent of an angle in radians.
+    pub(crate) fn tan(&self, num_limbs: usize) -> Self {
+        self.trig_ratio(num_limbs, |sin, cos, context| sin.div_with(&cos, context))
+    }
+
+    /// Cosecant of an angle in radians.
+    pub(crate) fn csc(&self, num_limbs: usize) -> Self {
+        self.trig_ratio(num_limbs, |sin, _, context| {
+            CASComplex::from(CASNum::from(1)).div_with(&sin, context)
+        })
+    }
+
+    /// Secant of an angle in radians.
+    pub(crate) fn sec(&self, num_limbs: usize) -> Self {
+        self.trig_ratio(num_limbs, |_, cos, context| {
+            CASComplex::from(CASNum::from(1)).div_with(&cos, context)
+        })
+    }
+
+    /// Cotangent of an angle in radians.
+    pub(crate) fn cot(&self, num_limbs: usize) -> Self {
+        self.trig_ratio(num_limbs, |sin, cos, context| cos.div_with(&sin, context))
+    }
+
+    /// Applies a function of the sine and cosine of this number, found with guard limbs.
+    fn trig_ratio(
+        &self,
+        num_limbs: usize,
+        ratio: impl Fn(CASComplex, CASComplex, Context) -> CASComplex,
+    ) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        ratio(self.sin(work), self.cos(work), nearest(work)).rounded(num_limbs)
+    }
+
+    /// Inverse sine on the principal branch, `-i * ln(iz + sqrt(1 - z ^ 2))`, whose real part is between -π/2 and π/2.
+    pub(crate) fn asin(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        let context = nearest(work);
+        let one = CASComplex::from(CASNum::from(1));
+        let root = one
+            .sub_with(&self.mul_with(self, context), context)
+            .sqrt(work);
+        let ln = (CASComplex::i() * self.clone())
+            .add_with(&root, context)
+            .ln(work);
+        // -i * (a + bi) = b - ai
+        CASComplex::new(ln.im, -ln.re).rounded(num_limbs)
+    }
+
+    /// Inverse cosine on the principal branch, `π/2 - asin(z)`, whose real part is between 0 and π.
+    pub(crate) fn acos(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        let half_pi = CASComplex::from(CASNum::pi(work).times_power_of_two(-1));
+        half_pi
+            .sub_with(&self.asin(work), nearest(work))
+            .rounded(num_limbs)
+    }
+
+    /// Inverse tangent on the principal branch, `i/2 * (ln(1 - iz) - ln(1 + iz))`, whose real part is between -π/2 and π/2.
+    pub(crate) fn atan(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        let context = nearest(work);
+        let one = CASComplex::from(CASNum::from(1));
+        let iz = CASComplex::i() * self.clone();
+        let difference = one
+            .sub_with(&iz, context)
+            .ln(work)
+            .sub_with(&one.add_with(&iz, context).ln(work), context);
+        // i/2 * (a + bi) = -b/2 + a/2 i
+        CASComplex::new(
+            -difference.im.times_power_of_two(-1),
+            difference.re.times_power_of_two(-1),
+        )
+        .rounded(num_limbs)
+    }
+
+    /// Inverse cosecant, `asin(1 / z)`.
+    pub(crate) fn acsc(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        self.recip(work).asin(num_limbs)
+    }
+
+    /// Inverse secant, `acos(1 / z)`.
+    pub(crate) fn asec(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        self.recip(work).acos(num_limbs)
+    }
+
+    /// Inverse cotangent, `atan(1 / z)`.
+    pub(crate) fn acot(&self, num_limbs: usize) -> Self {
+        let work = num_limbs + GUARD_LIMBS;
+        self.recip(work).atan(num_limbs)
+    }
+
+    /// Rounds both parts to the nearest number with at most `num_limbs` limbs.
+    fn rounded(self, num_limbs: usize) -> Self {
+        CASComplex::new(self.re.rounded(num_limbs), self.im.rounded(num_limbs))
+    }
+}
+
+/// Context for rounding to the nearest with `num_limbs` limbs.
+fn nearest(num_limbs: usize) -> Context {
+    Context::new(num_limbs, RoundingMode::HalfEven)
+}
+
+/// The hyperbolic sine and cosine of a finite number, `(e ^ x - e ^ -x) / 2` and `(e ^ x + e ^ -x) / 2`.
+fn sinh_cosh(x: &CASNum, num_limbs: usize) -> (CASNum, CASNum) {
+    //the difference cancels to about 2x, so small numbers need a limb more for each leading zero limb they have
+    let extra_limbs = match &x.value {
+        CASValue::Finite { exp, .. } if !x.is_zero() => (-*exp).max(0) as usize,
+        _ => 0,
+    };
+    let work = num_limbs + extra_limbs + GUARD_LIMBS;
+    let up = x.exp(work);
+    let down = CASNum::from(1).div_with(&up, nearest(work));
+    (
+        (up.clone() - down.clone())
+            .times_power_of_two(-1)
+            .rounded(num_limbs),
+        (up + down).times_power_of_two(-1).rounded(num_limbs),
+    )
+}
+
+impl From<CASNum> for CASComplex {
+    fn from(value: CASNum) -> Self {
+        CASComplex::new(value, ZERO)
+    }
+}
+
+impl Neg for CASComplex {
+    type Output = CASComplex;
+
+    fn neg(self) -> Self::Output {
+        CASComplex::new(-self.re, -self.im)
+    }
+}
+
+impl Add for CASComplex {
+    type Output = CASComplex;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        CASComplex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for CASComplex {
+    type Output = CASComplex;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        CASComplex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for CASComplex {
+    type Output = CASComplex;
+
+    /// The exact product, `(ac - bd) + (ad + bc)i`.
+    fn mul(self, rhs: Self) -> Self::Output {
+        CASComplex::new(
+            self.re.clone() * rhs.re.clone() - self.im.clone() * rhs.im.clone(),
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Display for CASComplex {
+    /// Writes the number like `1.5 + 2i`, leaving out a zero real or imaginary part. Both parts are written with the precision if there is one.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let part = |value: &CASNum| match f.precision() {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        };
+        if self.im.is_zero() {
+            return write!(f, "{}", part(&self.re));
+        }
+        let negative = self.im.partial_cmp(&ZERO) == Some(Ordering::Less);
+        let im = match part(&self.im.abs()).as_str() {
+            "1" => String::from("i"),
+            magnitude => format!("{}i", magnitude),
+        };
+        match (self.re.is_zero(), negative) {
+            (true, false) => write!(f, "{}", im),
+            (true, true) => write!(f, "-{}", im),
+            (false, false) => write!(f, "{} + {}", part(&self.re), im),
+            (false, true) => write!(f, "{} - {}", part(&self.re), im),
+        }
+    }
+}
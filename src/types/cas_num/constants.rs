@@ -0,0 +1,133 @@
+//mathematical constants π, e, ϕ, τ and ln(2) to any number of limbs
+//each value is correctly rounded and cached by the number of limbs it was found to, so asking for it again at the same precision doesn't compute it again
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+
+use super::elementary::{atanh_series, correctly_rounded, mult};
+use super::CASNum;
+
+/// Constants found so far, by constant and number of limbs.
+static CACHE: Mutex<BTreeMap<(Constant, usize), CASNum>> = Mutex::new(BTreeMap::new());
+
+/// 640320 ^ 3 / 24, the ratio between the denominators of consecutive terms of the Chudnovsky series.
+const CHUDNOVSKY_RATIO: u64 = 10_939_058_860_032_000;
+
+/// Bits of π each term of the Chudnovsky series adds, rounded down.
+const CHUDNOVSKY_TERM_BITS: usize = 47;
+
+/// The constants kept in [CACHE].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Constant {
+    /// π
+    Pi,
+    /// τ = 2 * π
+    Tau,
+    /// Euler's number e.
+    E,
+    /// The golden ratio ϕ.
+    Phi,
+    /// ln(2), used to reduce the arguments of exp and ln.
+    Ln2,
+}
+
+impl CASNum {
+    /// π to `num_limbs` limbs, from the Chudnovsky series.
+    pub(crate) fn pi(num_limbs: usize) -> CASNum {
+        cached(Constant::Pi, num_limbs, chudnovsky)
+    }
+
+    /// τ = 2 * π to `num_limbs` limbs.
+    pub(crate) fn tau(num_limbs: usize) -> CASNum {
+        //π rounded to `num_limbs` limbs and doubled isn't always rounded the right way, as τ can have the same last limb as π
+        cached(Constant::Tau, num_limbs, |limbs| {
+            CASNum::pi(limbs).times_power_of_two(1)
+        })
+    }
+
+    /// Euler's number e to `num_limbs` limbs, from the series 1 / 0! + 1 / 1! + 1 / 2! + ...
+    pub(crate) fn e(num_limbs: usize) -> CASNum {
+        cached(Constant::E, num_limbs, |limbs| {
+            let mut term = CASNum::from(1);
+            super::elementary::sum_series(limbs, |idx| {
+                if idx > 0 {
+                    term = term.quotient(&CASNum::from(idx), limbs);
+                }
+                term.clone()
+            })
+        })
+    }
+
+    /// The golden ratio ϕ = (1 + sqrt(5)) / 2 to `num_limbs` limbs.
+    pub(crate) fn phi(num_limbs: usize) -> CASNum {
+        cached(Constant::Phi, num_limbs, |limbs| {
+            (CASNum::from(1) + CASNum::from(5).sqrt(limbs))
+                .times_power_of_two(-1)
+                .with_precision(limbs)
+        })
+    }
+}
+
+/// The natural logarithm of 2 to `num_limbs` limbs, as 2 * atanh(1 / 3).
+pub(super) fn ln2(num_limbs: usize) -> CASNum {
+    cached(Constant::Ln2, num_limbs, |limbs| {
+        let third = CASNum::from(1).quotient(&CASNum::from(3), limbs);
+        atanh_series(&third, limbs).times_power_of_two(1)
+    })
+}
+
+/// Gets a constant from the cache, or rounds the value `compute` finds to `num_limbs` limbs and caches it.
+///
+/// The cache isn't locked while computing, so `compute` can use other constants.
+fn cached(constant: Constant, num_limbs: usize, compute: impl Fn(usize) -> CASNum) -> CASNum {
+    let key = (constant, num_limbs);
+    let lock = || CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(value) = lock().get(&key) {
+        return value.clone();
+    }
+    let value = correctly_rounded(num_limbs, compute);
+    lock().insert(key, value.clone());
+    value
+}
+
+/// π from the Chudnovsky series, summed exactly by binary splitting as π = 426880 * sqrt(10005) * Q / T.
+fn chudnovsky(num_limbs: usize) -> CASNum {
+    let limbs = num_limbs + 1;
+    let terms = (64 * limbs / CHUDNOVSKY_TERM_BITS + 2) as u64;
+    let (_, q, t) = binary_split(0, terms);
+    let numerator = mult(
+        &(q * CASNum::from(426_880)),
+        &CASNum::from(10_005).sqrt(limbs),
+        limbs,
+    );
+    numerator.quotient(&t, limbs).with_precision(num_limbs)
+}
+
+/// The exact integers P, Q and T of the Chudnovsky series for the terms from `start` up to but not including `end`.
+fn binary_split(start: u64, end: u64) -> (CASNum, CASNum, CASNum) {
+    if end - start == 1 {
+        let (p, q) = if start == 0 {
+            (CASNum::from(1), CASNum::from(1))
+        } else {
+            let cube = CASNum::from(start) * CASNum::from(start) * CASNum::from(start);
+            (
+                CASNum::from(6 * start - 5)
+                    * CASNum::from(2 * start - 1)
+                    * CASNum::from(6 * start - 1),
+                cube * CASNum::from(CHUDNOVSKY_RATIO),
+            )
+        };
+        let t = p.clone()
+            * (CASNum::from(13_591_409) + CASNum::from(545_140_134) * CASNum::from(start));
+        let t = if start % 2 == 1 { -t } else { t };
+        return (p, q, t);
+    }
+    let mid = (start + end) / 2;
+    let (p_left, q_left, t_left) = binary_split(start, mid);
+    let (p_right, q_right, t_right) = binary_split(mid, end);
+    (
+        p_left.clone() * p_right,
+        q_left * q_right.clone(),
+        q_right * t_left + p_left * t_right,
+    )
+}
@@ -77,17 +77,12 @@ impl From<u64> for CASNum {
 
 impl From<u128> for CASNum {
     fn from(value: u128) -> Self {
-        //we can avoid a normalize call since 0 is the only case where normalization is necessary
-        //which will give an empty vecdeque
-
-        let mut digits: VecDeque<DigitType> = VecDeque::new();
-        if value != 0 {
-            digits.push_back(value as DigitType);
-        }
+        let digits: VecDeque<DigitType> =
+            VecDeque::from([value as DigitType, (value >> NUM_BITS) as DigitType]);
 
-        CASNum {
-            value: CASValue::Finite { digits, exp: 0 },
-            sign: Sign::Pos,
+        CASNum {
+            value: CASValue::Finite { digits, exp: 1 }.normalize(),
+            sign: Sign::Pos,
         }
     }
 }
@@ -462,3 +457,21 @@ impl From<CASNum> for f64 {
         }
     }
 }
+
+impl TryFrom<&CASNum> for u64 {
+    type Error = ();
+
+    /// Converts a non-negative integer that fits in a u64, like the order of a derivative.
+    fn try_from(value: &CASNum) -> Result<Self, Self::Error> {
+        if value.value.is_zero() {
+            return Ok(0);
+        }
+        match value {
+            CASNum {
+                value: CASValue::Finite { digits, exp: 0 },
+                sign: Sign::Pos,
+            } if digits.len() == 1 => Ok(digits[0]),
+            _ => Err(()),
+        }
+    }
+}
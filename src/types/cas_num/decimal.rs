@@ -0,0 +1,345 @@
+//writing numbers in base 10, exactly from their limbs without going through f64
+//the significant digits are found by scaling the number by a power of 10 until it has that many digits before the point, then rounding it to an integer
+//large powers of 10 are only found to as many limbs as it takes to round the scaled number, so writing something like 1e1000000 doesn't need a million digit power
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use super::division::{div_rem, integer_from_limbs, integer_limbs};
+use super::literal::{from_decimal, integer};
+use super::multiplication::multiply;
+use super::rational::{mul, trim};
+use super::{CASInterval, CASNum, CASValue, Context, DigitType, RoundingMode, Sign, ZERO};
+
+/// Largest power of 10 that fits in a limb, used to write numbers in base 10 a limb at a time.
+const DECIMAL_LIMB: DigitType = 10_000_000_000_000_000_000;
+
+/// Number of decimal digits in [DECIMAL_LIMB].
+const DECIMAL_LIMB_DIGITS: usize = 19;
+
+/// Powers of 10 from this up to [DISPLAY_MAX_EXP] are displayed without an exponent.
+const DISPLAY_MIN_EXP: isize = -5;
+
+/// Numbers below 10 to this power are displayed without an exponent.
+const DISPLAY_MAX_EXP: isize = 21;
+
+/// How a number is written in base 10.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Notation {
+    /// Digits with a decimal point and no exponent, like `1234.5` or `0.00012`.
+    Fixed,
+    /// One digit before the decimal point and a power of 10, like `1.2345e3`.
+    Scientific,
+    /// One to three digits before the decimal point and a power of 10 that's a multiple of 3, like `1.2345e3` or `120e-6`.
+    Engineering,
+}
+
+impl CASNum {
+    /// Writes the number in base 10 with `digits` significant digits, rounded to the nearest and to an even last digit when exactly halfway.
+    ///
+    /// Trailing zeros are kept, since they're significant. Infinite and indeterminate numbers are written as `∞`, `-∞` and `NaN` whatever the notation.
+    pub(crate) fn to_decimal(&self, digits: usize, notation: Notation) -> String {
+        if !self.value.is_finite() {
+            return self.to_string();
+        }
+        let (significand, exp) = self.decimal_digits(digits.max(1), RoundingMode::HalfEven);
+        let sign = if self.sign == Sign::Neg && !self.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+        format!("{}{}", sign, with_notation(&significand, exp, notation))
+    }
+
+    /// Rounds the number to `digits` significant decimal digits with `mode`, so it's displayed as those digits with trailing zeros dropped.
+    ///
+    /// The result has as many limbs as a literal with those digits, and is rounded to them with the same mode, so rounding with [RoundingMode::Floor] never gives more than the number and [RoundingMode::Ceiling] never less. Infinite and indeterminate numbers are returned as they are.
+    pub(crate) fn round_decimal(&self, digits: usize, mode: RoundingMode) -> CASNum {
+        if !self.value.is_finite() {
+            return self.clone();
+        }
+        let digits = digits.max(1);
+        let (significand, exp) = self.decimal_digits(digits, mode);
+        let mut significand = integer(&significand, 10);
+        if self.sign == Sign::Neg {
+            significand = -significand;
+        }
+        from_decimal(significand, exp + 1 - digits as isize, digits, mode)
+    }
+
+    /// The first `digits` significant decimal digits of a finite number's magnitude rounded with `mode`, and the power of 10 of the first of them.
+    ///
+    /// Zero gives `digits` zeros and an exponent of 0.
+    fn decimal_digits(&self, digits: usize, mode: RoundingMode) -> (String, isize) {
+        let CASValue::Finite { digits: limbs, exp } = self.value.clone().normalize() else {
+            unreachable!("only finite numbers have digits");
+        };
+        let Some(top) = limbs.back() else {
+            return ("0".repeat(digits), 0);
+        };
+        //2 ^ (bits - 1) <= magnitude < 2 ^ bits, so the power of 10 of the first digit is at least (bits - 1) * log10(2), rounded down
+        let bits = 64 * (exp as i128 + 1) - top.leading_zeros() as i128;
+        let mut decimal_exp =
+            ((bits - 1) * 30_102_999_566_398).div_euclid(100_000_000_000_000) as isize;
+        loop {
+            //magnitude * 10 ^ scale has `digits` digits before the point if the exponent is right
+            let scale = digits as isize - 1 - decimal_exp;
+            let (quotient, inexact, half) = self.scaled_magnitude(scale);
+            let mut significand = decimal(&quotient);
+            let len = if quotient.is_empty() {
+                0
+            } else {
+                significand.len()
+            };
+            match len.cmp(&digits) {
+                //the estimate of the exponent can be a little low, and each power of 10 it's off by is a digit too many or too few
+                Ordering::Greater => decimal_exp += (len - digits) as isize,
+                Ordering::Less => decimal_exp -= (digits - len) as isize,
+                Ordering::Equal => {
+                    //whether the magnitude rounds up
+                    let round_up = match mode {
+                        RoundingMode::HalfEven => match half {
+                            Ordering::Greater => true,
+                            Ordering::Equal => significand.ends_with(['1', '3', '5', '7', '9']),
+                            Ordering::Less => false,
+                        },
+                        RoundingMode::TowardZero => false,
+                        RoundingMode::AwayFromZero => inexact,
+                        RoundingMode::Floor => inexact && self.sign == Sign::Neg,
+                        RoundingMode::Ceiling => inexact && self.sign == Sign::Pos,
+                    };
+                    if round_up && increment(&mut significand) {
+                        //the digits were all 9s, so they round up to the next power of 10
+                        decimal_exp += 1;
+                    }
+                    return (significand, decimal_exp);
+                }
+            }
+        }
+    }
+
+    /// The integer part of a finite nonzero number's magnitude times 10 ^ `scale`, whether it has a fractional part, and how the fractional part compares to 1/2.
+    ///
+    /// Large powers of 10 are first found to a few more limbs than the number has, with their ends rounded outward and more limbs until the bounds on the scaled magnitude pin these down. The exact power is used once that would take as many limbs as it has.
+    fn scaled_magnitude(&self, scale: isize) -> (Vec<DigitType>, bool, Ordering) {
+        let CASValue::Finite { digits: limbs, exp } = self.value.clone().normalize() else {
+            unreachable!("only finite numbers have digits");
+        };
+        let magnitude = self.abs();
+        let half = CASNum::from(0.5);
+        //10 ^ n has a little over 3.32 * n bits
+        let power_limbs = scale.unsigned_abs() * 3_322 / 64_000 + 1;
+        let ten = CASInterval::point(CASNum::from(10));
+        let power = CASInterval::point(CASNum::from(scale.unsigned_abs() as u64));
+        let mut num_limbs = limbs.len() + 2;
+        while num_limbs < power_limbs + limbs.len() {
+            let bounds = ten.pow(&power, num_limbs);
+            let (down, up) = (
+                Context::new(num_limbs, RoundingMode::Floor),
+                Context::new(num_limbs, RoundingMode::Ceiling),
+            );
+            let (lo, hi) = if scale >= 0 {
+                (
+                    magnitude.mul_with(&bounds.lo, down),
+                    magnitude.mul_with(&bounds.hi, up),
+                )
+            } else {
+                (
+                    magnitude.div_with(&bounds.hi, down),
+                    magnitude.div_with(&bounds.lo, up),
+                )
+            };
+            let quotient = trim(integer_limbs(&lo));
+            if quotient == trim(integer_limbs(&hi)) {
+                let whole = integer_from_limbs(Sign::Pos, &quotient);
+                let (lo, hi) = (lo - whole.clone(), hi - whole);
+                let inexact = if lo > ZERO {
+                    Some(true)
+                } else if hi.is_zero() {
+                    Some(false)
+                } else {
+                    None
+                };
+                let (lo_half, hi_half) = (lo.partial_cmp(&half), hi.partial_cmp(&half));
+                if let (Some(inexact), Some(half)) =
+                    (inexact, lo_half.filter(|_| lo_half == hi_half))
+                {
+                    return (quotient, inexact, half);
+                }
+            }
+            num_limbs *= 2;
+        }
+
+        //the magnitude is limbs * 2 ^ (64 * low)
+        let limbs: Vec<DigitType> = limbs.iter().copied().collect();
+        let low = exp - limbs.len() as isize + 1;
+        let mut numerator = limbs;
+        let mut denominator = vec![1];
+        if low >= 0 {
+            numerator = shifted(&numerator, low as usize);
+        } else {
+            denominator = shifted(&denominator, -low as usize);
+        }
+        if scale >= 0 {
+            numerator = multiply(&numerator, &power_of_ten(scale as usize));
+        } else {
+            denominator = multiply(&denominator, &power_of_ten(-scale as usize));
+        }
+        let (quotient, remainder) = div_rem(&numerator, &denominator);
+        let twice_remainder = integer_from_limbs(Sign::Pos, &remainder) * CASNum::from(2);
+        let half = twice_remainder
+            .partial_cmp(&integer_from_limbs(Sign::Pos, &denominator))
+            .unwrap();
+        (quotient, !remainder.is_empty(), half)
+    }
+
+    /// Number of significant digits to display when none are asked for.
+    ///
+    /// Integers are displayed exactly if they fit in two limbs or have a limb for their units. Other numbers get the digits their bits from the highest set bit to the end of the lowest limb determine, so rounding a decimal to a number of limbs and displaying it gives the decimal back.
+    pub(super) fn display_digits(&self) -> usize {
+        let CASValue::Finite { digits, exp } = &self.value else {
+            return 1;
+        };
+        let Some(top) = digits.back() else {
+            return 1;
+        };
+        let top_bits = (DigitType::BITS - top.leading_zeros()) as usize;
+        let integer_bits = 64 * *exp as i128 + top_bits as i128;
+        let units_limb = *exp + 1 == digits.len() as isize;
+        if self.is_integer() && (integer_bits <= 64 * 2 || units_limb) {
+            //there are at most log10(2) * bits digits, rounded up
+            return (integer_bits as usize * 30_103).div_ceil(100_000);
+        }
+        let bits = 64 * (digits.len() - 1) + top_bits;
+        (bits * 30_103 / 100_000).max(1)
+    }
+}
+
+impl Display for CASNum {
+    /// Writes the number in base 10, with as many significant digits as the precision if there is one.
+    ///
+    /// Without a precision, integers are written exactly and other numbers with the digits their limbs can hold, dropping trailing zeros. Numbers too large or small to write without an exponent are written in scientific notation.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CASNum {
+                value: CASValue::Infinite,
+                sign: Sign::Pos,
+            } => return write!(f, "∞"),
+            CASNum {
+                value: CASValue::Infinite,
+                sign: Sign::Neg,
+            } => return write!(f, "-∞"),
+            CASNum {
+                value: CASValue::Indeterminate,
+                ..
+            } => return write!(f, "NaN"),
+            _ => {}
+        }
+
+        let digits = f
+            .precision()
+            .unwrap_or_else(|| self.display_digits())
+            .max(1);
+        let (mut significand, exp) = self.decimal_digits(digits, RoundingMode::HalfEven);
+        if f.precision().is_none() {
+            let len = significand.trim_end_matches('0').len().max(1);
+            significand.truncate(len);
+        }
+        let notation = if (DISPLAY_MIN_EXP..DISPLAY_MAX_EXP).contains(&exp) {
+            Notation::Fixed
+        } else {
+            Notation::Scientific
+        };
+        if self.sign == Sign::Neg && !self.is_zero() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", with_notation(&significand, exp, notation))
+    }
+}
+
+/// Writes unsigned integers in base 10.
+pub(super) fn decimal(limbs: &[DigitType]) -> String {
+    let mut chunks = VecDeque::new();
+    let mut rest = limbs.to_vec();
+    while !rest.is_empty() {
+        let (quotient, remainder) = div_rem(&rest, &[DECIMAL_LIMB]);
+        chunks.push_front(remainder.first().copied().unwrap_or(0));
+        rest = quotient;
+    }
+    let Some(top) = chunks.pop_front() else {
+        return String::from("0");
+    };
+    chunks.iter().fold(top.to_string(), |string, chunk| {
+        format!("{}{:0>width$}", string, chunk, width = DECIMAL_LIMB_DIGITS)
+    })
+}
+
+/// 10 ^ `exp` as limbs, by repeated squaring.
+pub(super) fn power_of_ten(mut exp: usize) -> Vec<DigitType> {
+    let mut power = vec![1];
+    let mut square = vec![10];
+    while exp > 0 {
+        if exp & 1 == 1 {
+            power = mul(&power, &square);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            square = mul(&square, &square);
+        }
+    }
+    power
+}
+
+/// Multiplies an unsigned integer by 2 ^ (64 * `num_limbs`).
+fn shifted(limbs: &[DigitType], num_limbs: usize) -> Vec<DigitType> {
+    let mut shifted = vec![0; num_limbs];
+    shifted.extend_from_slice(limbs);
+    shifted
+}
+
+/// Adds 1 to the last of a string of decimal digits, returning true if it carries out of the first, which leaves a 1 followed by zeros.
+fn increment(digits: &mut String) -> bool {
+    let mut bytes = std::mem::take(digits).into_bytes();
+    let mut carry = true;
+    for digit in bytes.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            carry = false;
+            break;
+        }
+    }
+    if carry {
+        bytes[0] = b'1';
+    }
+    *digits = String::from_utf8(bytes).unwrap();
+    carry
+}
+
+/// Writes significant digits whose first digit has the power of 10 `exp` in a notation.
+fn with_notation(significand: &str, exp: isize, notation: Notation) -> String {
+    match notation {
+        Notation::Fixed if exp >= 0 => with_point(significand, exp as usize + 1),
+        Notation::Fixed => format!("0.{}{}", "0".repeat((-exp - 1) as usize), significand),
+        Notation::Scientific => format!("{}e{}", with_point(significand, 1), exp),
+        Notation::Engineering => {
+            let before_point = exp.rem_euclid(3) as usize + 1;
+            format!(
+                "{}e{}",
+                with_point(significand, before_point),
+                exp - before_point as isize + 1
+            )
+        }
+    }
+}
+
+/// Puts a decimal point after the first `before_point` digits, padding with zeros if there aren't that many.
+fn with_point(digits: &str, before_point: usize) -> String {
+    if digits.len() <= before_point {
+        format!("{:0<width$}", digits, width = before_point)
+    } else {
+        format!("{}.{}", &digits[..before_point], &digits[before_point..])
+    }
+}
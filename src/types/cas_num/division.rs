@@ -0,0 +1,235 @@
+//division of unsigned integers stored as little endian limbs
+//short divisors use schoolbook long division a limb at a time (Knuth's algorithm D), long ones multiply by a reciprocal found by Newton's method
+
+use std::cmp::Ordering;
+
+use super::multiplication::multiply;
+use super::rational::{cmp, shift_right, trim};
+use super::{CASNum, CASValue, DigitType, Sign};
+
+/// Divisors and quotients with at least this many limbs are divided by multiplying by the reciprocal of the divisor.
+const NEWTON_THRESHOLD: usize = 1536;
+
+/// Divides two unsigned integers, giving the quotient and remainder without leading zero limbs.
+///
+/// The divisor must not be zero.
+pub(super) fn div_rem(
+    dividend: &[DigitType],
+    divisor: &[DigitType],
+) -> (Vec<DigitType>, Vec<DigitType>) {
+    let dividend = trim(dividend.to_vec());
+    let divisor = trim(divisor.to_vec());
+    assert!(!divisor.is_empty(), "division by zero");
+
+    if cmp(&dividend, &divisor) == Ordering::Less {
+        return (vec![], dividend);
+    }
+    if let [divisor] = divisor[..] {
+        return short_div_rem(&dividend, divisor);
+    }
+    let quotient_len = dividend.len() - divisor.len() + 1;
+    if divisor.len() >= NEWTON_THRESHOLD && quotient_len >= NEWTON_THRESHOLD {
+        return newton_div_rem(&dividend, &divisor);
+    }
+    long_div_rem(&dividend, &divisor)
+}
+
+/// The limbs of a finite number's integer part, which is the whole number for integers.
+pub(super) fn integer_limbs(value: &CASNum) -> Vec<DigitType> {
+    let CASValue::Finite { digits, exp } = &value.value else {
+        return vec![];
+    };
+    if *exp < 0 {
+        return vec![];
+    }
+    //exponent of the least significant limb
+    let low = *exp - digits.len() as isize + 1;
+    let fractional = (-low).max(0) as usize;
+    let mut limbs = vec![0; low.max(0) as usize];
+    limbs.extend(digits.iter().skip(fractional));
+    limbs
+}
+
+/// Builds an integer from its sign and little endian limbs.
+pub(super) fn integer_from_limbs(sign: Sign, limbs: &[DigitType]) -> CASNum {
+    let value = CASValue::Finite {
+        digits: limbs.iter().copied().collect(),
+        exp: limbs.len() as isize - 1,
+    }
+    .normalize();
+    CASNum {
+        //zero is always positive
+        sign: if value.is_zero() { Sign::Pos } else { sign },
+        value,
+    }
+}
+
+impl CASNum {
+    /// Divides two integers exactly, giving the quotient rounded toward zero and the remainder, which has the sign of the dividend.
+    ///
+    /// Returns None if either number isn't a finite integer or the divisor is zero.
+    pub(crate) fn div_rem(&self, rhs: &CASNum) -> Option<(CASNum, CASNum)> {
+        if !self.is_integer() || !rhs.is_integer() || rhs.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem(&integer_limbs(self), &integer_limbs(rhs));
+        let quotient_sign = if self.sign == rhs.sign {
+            Sign::Pos
+        } else {
+            Sign::Neg
+        };
+        Some((
+            integer_from_limbs(quotient_sign, &quotient),
+            integer_from_limbs(self.sign, &remainder),
+        ))
+    }
+}
+
+/// Divides by a single limb.
+fn short_div_rem(dividend: &[DigitType], divisor: DigitType) -> (Vec<DigitType>, Vec<DigitType>) {
+    let divisor = divisor as u128;
+    let mut quotient = vec![0; dividend.len()];
+    let mut remainder: u128 = 0;
+    for (idx, digit) in dividend.iter().enumerate().rev() {
+        let current = (remainder << 64) | *digit as u128;
+        quotient[idx] = (current / divisor) as DigitType;
+        remainder = current % divisor;
+    }
+    (trim(quotient), trim(vec![remainder as DigitType]))
+}
+
+/// Long division a limb of the quotient at a time, guessing each limb from the top two limbs of what's left and correcting the guess (Knuth's algorithm D).
+///
+/// The divisor must have at least two limbs and the dividend must be at least as large.
+fn long_div_rem(dividend: &[DigitType], divisor: &[DigitType]) -> (Vec<DigitType>, Vec<DigitType>) {
+    //shifting both so the top bit of the divisor is set makes each guess off by at most 2
+    let shift = divisor.last().unwrap().leading_zeros();
+    let mut divisor = shift_left(divisor, shift);
+    divisor.pop();
+    let mut remainder = shift_left(dividend, shift);
+
+    let len = divisor.len();
+    let (top, second) = (divisor[len - 1] as u128, divisor[len - 2] as u128);
+    let mut quotient = vec![0; dividend.len() - len + 1];
+    for idx in (0..quotient.len()).rev() {
+        let current = (remainder[idx + len] as u128) << 64 | remainder[idx + len - 1] as u128;
+        let mut guess = current / top;
+        let mut guess_remainder = current % top;
+        while guess > DigitType::MAX as u128
+            || guess * second > (guess_remainder << 64 | remainder[idx + len - 2] as u128)
+        {
+            guess -= 1;
+            guess_remainder += top;
+            if guess_remainder > DigitType::MAX as u128 {
+                break;
+            }
+        }
+
+        //remainder -= guess * divisor, shifted to this limb
+        let mut carry: u128 = 0;
+        let mut borrow = false;
+        for (offset, digit) in divisor.iter().enumerate() {
+            let product = guess * *digit as u128 + carry;
+            carry = product >> 64;
+            let (difference, borrow_product) =
+                remainder[idx + offset].overflowing_sub(product as DigitType);
+            let (difference, borrow_carry) = difference.overflowing_sub(borrow as DigitType);
+            remainder[idx + offset] = difference;
+            borrow = borrow_product || borrow_carry;
+        }
+        let (difference, borrow_product) = remainder[idx + len].overflowing_sub(carry as DigitType);
+        let (difference, borrow_carry) = difference.overflowing_sub(borrow as DigitType);
+        remainder[idx + len] = difference;
+
+        if borrow_product || borrow_carry {
+            //the guess was one too big, so add a divisor back
+            guess -= 1;
+            let mut carry = false;
+            for (offset, digit) in divisor.iter().enumerate() {
+                let (sum, carry_digit) = remainder[idx + offset].overflowing_add(*digit);
+                let (sum, carry_carry) = sum.overflowing_add(carry as DigitType);
+                remainder[idx + offset] = sum;
+                carry = carry_digit || carry_carry;
+            }
+            remainder[idx + len] = remainder[idx + len].wrapping_add(carry as DigitType);
+        }
+        quotient[idx] = guess as DigitType;
+    }
+
+    remainder.truncate(len);
+    (trim(quotient), shift_right(&remainder, shift as usize))
+}
+
+/// Divides by multiplying the dividend by the reciprocal of the divisor, then corrects the quotient with the exact remainder.
+///
+/// Finding the reciprocal takes a few multiplications, so this only beats long division once the operands are large enough for fast multiplication to pay off.
+fn newton_div_rem(
+    dividend: &[DigitType],
+    divisor: &[DigitType],
+) -> (Vec<DigitType>, Vec<DigitType>) {
+    let quotient_len = dividend.len() - divisor.len() + 1;
+    let lhs = integer_from_limbs(Sign::Pos, dividend);
+    let rhs = integer_from_limbs(Sign::Pos, divisor);
+
+    //the divisor scaled by a power of 2 ^ 64 to between 1 and 2 ^ 64, so its reciprocal can be estimated with an f64
+    let scale = divisor.len() as isize - 1;
+    let mut scaled = rhs.clone();
+    if let CASValue::Finite { exp, .. } = &mut scaled.value {
+        *exp -= scale;
+    }
+    let mut reciprocal = reciprocal(&scaled, quotient_len + 1);
+    if let CASValue::Finite { exp, .. } = &mut reciprocal.value {
+        *exp -= scale;
+    }
+
+    //the estimate is within a few units of the quotient, so the remainder shows which way to correct it
+    let mut quotient = integer_from_limbs(Sign::Pos, &integer_limbs(&(lhs.clone() * reciprocal)));
+    let mut remainder =
+        lhs - integer_from_limbs(Sign::Pos, &multiply(&integer_limbs(&quotient), divisor));
+    let one = CASNum::from(1);
+    while remainder.sign == Sign::Neg && !remainder.is_zero() {
+        quotient = quotient - one.clone();
+        remainder = remainder + rhs.clone();
+    }
+    while remainder >= rhs {
+        quotient = quotient + one.clone();
+        remainder = remainder - rhs.clone();
+    }
+    (
+        trim(integer_limbs(&quotient)),
+        trim(integer_limbs(&remainder)),
+    )
+}
+
+/// 1 / `value` to `num_limbs` limbs by Newton's method, for a value between 1 and 2 ^ 64, with an error in the last limb.
+///
+/// Each step x -> x + x * (1 - value * x) doubles the number of correct bits, so the steps are taken at doubling precision and only the last is at the full precision.
+fn reciprocal(value: &CASNum, num_limbs: usize) -> CASNum {
+    let target_bits = 64 * num_limbs + 64;
+    //an f64 estimate has 53 correct bits, less a few for rounding
+    let mut bits = 48;
+    let mut estimate = CASNum::from(1.0 / f64::from(value.clone()));
+    while bits < target_bits {
+        bits = (2 * bits - 2).min(target_bits);
+        let work_limbs = bits / 64 + 2;
+        let error = CASNum::from(1)
+            - (value.clone().with_precision(work_limbs) * estimate.clone())
+                .with_precision(work_limbs);
+        estimate = (estimate.clone() + (estimate * error).with_precision(work_limbs))
+            .with_precision(work_limbs);
+    }
+    estimate
+}
+
+/// Shifts an unsigned integer left by fewer than 64 bits, giving one more limb than it had to hold the bits shifted out of the top.
+fn shift_left(limbs: &[DigitType], shift: u32) -> Vec<DigitType> {
+    let mut shifted = Vec::with_capacity(limbs.len() + 1);
+    let mut carry = 0;
+    for limb in limbs {
+        shifted.push((limb << shift) | carry);
+        //shifting by 64 would overflow, and nothing is carried without a shift
+        carry = limb.checked_shr(64 - shift).unwrap_or(0);
+    }
+    shifted.push(carry);
+    shifted
+}
@@ -0,0 +1,692 @@
+//elementary functions, sqrt, ln, sin, atan etc
+//every function takes the number of limbs to round its result to, and is computed with a few guard limbs first
+//results outside of a function's real domain, like ln(-1), are indeterminate
+
+use std::cmp::Ordering;
+
+use super::constants::ln2;
+use super::{CASNum, CASValue, DigitType, Sign, INDETERMINATE, INFINITY, NEG_INFINITY, ZERO};
+
+/// Extra limbs a result is computed with before rounding, tried in turn until the rounding direction is certain, see [correctly_rounded].
+const GUARD_LIMBS: [usize; 4] = [2, 3, 5, 9];
+
+/// Arguments of `exp` and `atan` are reduced until they're smaller than 2^-REDUCED_BITS before summing their series, which makes the series converge faster.
+const REDUCED_BITS: i64 = 8;
+
+/// Size past which `exp` overflows to ∞ or underflows to 0, as a power of 2.
+const MAX_EXP_BITS: u32 = 40;
+
+/// Most iterations of Newton's method before giving up on converging any further.
+const MAX_NEWTON_STEPS: usize = 64;
+
+/// Half of a limb, where rounding to the nearest limb switches from rounding down to rounding up.
+const HALF_LIMB: DigitType = 1 << 63;
+
+impl CASNum {
+    /// Square root, which is indeterminate for negative numbers.
+    pub(crate) fn sqrt(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => ZERO,
+            Class::Positive => correctly_rounded(num_limbs, |limbs| sqrt_finite(self, limbs)),
+            Class::PosInfinity => INFINITY,
+            Class::Negative | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Cube root, which is negative for negative numbers.
+    pub(crate) fn cbrt(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => ZERO,
+            Class::Positive => correctly_rounded(num_limbs, |limbs| cbrt_finite(self, limbs)),
+            Class::Negative => -(-self.clone()).cbrt(num_limbs),
+            Class::PosInfinity => INFINITY,
+            Class::NegInfinity => NEG_INFINITY,
+            Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// e raised to this number.
+    pub(crate) fn exp(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => CASNum::from(1),
+            Class::Positive | Class::Negative => {
+                correctly_rounded(num_limbs, |limbs| exp_finite(self, limbs))
+            }
+            Class::PosInfinity => INFINITY,
+            Class::NegInfinity => ZERO,
+            Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Natural logarithm, which is -∞ for 0 and indeterminate for negative numbers.
+    pub(crate) fn ln(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => NEG_INFINITY,
+            Class::Positive => correctly_rounded(num_limbs, |limbs| ln_finite(self, limbs)),
+            Class::PosInfinity => INFINITY,
+            Class::Negative | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Logarithm with base 2.
+    pub(crate) fn log2(&self, num_limbs: usize) -> CASNum {
+        self.log(&CASNum::from(2), num_limbs)
+    }
+
+    /// Logarithm with base 10.
+    pub(crate) fn log10(&self, num_limbs: usize) -> CASNum {
+        self.log(&CASNum::from(10), num_limbs)
+    }
+
+    /// Logarithm with base `base`, which is indeterminate unless the base is positive and not 1.
+    pub(crate) fn log(&self, base: &CASNum, num_limbs: usize) -> CASNum {
+        if base.classify() != Class::Positive || *base == CASNum::from(1) {
+            return INDETERMINATE;
+        }
+        match self.classify() {
+            Class::Positive => correctly_rounded(num_limbs, |limbs| {
+                ln_finite(self, limbs).quotient(&ln_finite(base, limbs), limbs)
+            }),
+            //the sign of ln(base) decides which way the infinities go
+            _ => self.ln(num_limbs).quotient(&base.ln(num_limbs), num_limbs),
+        }
+    }
+
+    /// Raises this number to a power, which is indeterminate for negative numbers raised to powers that aren't integers.
+    ///
+    /// For infinite exponents, the result goes to ∞ or 0 depending on whether the base is bigger or smaller than 1 in size.
+    pub(crate) fn pow(&self, exp: &CASNum, num_limbs: usize) -> CASNum {
+        if self.value.is_indeterminate() || exp.value.is_indeterminate() {
+            return INDETERMINATE;
+        }
+        if exp.value.is_infinite() {
+            // a ^ -∞ = (1 / a) ^ ∞
+            let base = if exp.sign == Sign::Neg {
+                self.reciprocal(num_limbs)
+            } else {
+                self.clone()
+            };
+            return match (base.abs().partial_cmp(&CASNum::from(1)), base.sign) {
+                (Some(Ordering::Less), _) => ZERO,
+                (Some(Ordering::Greater), Sign::Pos) => INFINITY,
+                //1 ^ ∞ is indeterminate, and powers of a negative base alternate in sign so they have no limit
+                _ => INDETERMINATE,
+            };
+        }
+        if exp.is_zero() {
+            return CASNum::from(1);
+        }
+        if let Ok(n) = u64::try_from(&exp.abs()) {
+            let power = integer_power(self, n, num_limbs + GUARD_LIMBS[0]);
+            let power = if exp.sign == Sign::Neg {
+                CASNum::from(1).quotient(&power, num_limbs + GUARD_LIMBS[0])
+            } else {
+                power
+            };
+            return power.rounded(num_limbs);
+        }
+
+        match self.classify() {
+            Class::Zero if exp.sign == Sign::Pos => ZERO,
+            Class::Zero => INFINITY,
+            Class::Positive => correctly_rounded(num_limbs, |limbs| {
+                //the error in exp * ln(self) is multiplied by the size of the result
+                let extra_limbs = exp.value.exp().unwrap().max(0) as usize + 1;
+                let exponent = (exp.clone() * ln_finite(self, limbs + extra_limbs))
+                    .with_precision(limbs + extra_limbs);
+                exp_finite(&exponent, limbs)
+            }),
+            Class::Negative if exp.is_integer() => {
+                // (-a) ^ n = (-1) ^ n * a ^ n
+                let odd = exp.integer_limb(0) & 1 == 1;
+                let power = (-self.clone()).pow(exp, num_limbs);
+                if odd {
+                    -power
+                } else {
+                    power
+                }
+            }
+            Class::PosInfinity | Class::NegInfinity if exp.sign == Sign::Neg => ZERO,
+            Class::PosInfinity => INFINITY,
+            Class::Negative | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Sine of an angle in radians.
+    pub(crate) fn sin(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => ZERO,
+            Class::Positive | Class::Negative => {
+                correctly_rounded(num_limbs, |limbs| sin_cos_finite(self, limbs).0)
+            }
+            Class::PosInfinity | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Cosine of an angle in radians.
+    pub(crate) fn cos(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => CASNum::from(1),
+            Class::Positive | Class::Negative => {
+                correctly_rounded(num_limbs, |limbs| sin_cos_finite(self, limbs).1)
+            }
+            Class::PosInfinity | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Tangent of an angle in radians.
+    pub(crate) fn tan(&self, num_limbs: usize) -> CASNum {
+        self.trig_ratio(num_limbs, |sin, cos, limbs| sin.quotient(&cos, limbs))
+    }
+
+    /// Cosecant of an angle in radians.
+    pub(crate) fn csc(&self, num_limbs: usize) -> CASNum {
+        self.trig_ratio(num_limbs, |sin, _, limbs| {
+            CASNum::from(1).quotient(&sin, limbs)
+        })
+    }
+
+    /// Secant of an angle in radians.
+    pub(crate) fn sec(&self, num_limbs: usize) -> CASNum {
+        self.trig_ratio(num_limbs, |_, cos, limbs| {
+            CASNum::from(1).quotient(&cos, limbs)
+        })
+    }
+
+    /// Cotangent of an angle in radians.
+    pub(crate) fn cot(&self, num_limbs: usize) -> CASNum {
+        self.trig_ratio(num_limbs, |sin, cos, limbs| cos.quotient(&sin, limbs))
+    }
+
+    /// Arcsine in radians, which is indeterminate outside of -1 to 1.
+    pub(crate) fn asin(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => ZERO,
+            Class::Positive | Class::Negative => match self.abs().partial_cmp(&CASNum::from(1)) {
+                Some(Ordering::Greater) => INDETERMINATE,
+                _ => correctly_rounded(num_limbs, |limbs| asin_finite(self, limbs)),
+            },
+            Class::PosInfinity | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Arccosine in radians, which is indeterminate outside of -1 to 1.
+    pub(crate) fn acos(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero | Class::Positive | Class::Negative => {
+                match self.abs().partial_cmp(&CASNum::from(1)) {
+                    Some(Ordering::Greater) => INDETERMINATE,
+                    _ => correctly_rounded(num_limbs, |limbs| acos_finite(self, limbs)),
+                }
+            }
+            Class::PosInfinity | Class::NegInfinity | Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Arctangent in radians, which goes to ±π / 2 at ±∞.
+    pub(crate) fn atan(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => ZERO,
+            Class::Positive | Class::Negative => {
+                correctly_rounded(num_limbs, |limbs| atan_finite(self, limbs))
+            }
+            Class::PosInfinity => correctly_rounded(num_limbs, half_pi),
+            Class::NegInfinity => -correctly_rounded(num_limbs, half_pi),
+            Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Arccosecant in radians, which is indeterminate between -1 and 1.
+    pub(crate) fn acsc(&self, num_limbs: usize) -> CASNum {
+        self.reciprocal(num_limbs).asin(num_limbs)
+    }
+
+    /// Arcsecant in radians, which is indeterminate between -1 and 1.
+    pub(crate) fn asec(&self, num_limbs: usize) -> CASNum {
+        self.reciprocal(num_limbs).acos(num_limbs)
+    }
+
+    /// Arccotangent in radians, between 0 and π.
+    pub(crate) fn acot(&self, num_limbs: usize) -> CASNum {
+        match self.classify() {
+            Class::Zero => correctly_rounded(num_limbs, half_pi),
+            Class::Positive => correctly_rounded(num_limbs, |limbs| {
+                atan_finite(&self.reciprocal(limbs), limbs)
+            }),
+            Class::Negative => correctly_rounded(num_limbs, |limbs| {
+                // acot(x) = π + atan(1 / x) for x < 0
+                CASNum::pi(limbs) + atan_finite(&self.reciprocal(limbs), limbs)
+            }),
+            Class::PosInfinity => ZERO,
+            Class::NegInfinity => CASNum::pi(num_limbs),
+            Class::Indeterminate => INDETERMINATE,
+        }
+    }
+
+    /// Rounds to the nearest number with at most `num_limbs` limbs, with halfway cases rounded away from 0.
+    pub(crate) fn rounded(self, num_limbs: usize) -> CASNum {
+        let (round_up, ulp_exp) = match &self.value {
+            CASValue::Finite { digits, exp } if digits.len() > num_limbs => (
+                digits[digits.len() - 1 - num_limbs] >= HALF_LIMB,
+                exp - num_limbs as isize + 1,
+            ),
+            _ => return self,
+        };
+
+        let sign = self.sign;
+        let truncated = self.with_precision(num_limbs);
+        if !round_up {
+            return truncated;
+        }
+        let ulp = CASNum {
+            value: CASValue::Finite {
+                digits: [1].into(),
+                exp: ulp_exp,
+            },
+            sign,
+        };
+        truncated + ulp
+    }
+
+    /// Applies a function of the sine and cosine of this number, computed at the given number of limbs.
+    fn trig_ratio(
+        &self,
+        num_limbs: usize,
+        ratio: impl Fn(CASNum, CASNum, usize) -> CASNum,
+    ) -> CASNum {
+        if !self.value.is_finite() {
+            return INDETERMINATE;
+        }
+        correctly_rounded(num_limbs, |limbs| {
+            let (sin, cos) = sin_cos_finite(self, limbs);
+            ratio(sin, cos, limbs)
+        })
+    }
+
+    /// 1 divided by this number, to `num_limbs` limbs.
+    fn reciprocal(&self, num_limbs: usize) -> CASNum {
+        CASNum::from(1).quotient(self, num_limbs)
+    }
+
+    /// Sorts a number into the cases elementary functions treat differently.
+    fn classify(&self) -> Class {
+        match (&self.value, self.sign) {
+            (CASValue::Indeterminate, _) => Class::Indeterminate,
+            (CASValue::Infinite, Sign::Pos) => Class::PosInfinity,
+            (CASValue::Infinite, Sign::Neg) => Class::NegInfinity,
+            _ if self.is_zero() => Class::Zero,
+            (CASValue::Finite { .. }, Sign::Pos) => Class::Positive,
+            (CASValue::Finite { .. }, Sign::Neg) => Class::Negative,
+        }
+    }
+
+    /// Checks if a finite number has no fractional part.
+    pub(super) fn is_integer(&self) -> bool {
+        match &self.value {
+            CASValue::Finite { digits, exp } => digits.len() as isize <= exp + 1,
+            CASValue::Infinite | CASValue::Indeterminate => false,
+        }
+    }
+
+    /// The limb of the integer part of a finite number multiplied by 2^(64 * `idx`).
+    fn integer_limb(&self, idx: isize) -> DigitType {
+        match &self.value {
+            CASValue::Finite { digits, exp } => {
+                let from_top = exp - idx;
+                if from_top < 0 || from_top >= digits.len() as isize {
+                    0
+                } else {
+                    digits[digits.len() - 1 - from_top as usize]
+                }
+            }
+            CASValue::Infinite | CASValue::Indeterminate => 0,
+        }
+    }
+
+    /// The integer nearest to a finite number, with halfway cases rounded away from 0.
+    fn nearest_integer(&self) -> CASNum {
+        let integer_limbs = match &self.value {
+            CASValue::Finite { exp, .. } if *exp >= 0 => *exp as usize + 1,
+            _ => 0,
+        };
+        let truncated = self.clone().with_precision(integer_limbs);
+        let fraction = (self.clone() - truncated.clone()).abs();
+        if fraction >= CASNum::from(0.5) {
+            truncated + CASNum::from(if self.sign == Sign::Pos { 1 } else { -1 })
+        } else {
+            truncated
+        }
+    }
+
+    /// Multiplies a finite number by 2^`bits`, which is exact.
+    pub(super) fn times_power_of_two(&self, bits: i64) -> CASNum {
+        let limbs = bits.div_euclid(64);
+        let shifted = self.clone() * CASNum::from(1_u64 << bits.rem_euclid(64));
+        match shifted.value {
+            CASValue::Finite { digits, exp } => CASNum {
+                value: CASValue::Finite {
+                    digits,
+                    exp: exp + limbs as isize,
+                },
+                sign: shifted.sign,
+            },
+            _ => shifted,
+        }
+    }
+
+    /// Position of the most significant bit of a nonzero finite number, so 1 has position 0 and 0.5 has position -1.
+    pub(super) fn top_bit(&self) -> i64 {
+        match &self.value {
+            CASValue::Finite { digits, exp } => {
+                let top = *digits.back().unwrap();
+                64 * *exp as i64 + 63 - top.leading_zeros() as i64
+            }
+            CASValue::Infinite | CASValue::Indeterminate => 0,
+        }
+    }
+}
+
+/// The cases elementary functions treat differently.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Class {
+    /// 0 or -0
+    Zero,
+    /// A finite number above 0.
+    Positive,
+    /// A finite number below 0.
+    Negative,
+    /// ∞
+    PosInfinity,
+    /// -∞
+    NegInfinity,
+    /// An indeterminate value, like 0 / 0.
+    Indeterminate,
+}
+
+/// Rounds the result of `f` to `num_limbs` limbs, where `f` finds a value to the number of limbs it's given with an error smaller than its last limb.
+///
+/// The value is found with more and more guard limbs until the guard limbs aren't so close to half a limb that the error could change which way it rounds (Ziv's strategy), so the result is the exact value rounded to the nearest.
+pub(super) fn correctly_rounded(num_limbs: usize, f: impl Fn(usize) -> CASNum) -> CASNum {
+    let mut value = ZERO;
+    for guard_limbs in GUARD_LIMBS {
+        value = f(num_limbs + guard_limbs);
+        if !near_halfway(&value, num_limbs, guard_limbs) {
+            break;
+        }
+    }
+    value.rounded(num_limbs)
+}
+
+/// Checks if the guard limbs of a value, other than the last one which could be wrong, are exactly half a limb or just under.
+fn near_halfway(value: &CASNum, num_limbs: usize, guard_limbs: usize) -> bool {
+    let CASValue::Finite { digits, .. } = &value.value else {
+        return false;
+    };
+    //guard limbs from most to least significant, with the zeros normalization removed put back
+    let guard: Vec<DigitType> = (num_limbs..num_limbs + guard_limbs - 1)
+        .map(|from_top| {
+            digits
+                .len()
+                .checked_sub(from_top + 1)
+                .map_or(0, |idx| digits[idx])
+        })
+        .collect();
+    let just_under = guard[1..].iter().all(|limb| *limb == DigitType::MAX);
+    let exactly = guard[1..].iter().all(|limb| *limb == 0);
+    (guard[0] == HALF_LIMB && exactly) || (guard[0] == HALF_LIMB - 1 && just_under)
+}
+
+/// Multiplies two numbers, keeping `num_limbs` limbs.
+pub(super) fn mult(lhs: &CASNum, rhs: &CASNum, num_limbs: usize) -> CASNum {
+    (lhs.clone() * rhs.clone()).with_precision(num_limbs)
+}
+
+/// Sums a series whose terms shrink in size, stopping once a term is too small to change the sum at `num_limbs` limbs.
+///
+/// `term` is called with 0, 1, 2, ... and gives the term with that index.
+pub(super) fn sum_series(num_limbs: usize, mut term: impl FnMut(u64) -> CASNum) -> CASNum {
+    let mut sum = ZERO;
+    for idx in 0.. {
+        let next = term(idx);
+        if next.is_zero() {
+            break;
+        }
+        if !sum.is_zero() && next.top_bit() < sum.top_bit() - 64 * num_limbs as i64 - 64 {
+            break;
+        }
+        sum = (sum + next).with_precision(num_limbs);
+    }
+    sum
+}
+
+/// Raises a number to a positive integer power by repeated squaring.
+fn integer_power(base: &CASNum, mut n: u64, num_limbs: usize) -> CASNum {
+    let mut result = CASNum::from(1);
+    let mut square = base.clone();
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mult(&result, &square, num_limbs);
+        }
+        n >>= 1;
+        if n > 0 {
+            square = mult(&square, &square, num_limbs);
+        }
+    }
+    result
+}
+
+/// Square root of a positive finite number by Newton's method.
+fn sqrt_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    // x = m * 2^(128 * k), so sqrt(x) = sqrt(m) * 2^(64 * k) where m is small enough for an f64 estimate
+    let k = x.value.exp().unwrap().div_euclid(2) as i64;
+    let m = x.times_power_of_two(-128 * k);
+    let estimate = CASNum::from(f64::from(m).sqrt()).times_power_of_two(64 * k);
+
+    newton(estimate, num_limbs, |y| {
+        // y -> (y + x / y) / 2
+        (y.clone() + x.quotient(y, num_limbs)).times_power_of_two(-1)
+    })
+}
+
+/// Cube root of a positive finite number by Newton's method.
+fn cbrt_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    // x = m * 2^(192 * k), so cbrt(x) = cbrt(m) * 2^(64 * k)
+    let k = x.value.exp().unwrap().div_euclid(3) as i64;
+    let m = x.times_power_of_two(-192 * k);
+    let estimate = CASNum::from(f64::from(m).cbrt()).times_power_of_two(64 * k);
+
+    newton(estimate, num_limbs, |y| {
+        // y -> (2 * y + x / y ^ 2) / 3
+        let step = y.times_power_of_two(1) + x.quotient(&mult(y, y, num_limbs), num_limbs);
+        step.quotient(&CASNum::from(3), num_limbs)
+    })
+}
+
+/// Repeats a step of Newton's method from `estimate` until it stops changing the value at `num_limbs` limbs.
+fn newton(estimate: CASNum, num_limbs: usize, step: impl Fn(&CASNum) -> CASNum) -> CASNum {
+    let mut value = estimate;
+    for _ in 0..MAX_NEWTON_STEPS {
+        let next = step(&value).with_precision(num_limbs);
+        let change = next.clone() - value;
+        value = next;
+        if change.is_zero() || change.top_bit() < value.top_bit() - 64 * num_limbs as i64 + 2 {
+            break;
+        }
+    }
+    value
+}
+
+/// e raised to a nonzero finite number.
+fn exp_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    if x.abs() > CASNum::from(1_u64 << MAX_EXP_BITS) {
+        return if x.sign == Sign::Pos { INFINITY } else { ZERO };
+    }
+
+    // x = k * ln(2) + r with |r| <= ln(2) / 2, so e ^ x = 2 ^ k * e ^ r
+    let ln2 = ln2(num_limbs + 1);
+    let k = f64::from(x.quotient(&ln2, num_limbs)).round() as i64;
+    let r = (x.clone() - mult(&CASNum::from(k), &ln2, num_limbs + 1)).with_precision(num_limbs);
+
+    // e ^ r = (e ^ (r / 2 ^ s)) ^ (2 ^ s)
+    let reduced = r.times_power_of_two(-REDUCED_BITS);
+    let mut term = CASNum::from(1);
+    let mut result = sum_series(num_limbs, |idx| {
+        if idx > 0 {
+            term = mult(&term, &reduced, num_limbs).quotient(&CASNum::from(idx), num_limbs);
+        }
+        term.clone()
+    });
+    for _ in 0..REDUCED_BITS {
+        result = mult(&result, &result, num_limbs);
+    }
+    result.times_power_of_two(k)
+}
+
+/// Natural logarithm of a positive finite number.
+fn ln_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    if *x == CASNum::from(1) {
+        return ZERO;
+    }
+
+    // x = 2 ^ n * f with 1 / sqrt(2) <= f < sqrt(2), so ln(x) = n * ln(2) + ln(f)
+    let mut n = x.top_bit();
+    let mut f = x.times_power_of_two(-n);
+    if mult(&f, &f, num_limbs) > CASNum::from(2) {
+        n += 1;
+        f = f.times_power_of_two(-1);
+    }
+
+    // ln(f) = 2 * atanh((f - 1) / (f + 1))
+    let z = (f.clone() - CASNum::from(1)).quotient(&(f + CASNum::from(1)), num_limbs);
+    let ln_f = atanh_series(&z, num_limbs).times_power_of_two(1);
+    if n == 0 {
+        return ln_f;
+    }
+    let extra_limbs = 1 + (64 - n.unsigned_abs().leading_zeros() as usize) / 64;
+    (mult(
+        &CASNum::from(n),
+        &ln2(num_limbs + extra_limbs),
+        num_limbs + extra_limbs,
+    ) + ln_f)
+        .with_precision(num_limbs)
+}
+
+/// atanh(z) = z + z ^ 3 / 3 + z ^ 5 / 5 + ..., for |z| < 1.
+pub(super) fn atanh_series(z: &CASNum, num_limbs: usize) -> CASNum {
+    odd_power_series(z, num_limbs, false)
+}
+
+/// atan(z) = z - z ^ 3 / 3 + z ^ 5 / 5 - ..., for |z| <= 1.
+fn atan_series(z: &CASNum, num_limbs: usize) -> CASNum {
+    odd_power_series(z, num_limbs, true)
+}
+
+/// Sums z ^ (2 * k + 1) / (2 * k + 1) over k, with alternating signs if `alternating` is true.
+fn odd_power_series(z: &CASNum, num_limbs: usize, alternating: bool) -> CASNum {
+    let z_squared = mult(z, z, num_limbs);
+    let z_squared = if alternating { -z_squared } else { z_squared };
+    let mut power = z.clone();
+    sum_series(num_limbs, |idx| {
+        if idx > 0 {
+            power = mult(&power, &z_squared, num_limbs);
+        }
+        power.quotient(&CASNum::from(2 * idx + 1), num_limbs)
+    })
+}
+
+/// π / 2
+fn half_pi(num_limbs: usize) -> CASNum {
+    CASNum::pi(num_limbs).times_power_of_two(-1)
+}
+
+/// Sine and cosine of a nonzero finite number.
+fn sin_cos_finite(x: &CASNum, num_limbs: usize) -> (CASNum, CASNum) {
+    // x = k * π / 2 + r with |r| <= π / 4, and π needs enough limbs that k * π / 2 is still accurate to num_limbs
+    let extra_limbs = x.value.exp().unwrap().max(0) as usize + 1;
+    let limbs = num_limbs + extra_limbs;
+    let half_pi = half_pi(limbs);
+    let k = x.quotient(&half_pi, limbs).nearest_integer();
+    let r = (x.clone() - mult(&k, &half_pi, limbs)).with_precision(num_limbs);
+
+    let r_squared = -mult(&r, &r, num_limbs);
+    let taylor = |first: CASNum, offset: u64| {
+        let mut term = first;
+        sum_series(num_limbs, |idx| {
+            if idx > 0 {
+                let divisor = CASNum::from((2 * idx - 1 + offset) * (2 * idx + offset));
+                term = mult(&term, &r_squared, num_limbs).quotient(&divisor, num_limbs);
+            }
+            term.clone()
+        })
+    };
+    let sin = taylor(r.clone(), 1);
+    let cos = taylor(CASNum::from(1), 0);
+
+    //k mod 4 picks the quadrant
+    let quadrant = if k.sign == Sign::Neg {
+        (4 - k.integer_limb(0) % 4) % 4
+    } else {
+        k.integer_limb(0) % 4
+    };
+    match quadrant {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+/// Arctangent of a nonzero finite number.
+fn atan_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    if x.sign == Sign::Neg {
+        return -atan_finite(&-x.clone(), num_limbs);
+    }
+    if *x > CASNum::from(1) {
+        // atan(x) = π / 2 - atan(1 / x)
+        return half_pi(num_limbs) - atan_finite(&x.reciprocal(num_limbs), num_limbs);
+    }
+
+    // atan(x) = 2 * atan(x / (1 + sqrt(1 + x ^ 2)))
+    let mut reduced = x.clone();
+    let mut halvings = 0;
+    while reduced.top_bit() >= -REDUCED_BITS {
+        let hypotenuse = sqrt_finite(
+            &(CASNum::from(1) + mult(&reduced, &reduced, num_limbs)),
+            num_limbs,
+        );
+        reduced = reduced.quotient(&(CASNum::from(1) + hypotenuse), num_limbs);
+        halvings += 1;
+    }
+    atan_series(&reduced, num_limbs).times_power_of_two(halvings)
+}
+
+/// Arcsine of a nonzero finite number between -1 and 1.
+fn asin_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    if x.abs() == CASNum::from(1) {
+        let half_pi = half_pi(num_limbs);
+        return if x.sign == Sign::Pos {
+            half_pi
+        } else {
+            -half_pi
+        };
+    }
+    // asin(x) = atan(x / sqrt(1 - x ^ 2))
+    let cos = sqrt_finite(&(CASNum::from(1) - mult(x, x, num_limbs)), num_limbs);
+    atan_finite(&x.quotient(&cos, num_limbs), num_limbs)
+}
+
+/// Arccosine of a finite number between -1 and 1.
+fn acos_finite(x: &CASNum, num_limbs: usize) -> CASNum {
+    if *x == CASNum::from(-1) {
+        return CASNum::pi(num_limbs);
+    }
+    if *x == CASNum::from(1) {
+        return ZERO;
+    }
+    if x.is_zero() {
+        return half_pi(num_limbs);
+    }
+    // acos(x) = 2 * atan(sqrt((1 - x) / (1 + x))), which doesn't lose precision near x = 1
+    let ratio = (CASNum::from(1) - x.clone()).quotient(&(CASNum::from(1) + x.clone()), num_limbs);
+    atan_finite(&sqrt_finite(&ratio, num_limbs), num_limbs).times_power_of_two(1)
+}
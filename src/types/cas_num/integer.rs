@@ -0,0 +1,390 @@
+//number theory on integers: gcd, modular arithmetic, factorials, primality and factorization
+//the work is done on the magnitudes as unsigned limbs without leading zeros, and the sign is sorted out at the end
+
+use std::cmp::Ordering;
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::division::{div_rem, integer_from_limbs, integer_limbs};
+use super::rational::{add, cmp, gcd, mul, shift_right, sub, trim};
+use super::{CASNum, DigitType, Sign};
+
+/// Largest number factorials are found for, and largest `k` that binomial coefficients are found for, so something like `fact(10 ^ 12)` doesn't try to build a number with trillions of digits.
+const MAX_FACTORIAL: DigitType = 100_000;
+
+/// Primes that numbers are divided by before testing them with Miller–Rabin or factoring them with Pollard's rho method.
+const SMALL_PRIMES: [DigitType; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Numbers below this have a factor in [SMALL_PRIMES] unless they're prime, since the next prime is 101.
+const SMALL_PRIMES_BOUND: DigitType = 101 * 101;
+
+/// Miller–Rabin tests with the first 12 primes as bases are never wrong below this, which is about 3.2e23.
+const DETERMINISTIC_BOUND: u128 = 318_665_857_834_031_151_167_461;
+
+/// Random bases numbers from [DETERMINISTIC_BOUND] up are tested with. Each lets through at most a quarter of composite numbers.
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+/// Steps Pollard's rho method takes between finding the gcd of the product of the differences it found with the number.
+const RHO_BATCH: usize = 64;
+
+impl CASNum {
+    /// The greatest common divisor of two integers, which is never negative.
+    ///
+    /// Returns None if either number isn't an integer.
+    pub(crate) fn gcd(&self, rhs: &CASNum) -> Option<CASNum> {
+        Some(positive(&gcd(&magnitude(self)?, &magnitude(rhs)?)))
+    }
+
+    /// The least common multiple of two integers, which is never negative and is zero if either of them is.
+    ///
+    /// Returns None if either number isn't an integer.
+    pub(crate) fn lcm(&self, rhs: &CASNum) -> Option<CASNum> {
+        let (lhs, rhs) = (magnitude(self)?, magnitude(rhs)?);
+        if lhs.is_empty() || rhs.is_empty() {
+            return Some(positive(&[]));
+        }
+        let divisor = gcd(&lhs, &rhs);
+        Some(positive(&mul(&div_rem(&lhs, &divisor).0, &rhs)))
+    }
+
+    /// The integer modulo `modulus`, which is between 0 and the magnitude of the modulus whatever their signs, so `-7 mod 3 = 2`.
+    ///
+    /// Returns None if either number isn't an integer or the modulus is zero.
+    pub(crate) fn modulo(&self, modulus: &CASNum) -> Option<CASNum> {
+        let modulus = magnitude(modulus).filter(|modulus| !modulus.is_empty())?;
+        Some(positive(&reduce(self, &modulus)?))
+    }
+
+    /// The integer to the power of `exp` modulo `modulus`, found without building the power, see [CASNum::modulo].
+    ///
+    /// Returns None if any of the numbers isn't an integer, the exponent is negative or the modulus is zero.
+    pub(crate) fn mod_pow(&self, exp: &CASNum, modulus: &CASNum) -> Option<CASNum> {
+        if is_negative(exp) {
+            return None;
+        }
+        let modulus = magnitude(modulus).filter(|modulus| !modulus.is_empty())?;
+        let base = reduce(self, &modulus)?;
+        Some(positive(&mod_pow(&base, &magnitude(exp)?, &modulus)))
+    }
+
+    /// The square root of an integer rounded down.
+    ///
+    /// Returns None if the number isn't an integer or is negative.
+    pub(crate) fn isqrt(&self) -> Option<CASNum> {
+        if is_negative(self) {
+            return None;
+        }
+        Some(positive(&isqrt(&magnitude(self)?)))
+    }
+
+    /// The factorial of an integer.
+    ///
+    /// Returns None if the number isn't an integer, is negative or is larger than 100000.
+    pub(crate) fn factorial(&self) -> Option<CASNum> {
+        let n = small(self)?;
+        Some(positive(&factorial(n)))
+    }
+
+    /// The binomial coefficient `self` choose `k`, the number of ways to pick `k` things out of `self`. It's zero if `k` is negative or larger than `self`.
+    ///
+    /// Returns None if either number isn't an integer, `self` is negative, or both `k` and `self - k` are larger than 100000.
+    pub(crate) fn binomial(&self, k: &CASNum) -> Option<CASNum> {
+        let n = magnitude(self).filter(|_| !is_negative(self))?;
+        let k_limbs = magnitude(k)?;
+        if is_negative(k) || cmp(&k_limbs, &n) == Ordering::Greater {
+            return Some(positive(&[]));
+        }
+        //n choose k is n choose n - k, so the fewest factors are multiplied by picking the smaller
+        let rest = sub(&n, &k_limbs);
+        let k = if cmp(&rest, &k_limbs) == Ordering::Less {
+            rest
+        } else {
+            k_limbs
+        };
+        let k = small(&positive(&k))?;
+        let factors: Vec<Vec<DigitType>> = (0..k).map(|idx| sub(&n, &trim(vec![idx]))).collect();
+        Some(positive(&div_rem(&product(&factors), &factorial(k)).0))
+    }
+
+    /// Checks if an integer is prime.
+    ///
+    /// Numbers below about 3.2e23 are tested exactly. Larger ones are tested with 32 random Miller–Rabin bases, which wrongly call a composite number prime with a probability below 2 ^ -64. The bases come from a fixed seed, so a number always gets the same answer. Returns None if the number isn't an integer.
+    pub(crate) fn is_prime(&self) -> Option<bool> {
+        let n = magnitude(self)?;
+        Some(!is_negative(self) && is_prime(&n))
+    }
+
+    /// The prime factors of the magnitude of an integer in ascending order, with how many times each divides it. 1 has no prime factors.
+    ///
+    /// Factors up to 97 are found by trial division, larger ones by Pollard's rho method, which takes time proportional to the square root of the second largest prime factor. Returns None if the number isn't an integer or is zero.
+    pub(crate) fn factor(&self) -> Option<Vec<(CASNum, u64)>> {
+        let n = magnitude(self).filter(|n| !n.is_empty())?;
+        let mut primes = factor(&n);
+        primes.sort_by(|lhs, rhs| cmp(lhs, rhs));
+
+        let mut factors: Vec<(Vec<DigitType>, u64)> = vec![];
+        for prime in primes {
+            match factors.last_mut() {
+                Some((last, count)) if *last == prime => *count += 1,
+                _ => factors.push((prime, 1)),
+            }
+        }
+        Some(
+            factors
+                .into_iter()
+                .map(|(prime, count)| (positive(&prime), count))
+                .collect(),
+        )
+    }
+}
+
+/// The magnitude of a number as limbs, or None if it isn't an integer.
+fn magnitude(value: &CASNum) -> Option<Vec<DigitType>> {
+    value.is_integer().then(|| trim(integer_limbs(value)))
+}
+
+/// Builds a nonnegative integer from its limbs.
+fn positive(limbs: &[DigitType]) -> CASNum {
+    integer_from_limbs(Sign::Pos, limbs)
+}
+
+/// Checks if a number is below zero.
+fn is_negative(value: &CASNum) -> bool {
+    value.sign == Sign::Neg && !value.is_zero()
+}
+
+/// A nonnegative integer no larger than [MAX_FACTORIAL].
+fn small(value: &CASNum) -> Option<DigitType> {
+    match magnitude(value)?.as_slice() {
+        _ if is_negative(value) => None,
+        [] => Some(0),
+        [n] if *n <= MAX_FACTORIAL => Some(*n),
+        _ => None,
+    }
+}
+
+/// An integer modulo a positive modulus, between 0 and the modulus, or None if it isn't an integer.
+fn reduce(value: &CASNum, modulus: &[DigitType]) -> Option<Vec<DigitType>> {
+    let remainder = div_rem(&magnitude(value)?, modulus).1;
+    if is_negative(value) && !remainder.is_empty() {
+        //-a mod m = m - (a mod m)
+        Some(sub(modulus, &remainder))
+    } else {
+        Some(remainder)
+    }
+}
+
+/// `lhs * rhs` modulo `modulus`.
+fn mul_mod(lhs: &[DigitType], rhs: &[DigitType], modulus: &[DigitType]) -> Vec<DigitType> {
+    div_rem(&mul(lhs, rhs), modulus).1
+}
+
+/// `base ^ exp` modulo `modulus`, by squaring the base once for each bit of the exponent and multiplying in the squares for the bits that are set.
+fn mod_pow(base: &[DigitType], exp: &[DigitType], modulus: &[DigitType]) -> Vec<DigitType> {
+    //1 mod 1 is 0
+    let mut power = div_rem(&[1], modulus).1;
+    let mut square = base.to_vec();
+    let bits = bit_len(exp);
+    for bit in 0..bits {
+        if (exp[bit / 64] >> (bit % 64)) & 1 == 1 {
+            power = mul_mod(&power, &square, modulus);
+        }
+        if bit + 1 < bits {
+            square = mul_mod(&square, &square, modulus);
+        }
+    }
+    power
+}
+
+/// Number of bits up to the highest set bit.
+fn bit_len(limbs: &[DigitType]) -> usize {
+    limbs
+        .last()
+        .map_or(0, |top| 64 * limbs.len() - top.leading_zeros() as usize)
+}
+
+/// The square root of an unsigned integer rounded down, by Newton's method.
+pub(super) fn isqrt(n: &[DigitType]) -> Vec<DigitType> {
+    if n.is_empty() {
+        return vec![];
+    }
+    //2 ^ ceil(bits / 2) is at least the root, and each step from above the root stays above it until the root is reached
+    let half_bits = bit_len(n).div_ceil(2);
+    let mut root = vec![0; half_bits / 64];
+    root.push(1 << (half_bits % 64));
+    loop {
+        let next = shift_right(&add(&root, &div_rem(n, &root).0), 1);
+        if cmp(&next, &root) != Ordering::Less {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// `n!` for a small `n`.
+fn factorial(n: DigitType) -> Vec<DigitType> {
+    let factors: Vec<Vec<DigitType>> = (2..=n).map(|factor| vec![factor]).collect();
+    product(&factors)
+}
+
+/// The product of unsigned integers, multiplying the products of each half so the operands of each multiplication have about the same size.
+fn product(factors: &[Vec<DigitType>]) -> Vec<DigitType> {
+    match factors {
+        [] => vec![1],
+        [factor] => factor.clone(),
+        _ => {
+            let (lhs, rhs) = factors.split_at(factors.len() / 2);
+            mul(&product(lhs), &product(rhs))
+        }
+    }
+}
+
+/// Checks if an unsigned integer is prime, by trial division by [SMALL_PRIMES] then Miller–Rabin tests.
+fn is_prime(n: &[DigitType]) -> bool {
+    if cmp(n, &[2]) == Ordering::Less {
+        return false;
+    }
+    for prime in SMALL_PRIMES {
+        if n == [prime] {
+            return true;
+        }
+        if div_rem(n, &[prime]).1.is_empty() {
+            return false;
+        }
+    }
+    if cmp(n, &[SMALL_PRIMES_BOUND]) == Ordering::Less {
+        return true;
+    }
+
+    let small = match n {
+        [low] => Some(*low as u128),
+        [low, high] => Some((*high as u128) << 64 | *low as u128),
+        _ => None,
+    };
+    if small.is_some_and(|n| n < DETERMINISTIC_BOUND) {
+        return SMALL_PRIMES[..12]
+            .iter()
+            .all(|base| is_strong_probable_prime(n, &[*base]));
+    }
+
+    //random bases between 2 and n - 2
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let range = sub(n, &[3]);
+    (0..MILLER_RABIN_ROUNDS).all(|_| {
+        let random: Vec<DigitType> = (0..=n.len()).map(|_| rng.next_u64()).collect();
+        let base = add(&div_rem(&random, &range).1, &[2]);
+        is_strong_probable_prime(n, &base)
+    })
+}
+
+/// The Miller–Rabin test of an odd number `n` with a base less than it.
+///
+/// Writing `n - 1` as `d * 2 ^ s` with `d` odd, every prime passes, since either `base ^ d = 1` or `base ^ (d * 2 ^ r) = -1` for some `r < s`. At most a quarter of bases let a composite number pass.
+fn is_strong_probable_prime(n: &[DigitType], base: &[DigitType]) -> bool {
+    let n_minus_one = sub(n, &[1]);
+    let twos = n_minus_one
+        .iter()
+        .position(|limb| *limb != 0)
+        .map_or(0, |idx| {
+            64 * idx + n_minus_one[idx].trailing_zeros() as usize
+        });
+    let mut power = mod_pow(base, &shift_right(&n_minus_one, twos), n);
+    if power == [1] || power == n_minus_one {
+        return true;
+    }
+    for _ in 1..twos {
+        power = mul_mod(&power, &power, n);
+        if power == n_minus_one {
+            return true;
+        }
+        if power == [1] {
+            return false;
+        }
+    }
+    false
+}
+
+/// The prime factors of an unsigned integer greater than zero, with repeats, in no particular order.
+fn factor(n: &[DigitType]) -> Vec<Vec<DigitType>> {
+    let mut primes = vec![];
+    let mut rest = n.to_vec();
+    for prime in SMALL_PRIMES {
+        loop {
+            let (quotient, remainder) = div_rem(&rest, &[prime]);
+            if !remainder.is_empty() {
+                break;
+            }
+            primes.push(vec![prime]);
+            rest = quotient;
+        }
+    }
+
+    //numbers left to split, which have no factors in SMALL_PRIMES
+    let mut pending = vec![rest];
+    while let Some(n) = pending.pop() {
+        if n == [1] {
+            continue;
+        }
+        if is_prime(&n) {
+            primes.push(n);
+            continue;
+        }
+        let divisor = rho_divisor(&n);
+        pending.push(div_rem(&n, &divisor).0);
+        pending.push(divisor);
+    }
+    primes
+}
+
+/// A divisor of a composite number other than 1 and itself, by Pollard's rho method.
+///
+/// The sequence `x -> x ^ 2 + c` modulo a prime factor `p` of `n` repeats after about `sqrt(p)` steps, and when a step `x` and a step `y` twice as far along meet modulo `p`, `gcd(x - y, n)` is a multiple of `p`. The gcd is taken of the product of a batch of differences, and if that's all of `n` the batch is gone back over a step at a time. If the sequences meet modulo every factor at once, `c` is changed.
+fn rho_divisor(n: &[DigitType]) -> Vec<DigitType> {
+    for c in 1.. {
+        let step = |x: &[DigitType]| div_rem(&add(&mul(x, x), &[c]), n).1;
+        let (mut x, mut y) = (vec![2], vec![2]);
+        loop {
+            let (batch_x, batch_y) = (x.clone(), y.clone());
+            let mut differences = vec![1];
+            for _ in 0..RHO_BATCH {
+                x = step(&x);
+                y = step(&step(&y));
+                differences = mul_mod(&differences, &difference(&x, &y), n);
+            }
+            let divisor = gcd(&differences, n);
+            if divisor == [1] {
+                continue;
+            }
+            if divisor != n {
+                return divisor;
+            }
+
+            (x, y) = (batch_x, batch_y);
+            let divisor = loop {
+                x = step(&x);
+                y = step(&step(&y));
+                let divisor = gcd(&difference(&x, &y), n);
+                if divisor != [1] {
+                    break divisor;
+                }
+            };
+            if divisor != n {
+                return divisor;
+            }
+            break;
+        }
+    }
+    unreachable!("every composite number has a divisor")
+}
+
+/// The magnitude of the difference of two unsigned integers.
+fn difference(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    if cmp(lhs, rhs) == Ordering::Less {
+        sub(rhs, lhs)
+    } else {
+        sub(lhs, rhs)
+    }
+}
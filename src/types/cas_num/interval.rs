@@ -0,0 +1,568 @@
+//closed intervals of real numbers with a CASNum at each end, for bounds that are guaranteed to hold an exact value
+//every operation rounds its lower end down and its upper end up, so the exact result for any numbers in its arguments is always inside
+//elementary functions are correctly rounded with an extra limb, so moving a limb at that precision outward from them is enough
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::Neg;
+
+use super::{
+    CASNum, CASValue, Context, RoundingMode, Sign, INDETERMINATE, INFINITY, NEG_INFINITY, ZERO,
+};
+
+/// Extra limbs the ends of elementary functions are found to, before they're rounded outward.
+const EXTRA_LIMBS: usize = 1;
+
+/// A closed interval of real numbers, which can have infinite ends.
+///
+/// Parts of an argument outside of a function's domain are left out, so `sqrt` of the interval from -1 to 4 is the interval from 0 to 2. Results of functions that aren't defined anywhere in an argument, like `sqrt` of an interval of negative numbers, have indeterminate ends.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CASInterval {
+    /// The lower end.
+    pub(crate) lo: CASNum,
+    /// The upper end.
+    pub(crate) hi: CASNum,
+}
+
+/// An interval whose ends are both indeterminate.
+const INTERVAL_INDETERMINATE: CASInterval = CASInterval {
+    lo: INDETERMINATE,
+    hi: INDETERMINATE,
+};
+
+/// The interval holding every real number.
+const ENTIRE: CASInterval = CASInterval {
+    lo: NEG_INFINITY,
+    hi: INFINITY,
+};
+
+impl CASInterval {
+    /// The interval between `lo` and `hi`, which are swapped if `hi` is the smaller one.
+    pub(crate) fn new(lo: CASNum, hi: CASNum) -> Self {
+        if hi < lo {
+            CASInterval { lo: hi, hi: lo }
+        } else {
+            CASInterval { lo, hi }
+        }
+        .checked()
+    }
+
+    /// The interval holding just `value`.
+    pub(crate) fn point(value: CASNum) -> Self {
+        CASInterval::new(value.clone(), value)
+    }
+
+    /// An interval with `num_limbs` limb ends holding the exact value of a function, given the function correctly rounded to any number of limbs, like [CASNum::pi] or [CASNum::exp].
+    pub(crate) fn enclose(num_limbs: usize, f: impl Fn(usize) -> CASNum) -> Self {
+        let value = f(num_limbs + EXTRA_LIMBS);
+        let exp = match &value.value {
+            CASValue::Finite { exp, .. } if !value.is_zero() => *exp,
+            //0 and infinities are only ever exact results
+            _ => return CASInterval::point(value),
+        };
+        //the exact value is within half of this of the correctly rounded one
+        let last_limb = CASNum {
+            value: CASValue::Finite {
+                digits: [1].into(),
+                exp: exp - (num_limbs + EXTRA_LIMBS) as isize + 1,
+            },
+            sign: Sign::Pos,
+        };
+        CASInterval {
+            lo: value.sub_with(&last_limb, down(num_limbs)),
+            hi: value.add_with(&last_limb, up(num_limbs)),
+        }
+    }
+
+    /// Checks if either end is indeterminate.
+    pub(crate) fn is_indeterminate(&self) -> bool {
+        self.lo.value.is_indeterminate() || self.hi.value.is_indeterminate()
+    }
+
+    /// Checks if `value` is between the ends.
+    pub(crate) fn contains(&self, value: &CASNum) -> bool {
+        self.lo <= *value && *value <= self.hi
+    }
+
+    /// The smallest interval holding both intervals. An indeterminate interval holds nothing, so the hull with one is the other interval.
+    pub(crate) fn hull(&self, other: &CASInterval) -> Self {
+        if self.is_indeterminate() {
+            return other.clone();
+        }
+        if other.is_indeterminate() {
+            return self.clone();
+        }
+        CASInterval {
+            lo: min(&self.lo, &other.lo),
+            hi: max(&self.hi, &other.hi),
+        }
+    }
+
+    /// The part of the interval between `lo` and `hi`, which is indeterminate if there isn't any.
+    fn intersect(&self, lo: &CASNum, hi: &CASNum) -> Self {
+        let lo = max(&self.lo, lo);
+        let hi = min(&self.hi, hi);
+        if hi < lo {
+            return INTERVAL_INDETERMINATE;
+        }
+        CASInterval { lo, hi }.checked()
+    }
+
+    /// Rounds the ends outward to `digits` significant decimal digits, so the interval still holds everything it did and its ends are written with at most that many digits.
+    pub(crate) fn round_decimal(&self, digits: usize) -> Self {
+        CASInterval {
+            lo: self.lo.round_decimal(digits, RoundingMode::Floor),
+            hi: self.hi.round_decimal(digits, RoundingMode::Ceiling),
+        }
+    }
+
+    /// Makes the whole interval indeterminate if either end is.
+    fn checked(self) -> Self {
+        if self.is_indeterminate() {
+            INTERVAL_INDETERMINATE
+        } else {
+            self
+        }
+    }
+
+    /// The sum of two intervals, with ends rounded outward to `num_limbs` limbs.
+    pub(crate) fn add(&self, rhs: &CASInterval, num_limbs: usize) -> Self {
+        CASInterval {
+            lo: self.lo.add_with(&rhs.lo, down(num_limbs)),
+            hi: self.hi.add_with(&rhs.hi, up(num_limbs)),
+        }
+        .checked()
+    }
+
+    /// The difference of two intervals, with ends rounded outward to `num_limbs` limbs.
+    pub(crate) fn sub(&self, rhs: &CASInterval, num_limbs: usize) -> Self {
+        CASInterval {
+            lo: self.lo.sub_with(&rhs.hi, down(num_limbs)),
+            hi: self.hi.sub_with(&rhs.lo, up(num_limbs)),
+        }
+        .checked()
+    }
+
+    /// The product of two intervals, with ends rounded outward to `num_limbs` limbs.
+    pub(crate) fn mul(&self, rhs: &CASInterval, num_limbs: usize) -> Self {
+        if self.is_indeterminate() || rhs.is_indeterminate() {
+            return INTERVAL_INDETERMINATE;
+        }
+        //infinite ends are limits of the numbers in an interval rather than numbers in it, so 0 * ∞ is 0
+        let product = |lhs: &CASNum, rhs: &CASNum, context: Context| {
+            if lhs.is_zero() || rhs.is_zero() {
+                ZERO
+            } else {
+                lhs.mul_with(rhs, context)
+            }
+        };
+        let ends = [
+            (&self.lo, &rhs.lo),
+            (&self.lo, &rhs.hi),
+            (&self.hi, &rhs.lo),
+            (&self.hi, &rhs.hi),
+        ];
+        let lo = ends
+            .iter()
+            .map(|(lhs, rhs)| product(lhs, rhs, down(num_limbs)))
+            .reduce(|lhs, rhs| min(&lhs, &rhs))
+            .unwrap();
+        let hi = ends
+            .iter()
+            .map(|(lhs, rhs)| product(lhs, rhs, up(num_limbs)))
+            .reduce(|lhs, rhs| max(&lhs, &rhs))
+            .unwrap();
+        CASInterval { lo, hi }
+    }
+
+    /// The quotient of two intervals, with ends rounded outward to `num_limbs` limbs.
+    ///
+    /// Dividing by an interval with 0 strictly inside it gives every real number.
+    pub(crate) fn div(&self, rhs: &CASInterval, num_limbs: usize) -> Self {
+        self.mul(&rhs.recip(num_limbs + EXTRA_LIMBS), num_limbs)
+    }
+
+    /// 1 divided by the interval, which is indeterminate for the interval holding just 0.
+    fn recip(&self, num_limbs: usize) -> Self {
+        if self.is_indeterminate() {
+            return INTERVAL_INDETERMINATE;
+        }
+        let one = CASNum::from(1);
+        match (self.lo.partial_cmp(&ZERO), self.hi.partial_cmp(&ZERO)) {
+            (Some(Ordering::Equal), Some(Ordering::Equal)) => INTERVAL_INDETERMINATE,
+            (Some(Ordering::Less), Some(Ordering::Greater)) => ENTIRE,
+            (Some(Ordering::Equal), _) => CASInterval {
+                lo: one.div_with(&self.hi, down(num_limbs)),
+                hi: INFINITY,
+            },
+            (_, Some(Ordering::Equal)) => CASInterval {
+                lo: NEG_INFINITY,
+                hi: one.div_with(&self.lo, up(num_limbs)),
+            },
+            _ => CASInterval {
+                lo: one.div_with(&self.hi, down(num_limbs)),
+                hi: one.div_with(&self.lo, up(num_limbs)),
+            },
+        }
+    }
+
+    /// The absolute values of the numbers in the interval.
+    fn abs(&self) -> Self {
+        if self.lo >= ZERO {
+            self.clone()
+        } else if self.hi <= ZERO {
+            -self.clone()
+        } else {
+            CASInterval {
+                lo: ZERO,
+                hi: max(&-self.lo.clone(), &self.hi),
+            }
+        }
+    }
+
+    /// The interval raised to a power, with ends rounded outward to `num_limbs` limbs.
+    ///
+    /// Any number can be raised to an integer, as with [CASNum::pow]. Other powers are `exp(exp * ln(x))`, so they leave out negative numbers.
+    pub(crate) fn pow(&self, exp: &CASInterval, num_limbs: usize) -> Self {
+        if exp.lo != exp.hi || !exp.lo.is_integer() {
+            return self
+                .ln(num_limbs + EXTRA_LIMBS)
+                .mul(exp, num_limbs + EXTRA_LIMBS)
+                .exp(num_limbs);
+        }
+        let n = &exp.lo;
+        if n.is_zero() {
+            return CASInterval::point(CASNum::from(1));
+        }
+        let magnitude = n.abs();
+        let power = |base: &CASInterval, limbs| match u64::try_from(&magnitude) {
+            //exact powers stay exact
+            Ok(n) => CASInterval {
+                lo: power_rounded(&base.lo, n, down(limbs)),
+                hi: power_rounded(&base.hi, n, up(limbs)),
+            }
+            .checked(),
+            Err(_) => base.monotone(true, limbs, |x, limbs| x.pow(&magnitude, limbs)),
+        };
+        //even powers have their smallest value at 0, and odd powers increase everywhere
+        let base = if magnitude.times_power_of_two(-1).is_integer() {
+            self.abs()
+        } else {
+            self.clone()
+        };
+        if n.sign == Sign::Neg {
+            power(&base, num_limbs + EXTRA_LIMBS).recip(num_limbs)
+        } else {
+            power(&base, num_limbs)
+        }
+    }
+
+    /// Square root.
+    pub(crate) fn sqrt(&self, num_limbs: usize) -> Self {
+        self.intersect(&ZERO, &INFINITY)
+            .monotone(true, num_limbs, CASNum::sqrt)
+    }
+
+    /// Cube root.
+    pub(crate) fn cbrt(&self, num_limbs: usize) -> Self {
+        self.monotone(true, num_limbs, CASNum::cbrt)
+    }
+
+    /// e raised to the interval.
+    pub(crate) fn exp(&self, num_limbs: usize) -> Self {
+        self.monotone(true, num_limbs, CASNum::exp)
+    }
+
+    /// Natural logarithm.
+    pub(crate) fn ln(&self, num_limbs: usize) -> Self {
+        self.intersect(&ZERO, &INFINITY)
+            .monotone(true, num_limbs, CASNum::ln)
+    }
+
+    /// Logarithm with base `base`.
+    pub(crate) fn log(&self, base: &CASInterval, num_limbs: usize) -> Self {
+        let limbs = num_limbs + EXTRA_LIMBS;
+        self.ln(limbs).div(&base.ln(limbs), num_limbs)
+    }
+
+    /// Logarithm with base 2.
+    pub(crate) fn log2(&self, num_limbs: usize) -> Self {
+        self.log(&CASInterval::point(CASNum::from(2)), num_limbs)
+    }
+
+    /// Logarithm with base 10.
+    pub(crate) fn log10(&self, num_limbs: usize) -> Self {
+        self.log(&CASInterval::point(CASNum::from(10)), num_limbs)
+    }
+
+    /// Sine of an interval of angles in radians.
+    pub(crate) fn sin(&self, num_limbs: usize) -> Self {
+        //sin(x) is 1 where x / π - 1 / 2 is even and -1 where it's odd
+        self.periodic(num_limbs, &CASNum::from(0.5), CASNum::sin)
+    }
+
+    /// Cosine of an interval of angles in radians.
+    pub(crate) fn cos(&self, num_limbs: usize) -> Self {
+        //cos(x) is 1 where x / π is even and -1 where it's odd
+        self.periodic(num_limbs, &ZERO, CASNum::cos)
+    }
+
+    /// Tangent of an interval of angles in radians, which is every real number if it has a pole in it.
+    pub(crate) fn tan(&self, num_limbs: usize) -> Self {
+        //tan(x) has poles where x / π - 1 / 2 is an integer
+        self.between_poles(num_limbs, &CASNum::from(0.5), true, CASNum::tan)
+    }
+
+    /// Cosecant of an interval of angles in radians.
+    pub(crate) fn csc(&self, num_limbs: usize) -> Self {
+        self.sin(num_limbs + EXTRA_LIMBS).recip(num_limbs)
+    }
+
+    /// Secant of an interval of angles in radians.
+    pub(crate) fn sec(&self, num_limbs: usize) -> Self {
+        self.cos(num_limbs + EXTRA_LIMBS).recip(num_limbs)
+    }
+
+    /// Cotangent of an interval of angles in radians, which is every real number if it has a pole in it.
+    pub(crate) fn cot(&self, num_limbs: usize) -> Self {
+        //cot(x) has poles where x / π is an integer
+        self.between_poles(num_limbs, &ZERO, false, CASNum::cot)
+    }
+
+    /// Arcsine in radians.
+    pub(crate) fn asin(&self, num_limbs: usize) -> Self {
+        self.intersect(&CASNum::from(-1), &CASNum::from(1))
+            .monotone(true, num_limbs, CASNum::asin)
+    }
+
+    /// Arccosine in radians.
+    pub(crate) fn acos(&self, num_limbs: usize) -> Self {
+        self.intersect(&CASNum::from(-1), &CASNum::from(1))
+            .monotone(false, num_limbs, CASNum::acos)
+    }
+
+    /// Arctangent in radians.
+    pub(crate) fn atan(&self, num_limbs: usize) -> Self {
+        self.monotone(true, num_limbs, CASNum::atan)
+    }
+
+    /// Arccosecant in radians.
+    pub(crate) fn acsc(&self, num_limbs: usize) -> Self {
+        self.recip(num_limbs + EXTRA_LIMBS).asin(num_limbs)
+    }
+
+    /// Arcsecant in radians.
+    pub(crate) fn asec(&self, num_limbs: usize) -> Self {
+        self.recip(num_limbs + EXTRA_LIMBS).acos(num_limbs)
+    }
+
+    /// Arccotangent in radians, which decreases from π at -∞ to 0 at ∞.
+    pub(crate) fn acot(&self, num_limbs: usize) -> Self {
+        self.monotone(false, num_limbs, CASNum::acot)
+    }
+
+    /// Applies a function that increases or decreases everywhere in the interval, from its values at the ends.
+    fn monotone(
+        &self,
+        increasing: bool,
+        num_limbs: usize,
+        f: impl Fn(&CASNum, usize) -> CASNum,
+    ) -> Self {
+        if self.is_indeterminate() {
+            return INTERVAL_INDETERMINATE;
+        }
+        let at_lo = CASInterval::enclose(num_limbs, |limbs| f(&self.lo, limbs));
+        let at_hi = if self.lo == self.hi {
+            at_lo.clone()
+        } else {
+            CASInterval::enclose(num_limbs, |limbs| f(&self.hi, limbs))
+        };
+        if increasing {
+            CASInterval {
+                lo: at_lo.lo,
+                hi: at_hi.hi,
+            }
+        } else {
+            CASInterval {
+                lo: at_hi.lo,
+                hi: at_lo.hi,
+            }
+        }
+        .checked()
+    }
+
+    /// Applies a function like sin or cos, which has a period of 2π, is 1 where x / π - `shift` is an even integer and -1 where it's an odd integer, and is monotone in between.
+    fn periodic(
+        &self,
+        num_limbs: usize,
+        shift: &CASNum,
+        f: impl Fn(&CASNum, usize) -> CASNum,
+    ) -> Self {
+        let Some((first, last)) = self.turns(num_limbs, shift) else {
+            return self.unbounded(CASInterval::new(CASNum::from(-1), CASNum::from(1)));
+        };
+        let at_lo = CASInterval::point(self.lo.clone()).monotone(true, num_limbs, &f);
+        let at_hi = CASInterval::point(self.hi.clone()).monotone(true, num_limbs, &f);
+        let mut value = at_lo.hull(&at_hi);
+        match (last.clone() - first.clone()).partial_cmp(&ZERO) {
+            Some(Ordering::Less) => {}
+            Some(Ordering::Equal) if first.times_power_of_two(-1).is_integer() => {
+                value.hi = CASNum::from(1)
+            }
+            Some(Ordering::Equal) => value.lo = CASNum::from(-1),
+            _ => return CASInterval::new(CASNum::from(-1), CASNum::from(1)),
+        }
+        //rounding outward can go past 1 or -1
+        value.intersect(&CASNum::from(-1), &CASNum::from(1))
+    }
+
+    /// Applies a function like tan or cot, which has a pole where x / π - `shift` is an integer and increases or decreases in between.
+    fn between_poles(
+        &self,
+        num_limbs: usize,
+        shift: &CASNum,
+        increasing: bool,
+        f: impl Fn(&CASNum, usize) -> CASNum,
+    ) -> Self {
+        match self.turns(num_limbs, shift) {
+            Some((first, last)) if last < first => self.monotone(increasing, num_limbs, f),
+            Some(_) => ENTIRE,
+            None => self.unbounded(ENTIRE),
+        }
+    }
+
+    /// The first and last integers `k` where `(k + shift) * π` could be in the interval, so there are none if the first is bigger. Returns None for intervals with infinite or indeterminate ends.
+    fn turns(&self, num_limbs: usize, shift: &CASNum) -> Option<(CASNum, CASNum)> {
+        if !self.lo.value.is_finite() || !self.hi.value.is_finite() {
+            return None;
+        }
+        let limbs = num_limbs + EXTRA_LIMBS;
+        let pi = CASInterval::enclose(limbs, CASNum::pi);
+        let shift = CASInterval::point(shift.clone());
+        let turns = |x: &CASNum| {
+            CASInterval::point(x.clone())
+                .div(&pi, limbs)
+                .sub(&shift, limbs)
+        };
+        Some((ceil(&turns(&self.lo).lo), floor(&turns(&self.hi).hi)))
+    }
+
+    /// The value of a periodic function of an interval with an infinite or indeterminate end, given the values it takes over a period.
+    fn unbounded(&self, range: CASInterval) -> Self {
+        if self.is_indeterminate() || self.lo == self.hi {
+            //the function has no limit at ∞ or -∞
+            INTERVAL_INDETERMINATE
+        } else {
+            range
+        }
+    }
+}
+
+/// Context rounding toward -∞ to `num_limbs` limbs, for lower ends.
+fn down(num_limbs: usize) -> Context {
+    Context::new(num_limbs, RoundingMode::Floor)
+}
+
+/// Context rounding toward ∞ to `num_limbs` limbs, for upper ends.
+fn up(num_limbs: usize) -> Context {
+    Context::new(num_limbs, RoundingMode::Ceiling)
+}
+
+/// `value` raised to the power `n` by repeated squaring, rounding every product the way `context` rounds the result. Negative numbers are only raised to odd powers.
+fn power_rounded(value: &CASNum, mut n: u64, context: Context) -> CASNum {
+    //every product has the same sign, so rounding them all in the same direction rounds the result in that direction
+    let negative = value.sign == Sign::Neg;
+    let mode = if negative == (context.mode == RoundingMode::Floor) {
+        RoundingMode::AwayFromZero
+    } else {
+        RoundingMode::TowardZero
+    };
+    let context = Context::new(context.num_limbs, mode);
+    let mut base = value.abs();
+    let mut power = CASNum::from(1);
+    while n > 0 {
+        if n & 1 == 1 {
+            power = power.mul_with(&base, context);
+        }
+        n >>= 1;
+        if n > 0 {
+            base = base.mul_with(&base, context);
+        }
+    }
+    if negative {
+        -power
+    } else {
+        power
+    }
+}
+
+/// The smaller of two determinate numbers.
+fn min(lhs: &CASNum, rhs: &CASNum) -> CASNum {
+    if rhs < lhs {
+        rhs.clone()
+    } else {
+        lhs.clone()
+    }
+}
+
+/// The larger of two determinate numbers.
+fn max(lhs: &CASNum, rhs: &CASNum) -> CASNum {
+    if rhs > lhs {
+        rhs.clone()
+    } else {
+        lhs.clone()
+    }
+}
+
+/// The largest integer no bigger than a finite number.
+fn floor(value: &CASNum) -> CASNum {
+    let integer_limbs = match &value.value {
+        CASValue::Finite { exp, .. } if *exp >= 0 => *exp as usize + 1,
+        _ => 0,
+    };
+    let truncated = value.clone().with_precision(integer_limbs);
+    if value.sign == Sign::Neg && truncated != *value {
+        truncated - CASNum::from(1)
+    } else {
+        truncated
+    }
+}
+
+/// The smallest integer no smaller than a finite number.
+fn ceil(value: &CASNum) -> CASNum {
+    -floor(&-value.clone())
+}
+
+impl From<CASNum> for CASInterval {
+    fn from(value: CASNum) -> Self {
+        CASInterval::point(value)
+    }
+}
+
+impl Neg for CASInterval {
+    type Output = CASInterval;
+
+    fn neg(self) -> Self::Output {
+        CASInterval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Display for CASInterval {
+    /// Writes the interval like `[1.5, 2]`, with both ends written with the precision if there is one.
+    ///
+    /// The lower end is rounded down and the upper end up, so the interval written holds the interval.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = |end: &CASNum| f.precision().unwrap_or_else(|| end.display_digits());
+        let lo = self.lo.round_decimal(digits(&self.lo), RoundingMode::Floor);
+        let hi = self
+            .hi
+            .round_decimal(digits(&self.hi), RoundingMode::Ceiling);
+        match f.precision() {
+            Some(precision) => write!(f, "[{:.*}, {:.*}]", precision, lo, precision, hi),
+            None => write!(f, "[{}, {}]", lo, hi),
+        }
+    }
+}
@@ -1,231 +1,250 @@
-//used for parsing string literals to CASNums
+//used for parsing string literals to numbers
 
 //parses numerical literals
-//0.02, -1,304,774, 1.264e-13
+//0.02, 1_304_774, 1.264e-13, 0xFF, 0b1010, 0o17
 
 use std::iter::{Enumerate, Peekable};
 
-use crate::types::{
-    cas_error::{CASError, CASErrorKind},
-    cas_num::Sign,
-};
+use crate::types::cas_error::{CASError, CASErrorKind};
 
-use super::CASNum;
+use super::number::INEXACT_LIMBS;
+use super::{CASInterval, CASNum, CASRational, Context, Number, RoundingMode, Sign};
 
-pub(super) fn parse_lit(
+/// Largest power of 10 a literal can be scaled by, like the 400 in `1e400` or the -3 in `0.001`. Literals with larger exponents are malformed, so the exponents of their limbs stay far from overflowing as they're used.
+const MAX_DECIMAL_EXP: usize = 1_000_000_000_000;
+
+/// Most guard limbs the power of 10 in a literal is found to, see [from_decimal].
+const MAX_GUARD_LIMBS: usize = 64;
+
+/// Parses a numeric literal starting with `first_char`, leaving `line_pos` at its last character.
+///
+/// Integer literals, including ones with a `0x`, `0b` or `0o` prefix, give exact numbers of any size. Literals with a decimal point or an exponent give inexact numbers, rounded to the nearest from their exact value with at least as many limbs as their digits need, and their power of 10 can't be more than [MAX_DECIMAL_EXP] from 0. Digits can be grouped with underscores, like `1_000_000`.
+///
+/// Returns None if `first_char` can't start a number, and an error if the literal is malformed.
+pub(crate) fn parse_lit(
     first_char: char,
     iter: &mut Peekable<Enumerate<std::str::Chars>>,
     line_pos: &mut usize,
-) -> Option<Result<CASNum, CASError>>
-//if number returns Some(Ok(CASNum), if not returns None, if error returns Some(Err)
-{
-    //we don't match unary minus or plus
-
-    let mut num = CASNum::from(0);
+) -> Option<Result<Number, CASError>> {
+    //we don't match unary minus or plus, they're operators
+    if !first_char.is_ascii_digit() && first_char != '.' {
+        return None;
+    }
 
-    let mut int: String = if first_char.is_ascii_digit() {
-        first_char.to_string()
-    } else {
-        "".to_string()
-    }; //digits before decimal pt
-    let mut frac: String = String::from(""); //digits after decimal pt
-    let mut exp: String = String::from(""); //digits after e
     let mut lit: String = first_char.to_string(); //literal of full number to return on error
-    let sign: Sign;
+    let mut int: String = String::new(); //digits before decimal pt
+    let mut frac: String = String::new(); //digits after decimal pt
+    let mut exp: String = String::new(); //digits after e, with their sign
 
-    let mut in_frac: bool = false; //if true and exp is false, we are in the fractional part
+    let mut in_frac: bool = first_char == '.'; //if true and exp is false, we are in the fractional part
     let mut in_exp: bool = false; //if true we are after e in the exponent
-
-    if first_char == '-' {
-        sign = Sign::Neg;
-    } else if first_char == '.' {
-        in_frac = true;
-        sign = Sign::Pos;
-    } else if first_char == '+' || first_char.is_ascii_digit() {
-        sign = Sign::Pos;
-    } else {
-        return None; //not number
+    let mut radix = 10;
+
+    if first_char == '0' {
+        radix = match iter.peek() {
+            Some((_, 'x' | 'X')) => 16,
+            Some((_, 'b' | 'B')) => 2,
+            Some((_, 'o' | 'O')) => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            consume(iter, &mut lit, line_pos);
+        } else {
+            int.push(first_char);
+        }
+    } else if first_char.is_ascii_digit() {
+        int.push(first_char);
     }
 
-    while let Some(&(char_pos, chr)) = iter.peek() {
-        iter.next();
-        lit.push(chr);
-        *line_pos = char_pos;
-
+    while let Some(&(_, chr)) = iter.peek() {
+        let digits = if in_exp {
+            &mut exp
+        } else if in_frac {
+            &mut frac
+        } else {
+            &mut int
+        };
         match chr {
-            chr if chr.is_ascii_digit() => {
-                if in_exp {
-                    exp.push(chr);
-                } else if in_frac {
-                    frac.push(chr);
-                } else {
-                    int.push(chr);
+            chr if chr.is_digit(radix) => digits.push(chr),
+            '_' => {
+                //separators only go between digits
+                let after_digit = lit.ends_with(|chr: char| chr.is_digit(radix));
+                if !after_digit || !next_is_digit(iter, radix) {
+                    return Some(malformed(lit, iter, line_pos));
                 }
             }
-            '.' => {
-                if in_frac {
-                    return Some(Err(CASError {
-                        line_pos: *line_pos,
-                        kind: CASErrorKind::MalformedNumericLiteral { lit },
-                    }));
-                } else {
-                    in_frac = true;
+            '.' if radix == 10 => {
+                if in_frac || in_exp {
+                    return Some(malformed(lit, iter, line_pos));
                 }
+                in_frac = true;
             }
-            'e' => {
-                if in_exp {
-                    return Some(Err(CASError {
-                        line_pos: *line_pos,
-                        kind: CASErrorKind::MalformedNumericLiteral { lit },
-                    }));
-                } else {
-                    in_exp = true;
-                    match iter.peek() {
-                        Some(&(_, '-')) => {
-                            exp.push('-');
-                            iter.next();
-                        }
-                        Some(&(_, '+')) => {
-                            iter.next();
-                        }
-                        None => break,
-                        _ => {}
+            'e' | 'E' if radix == 10 && !in_exp => {
+                //an e not followed by an exponent is the next token, like in 2e for 2 * e
+                let mut ahead = iter.clone();
+                ahead.next();
+                let sign = ahead
+                    .peek()
+                    .map(|(_, chr)| *chr)
+                    .filter(|chr| *chr == '-' || *chr == '+');
+                if sign.is_some() {
+                    ahead.next();
+                }
+                if !matches!(ahead.peek(), Some((_, chr)) if chr.is_ascii_digit()) {
+                    break;
+                }
+                in_exp = true;
+                if let Some(sign) = sign {
+                    //the e is consumed here and the sign below
+                    consume(iter, &mut lit, line_pos);
+                    if sign == '-' {
+                        exp.push('-');
                     }
                 }
             }
-            '-' => {
-                return Some(Err(CASError {
-                    line_pos: *line_pos,
-                    kind: CASErrorKind::MalformedNumericLiteral { lit },
-                }));
-            }
-            ',' | '_' => {
-                //separators
-                continue;
+            //digits too large for the radix, like 0b12
+            chr if radix != 10 && chr.is_ascii_alphanumeric() => {
+                return Some(malformed(lit, iter, line_pos));
             }
-
             _ => break,
         }
+        consume(iter, &mut lit, line_pos);
     }
 
-    if in_exp && exp.is_empty() {
-        return Some(Err(CASError {
-            line_pos: *line_pos,
-            kind: CASErrorKind::MalformedNumericLiteral { lit },
-        }));
-        //we just randomly have an e at the end of our number
+    if int.is_empty() && frac.is_empty() {
+        //a lone decimal point, or a prefix with no digits after it
+        return Some(malformed(lit, iter, line_pos));
     }
 
-    let mut chunks = vec![];
-    let mut current = int.as_str();
-    while current.len() >= 19 {
-        let (rest, chunk) = current.split_at(current.len() - 19);
-        chunks.push(chunk);
-        current = rest;
+    let significand = integer(&(int + &frac), radix);
+    if !in_frac && !in_exp {
+        return Some(Ok(Number::Exact(significand)));
     }
-    chunks.push(current);
-
-    //splits int into chunks of 19 digits or less, with remainder at front
-    //a 19 digit number is smaller than the largest CASNum, so it will never overflow
-
-    let mut power = CASNum::from(1);
-    let factor = CASNum::from(10000000000000000000_u64);
-
-    for chunk in chunks.iter() {
-        let chunk_val = chunk.parse::<u64>();
-        match chunk_val {
-            Err(_) => {
-                return Some(Err(CASError {
-                    line_pos: *line_pos,
-                    kind: CASErrorKind::MalformedNumericLiteral { lit },
-                }));
-            }
-            Ok(value) => {
-                let mut chunk_num = CASNum::from(value);
-                chunk_num *= &power;
-                num += &chunk_num;
-                power *= &factor;
+
+    //the value is significand * 10 ^ (exp - frac.len())
+    let exp = if exp.is_empty() {
+        Some(0)
+    } else {
+        exp.parse::<isize>().ok()
+    };
+    let Some(exp) = exp
+        .and_then(|exp| exp.checked_sub(frac.len() as isize))
+        .filter(|exp| exp.unsigned_abs() <= MAX_DECIMAL_EXP)
+    else {
+        return Some(malformed(lit, iter, line_pos));
+    };
+    let significant_digits = significand.to_string().len();
+    Some(Ok(Number::Inexact(from_decimal(
+        significand,
+        exp,
+        significant_digits,
+        RoundingMode::HalfEven,
+    ))))
+}
+
+/// Rounds `significand * 10 ^ exp` with `mode`, to at least as many limbs as `significant_digits` decimal digits need.
+///
+/// The power of 10 is found to a few more limbs than that with its ends rounded outward, and with more limbs until the value rounds the same way from both ends, so a literal like `1e1000000` doesn't need the power's million digits. The power is exact once it has enough limbs, which exponents up to about 1200 reach within [MAX_GUARD_LIMBS] guard limbs. Past that, ends that still round apart are a limb apart, and the one on the side `mode` rounds towards is returned, or the lower one when rounding to the nearest.
+pub(super) fn from_decimal(
+    significand: CASRational,
+    exp: isize,
+    significant_digits: usize,
+    mode: RoundingMode,
+) -> CASNum {
+    //each 64 bit limb holds a little over 19 decimal digits
+    let num_limbs = INEXACT_LIMBS.max(significant_digits * 1000 / 19266 + 2);
+    let context = Context::new(num_limbs, mode);
+    //integers are converted exactly whatever the number of limbs
+    let significand = significand.to_cas_num(1);
+    let ten = CASInterval::point(CASNum::from(10));
+    let power = CASInterval::point(CASNum::from(exp.unsigned_abs() as u64));
+
+    let mut guard_limbs = 1;
+    loop {
+        let bounds = ten.pow(&power, num_limbs + guard_limbs);
+        //rounding once from the exact product or quotient is monotonic, so the value rounds to anything both ends round to
+        let [lo, hi] = [&bounds.lo, &bounds.hi].map(|power| {
+            if exp >= 0 {
+                significand.mul_with(power, context)
+            } else {
+                significand.div_with(power, context)
             }
+        });
+        if lo == hi {
+            return lo;
         }
+        if guard_limbs >= MAX_GUARD_LIMBS {
+            let (lower, upper) = if lo < hi { (lo, hi) } else { (hi, lo) };
+            let positive = significand.sign == Sign::Pos;
+            let round_up = match mode {
+                RoundingMode::Ceiling => true,
+                RoundingMode::AwayFromZero => positive,
+                RoundingMode::TowardZero => !positive,
+                RoundingMode::Floor | RoundingMode::HalfEven => false,
+            };
+            return if round_up { upper } else { lower };
+        }
+        guard_limbs *= 2;
     }
-    //if it has 19 base 10 digits or less we can ensure it is less than the max u64 size
-    //we parse each chunk as a u64, convert it to a CASNum, multiply it by a power of 10, and add it to an accumulator
+}
 
-    todo!();
+/// Moves past the next character, adding it to the literal.
+fn consume(
+    iter: &mut Peekable<Enumerate<std::str::Chars>>,
+    lit: &mut String,
+    line_pos: &mut usize,
+) {
+    if let Some((char_pos, chr)) = iter.next() {
+        lit.push(chr);
+        *line_pos = char_pos;
+    }
+}
+
+/// Checks if the character after the next one is a digit in `radix`.
+fn next_is_digit(iter: &Peekable<Enumerate<std::str::Chars>>, radix: u32) -> bool {
+    let mut ahead = iter.clone();
+    ahead.next();
+    matches!(ahead.peek(), Some((_, chr)) if chr.is_digit(radix))
 }
 
-// fn parse_number(
-//     next_char: char,
-//     iter: &mut Peekable<Enumerate<str::Chars>>,
-//     line_pos: &mut usize,
-// ) -> Option<Result<Token, CASError>> {
-//     //parses numerical literals like 3.4, 1234, -1523
-
-//     //minus 1 since peek is following char
-
-//     if next_char.is_numeric() || next_char == '.' {
-//         let token_type = match get_next_number(next_char, iter, line_pos) {
-//             //check if its a float, int, or something that cant be either
-//             Ok(Token {
-//                 token_type: Float(float),
-//                 ..
-//             }) => Float(float),
-//             Ok(Token {
-//                 token_type: Int(int),
-//                 ..
-//             }) => Int(int),
-
-//             Err(lit) => {
-//                 return Some(Err(CASError {
-//                     line_pos: *line_pos,
-//                     kind: CASErrorKind::MalformedNumericLiteral { lit },
-//                 }))
-//             }
-//             _ => {
-//                 return Some(Err(CASError {
-//                     line_pos: *line_pos,
-//                     kind: CASErrorKind::SyntaxError,
-//                 }))
-//             }
-//         };
-//         return Some(Ok(Token {
-//             token_type,
-//             line_pos: *line_pos,
-//         }));
-//     }
-//     None
-// }
-
-// fn get_next_number(
-//     chr: char,
-//     iter: &mut Peekable<Enumerate<str::Chars>>,
-//     line_pos: &mut usize,
-// ) -> Result<Token, String> {
-//     let mut num: String = chr.to_string();
-
-//     while let Some(&(_, chr)) = iter.peek() {
-//         if !chr.is_numeric() && chr != '.' {
-//             break;
-//         }
-//         num.push(chr);
-//         iter.next();
-//         *line_pos += 1;
-//     }
-//     let int_parse = num.parse::<i64>();
-//     if let Ok(int) = int_parse {
-//         return Ok(Token {
-//             token_type: Int(int.into()),
-//             line_pos: *line_pos,
-//         });
-//     }
-//     let float_parse = num.parse::<f64>();
-//     match float_parse {
-//         Ok(float) => {
-//             return Ok(Token {
-//                 token_type: Float(float),
-//                 line_pos: *line_pos,
-//             })
-//         }
-//         Err(_) => return Err(num),
-//     }
-// }
+/// Consumes the rest of a malformed literal, so the error shows all of it.
+fn malformed(
+    mut lit: String,
+    iter: &mut Peekable<Enumerate<std::str::Chars>>,
+    line_pos: &mut usize,
+) -> Result<Number, CASError> {
+    while let Some(&(char_pos, chr)) = iter.peek() {
+        if !chr.is_ascii_alphanumeric() && chr != '.' && chr != '_' {
+            break;
+        }
+        iter.next();
+        lit.push(chr);
+        *line_pos = char_pos;
+    }
+    Err(CASError {
+        line_pos: *line_pos,
+        kind: CASErrorKind::MalformedNumericLiteral { lit },
+    })
+}
+
+/// The value of a string of digits in `radix`, which is 2, 8, 10 or 16.
+pub(super) fn integer(digits: &str, radix: u32) -> CASRational {
+    //digits are parsed in chunks small enough to fit in a u64
+    let chunk_len = match radix {
+        2 => 63,
+        8 => 21,
+        10 => 19,
+        16 => 15,
+        _ => unreachable!("unsupported radix {}", radix),
+    };
+    let mut value = CASRational::from(0);
+    let mut rest = digits;
+    while !rest.is_empty() {
+        let (chunk, after) = rest.split_at(chunk_len.min(rest.len()));
+        let shift = (radix as u64).pow(chunk.len() as u32);
+        value = value * CASRational::from(shift)
+            + CASRational::from(u64::from_str_radix(chunk, radix).unwrap());
+        rest = after;
+    }
+    value
+}
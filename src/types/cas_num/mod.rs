@@ -9,12 +9,42 @@ type DigitType = u64;
 const NUM_BITS: i128 = 64;
 
 mod comp;
+/// Complex numbers and their elementary functions.
+mod complex;
+/// Constants like π and e, cached by precision.
+mod constants;
 mod conversion;
+/// Writing numbers in base 10.
+mod decimal;
+/// Division of limbs, by long division or multiplying by a reciprocal.
+mod division;
+/// Elementary functions like sqrt, ln and sin.
+mod elementary;
+/// Number theory on integers, like gcd, factorials and primality.
+mod integer;
+/// Intervals of real numbers, with arithmetic rounded outward.
+mod interval;
 mod iter;
 mod literal;
+/// Multiplication of limbs, by the fastest algorithm for their size.
+mod multiplication;
+/// Numbers in expressions, which can be exact or inexact.
+mod number;
 mod operators;
+/// Exact rational numbers.
+mod rational;
+/// Rounding to a number of limbs.
+mod rounding;
 mod test;
 
+pub(crate) use complex::CASComplex;
+pub(crate) use decimal::Notation;
+pub(crate) use interval::CASInterval;
+pub(crate) use literal::parse_lit;
+pub(crate) use number::Number;
+pub(crate) use rational::CASRational;
+pub(crate) use rounding::{Context, RoundingMode};
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Copy)]
 enum Sign {
     Pos,
@@ -40,17 +70,17 @@ pub(crate) enum CASValue {
     Indeterminate,
 }
 
-const INFINITY: CASNum = CASNum {
+pub(crate) const INFINITY: CASNum = CASNum {
     value: CASValue::Infinite,
     sign: Sign::Pos,
 };
 
-const NEG_INFINITY: CASNum = CASNum {
+pub(crate) const NEG_INFINITY: CASNum = CASNum {
     value: CASValue::Infinite,
     sign: Sign::Neg,
 };
 
-const INDETERMINATE: CASNum = CASNum {
+pub(crate) const INDETERMINATE: CASNum = CASNum {
     value: CASValue::Indeterminate,
     sign: Sign::Pos,
 };
@@ -71,7 +101,7 @@ impl CASNum {
         i.into()
     }
     //only put functions in here instead of CASValue if they interact with sign
-    fn abs(&self) -> Self {
+    pub(crate) fn abs(&self) -> Self {
         CASNum {
             sign: Sign::Pos,
             value: self.value.clone(),
@@ -82,6 +112,12 @@ impl CASNum {
         self.value.is_zero()
     }
 
+    /// Truncates the number to its `num_limbs` most significant limbs.
+    pub(crate) fn with_precision(mut self, num_limbs: usize) -> Self {
+        self.value.set_precision(num_limbs);
+        self
+    }
+
     fn compare_finite(&self, other: &CASNum) -> Ordering {
         match (self.value.is_zero(), other.value.is_zero()) {
             (true, true) => return Equal, //0 == 0
@@ -309,109 +345,26 @@ impl CASValue {
         Some(out)
     }
 
-    fn cartesian(&self, other: &Self) -> Option<VecDeque<VecDeque<(DigitType, DigitType, isize)>>> {
-        //aligned cartesian product of base 256 digits
-        //base 10 example
-
-        //120.45, 4.067 ->
-        // (1, 4,  2) (2, 4,  1) . (4, 4, -1) (5, 4, -2)
-        //     .      .      .        .      .
-        // (1, 6,  0) (2, 6, -1)  . (4, 6, -3) (5, 6, -4)
-        // (1, 7, -1) (2, 7, -2)  . (4, 7, -4) (5, 7, -5)
-
-        if let CASValue::Finite {
-            digits: self_digits,
-            exp: self_exp,
-        } = self
-        {
-            if let CASValue::Finite {
-                digits: other_digits,
-                exp: other_exp,
-            } = other
-            {
-                let self_max_exp: isize = *self_exp;
-                //exponent of max digit of self_digits
-                let self_min_exp = self_max_exp - (self_digits.len() - 1) as isize;
-                //exponent of min digit of self_digits
-
-                let other_max_exp = *other_exp;
-                //exponent of max digit of other_digits
-                let other_min_exp = other_max_exp - (other_digits.len() - 1) as isize;
-
-                let mut out: VecDeque<VecDeque<(DigitType, DigitType, isize)>> = VecDeque::new();
-                for i in self_min_exp..=self_max_exp {
-                    let self_num = self_digits[(i - self_min_exp).try_into().unwrap()];
-
-                    if self_num == 0 {
-                        //0s don't contribute to multiplication
-                        continue;
-                    }
-
-                    let mut row: VecDeque<(DigitType, DigitType, isize)> = VecDeque::new();
-
-                    for j in other_min_exp..=other_max_exp {
-                        let other_num = other_digits[(j - other_min_exp).try_into().unwrap()];
-
-                        if other_num == 0 {
-                            continue;
-                        }
-                        row.push_back((self_num, other_num, i + j));
-                    }
-                    out.push_back(row);
-                }
-                return Some(out);
-            }
+    fn num_digits(&self) -> usize {
+        match self {
+            CASValue::Finite { digits, .. } => digits.len(),
+            CASValue::Infinite | CASValue::Indeterminate => 0,
         }
-
-        None
     }
 
     fn set_precision(&mut self, num_digits: usize) {
         if let CASValue::Finite { digits, exp } = self {
             while digits.len() > num_digits {
                 digits.pop_front();
-                *exp += 1;
+                //exp is the exponent of the most significant digit, so it doesn't change
             }
-        }
-    }
-}
-
-use std::fmt::Display;
-
-impl Display for CASNum {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let float: f64 = <CASNum as Clone>::clone(self).into();
-        //TODO: get rid of this clone
-        match self {
-            CASNum {
-                value: CASValue::Finite { digits, exp },
-                sign,
-            } => {
-                let mut hex_str: String = String::new();
-                for digit in digits.iter().rev() {
-                    hex_str += &format!("{:0>2x}", digit);
-                }
-                write!(
-                    f,
-                    "0x{}{} x (2^64) ^ {} ({:e})",
-                    if *sign == Sign::Pos { "" } else { "-" },
-                    hex_str,
-                    exp,
-                    float,
-                )
+            while digits.front() == Some(&0) {
+                //dropping digits can leave trailing 0s
+                digits.pop_front();
+            }
+            if digits.is_empty() {
+                *exp = 0;
             }
-            CASNum {
-                value: CASValue::Infinite,
-                sign: Sign::Pos,
-            } => write!(f, "∞"),
-            CASNum {
-                value: CASValue::Infinite,
-                sign: Sign::Neg,
-            } => write!(f, "-∞"),
-            CASNum {
-                value: CASValue::Indeterminate,
-                ..
-            } => write!(f, "NaN"),
         }
     }
 }
@@ -0,0 +1,289 @@
+//multiplication of unsigned integers stored as little endian limbs
+//products are added into an output buffer in place, using schoolbook multiplication for small operands, Karatsuba's algorithm for larger ones and a number theoretic transform for the largest
+
+use super::rational::add;
+use super::DigitType;
+
+/// Operands where the shorter one has fewer limbs than this are multiplied by schoolbook multiplication.
+const KARATSUBA_THRESHOLD: usize = 64;
+
+/// Operands where the shorter one has at least this many limbs are multiplied with a number theoretic transform.
+const NTT_THRESHOLD: usize = 4096;
+
+/// The prime 2 ^ 64 - 2 ^ 32 + 1 the number theoretic transform works modulo, which has roots of unity of every power of 2 up to 2 ^ 32.
+const NTT_PRIME: u64 = 0xFFFF_FFFF_0000_0001;
+
+/// 2 ^ 64 - [NTT_PRIME], which 2 ^ 64 is congruent to.
+const NTT_EPSILON: u64 = 0xFFFF_FFFF;
+
+/// A generator of the multiplicative group modulo [NTT_PRIME].
+const NTT_GENERATOR: u64 = 7;
+
+/// Bits of each coefficient limbs are split into for the number theoretic transform, small enough that no coefficient of the product reaches [NTT_PRIME].
+const NTT_PIECE_BITS: u32 = 16;
+
+/// Mask of the low [NTT_PIECE_BITS] bits of a limb.
+const PIECE_MASK: DigitType = (1 << NTT_PIECE_BITS) - 1;
+
+/// Multiplies two unsigned integers, giving exactly `lhs.len() + rhs.len()` limbs, the most significant of which can be zero.
+pub(super) fn multiply(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    let mut product = vec![0; lhs.len() + rhs.len()];
+    multiply_into(&mut product, lhs, rhs);
+    product
+}
+
+/// Adds `lhs * rhs` to `out`, picking the algorithm by the size of the shorter operand.
+///
+/// `out` must be long enough to hold the sum.
+fn multiply_into(out: &mut [DigitType], lhs: &[DigitType], rhs: &[DigitType]) {
+    let shorter = lhs.len().min(rhs.len());
+    if shorter < KARATSUBA_THRESHOLD {
+        schoolbook_into(out, lhs, rhs);
+    } else if shorter < NTT_THRESHOLD {
+        karatsuba_into(out, lhs, rhs);
+    } else {
+        ntt_into(out, lhs, rhs);
+    }
+}
+
+/// Adds `lhs * rhs` to `out` one row of partial products at a time.
+pub(super) fn schoolbook_into(out: &mut [DigitType], lhs: &[DigitType], rhs: &[DigitType]) {
+    for (lhs_idx, lhs_digit) in lhs.iter().enumerate() {
+        if *lhs_digit == 0 {
+            continue;
+        }
+        let mut carry: u128 = 0;
+        for (rhs_idx, rhs_digit) in rhs.iter().enumerate() {
+            //at most (2 ^ 64 - 1) ^ 2 + 2 * (2 ^ 64 - 1), which is exactly u128::MAX
+            let total =
+                *lhs_digit as u128 * *rhs_digit as u128 + out[lhs_idx + rhs_idx] as u128 + carry;
+            out[lhs_idx + rhs_idx] = total as DigitType;
+            carry = total >> 64;
+        }
+        add_into(&mut out[lhs_idx + rhs.len()..], &[carry as DigitType]);
+    }
+}
+
+/// Adds `lhs * rhs` to `out` by Karatsuba's algorithm, which replaces four half size products with three.
+fn karatsuba_into(out: &mut [DigitType], lhs: &[DigitType], rhs: &[DigitType]) {
+    let (longer, shorter) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    if longer.len() >= 2 * shorter.len() {
+        //split the longer operand into pieces the size of the shorter one, so the halves below are balanced
+        for (idx, piece) in longer.chunks(shorter.len()).enumerate() {
+            multiply_into(&mut out[idx * shorter.len()..], piece, shorter);
+        }
+        return;
+    }
+
+    // (a1 * B + a0) * (b1 * B + b0) = a1 * b1 * B ^ 2 + ((a0 + a1) * (b0 + b1) - a0 * b0 - a1 * b1) * B + a0 * b0
+    let half = longer.len() / 2;
+    let (longer_low, longer_high) = longer.split_at(half);
+    let (shorter_low, shorter_high) = shorter.split_at(half);
+
+    let low = multiply(longer_low, shorter_low);
+    let high = multiply(longer_high, shorter_high);
+    let mut middle = multiply(
+        &add(longer_low, longer_high),
+        &add(shorter_low, shorter_high),
+    );
+    sub_in_place(&mut middle, &low);
+    sub_in_place(&mut middle, &high);
+
+    add_into(out, &low);
+    add_into(&mut out[half..], &middle);
+    add_into(&mut out[2 * half..], &high);
+}
+
+/// Adds `lhs * rhs` to `out` by multiplying their coefficients pointwise after a number theoretic transform, which takes O(n log n) steps.
+fn ntt_into(out: &mut [DigitType], lhs: &[DigitType], rhs: &[DigitType]) {
+    let mut lhs = split_pieces(lhs);
+    let mut rhs = split_pieces(rhs);
+    let size = (lhs.len() + rhs.len()).next_power_of_two();
+    lhs.resize(size, 0);
+    rhs.resize(size, 0);
+
+    ntt(&mut lhs, false);
+    ntt(&mut rhs, false);
+    for (lhs, rhs) in lhs.iter_mut().zip(&rhs) {
+        *lhs = mul_mod(*lhs, *rhs);
+    }
+    ntt(&mut lhs, true);
+
+    //every coefficient is smaller than the prime, so carrying them into pieces gives the product exactly
+    let mut pieces = Vec::with_capacity(size + 4);
+    let mut carry: u128 = 0;
+    for coefficient in lhs {
+        carry += coefficient as u128;
+        pieces.push(carry as DigitType & PIECE_MASK);
+        carry >>= NTT_PIECE_BITS;
+    }
+    while carry > 0 {
+        pieces.push(carry as DigitType & PIECE_MASK);
+        carry >>= NTT_PIECE_BITS;
+    }
+    add_into(out, &join_pieces(&pieces));
+}
+
+/// Splits limbs into little endian pieces of [NTT_PIECE_BITS] bits.
+fn split_pieces(limbs: &[DigitType]) -> Vec<u64> {
+    let pieces_per_limb = DigitType::BITS / NTT_PIECE_BITS;
+    limbs
+        .iter()
+        .flat_map(|limb| {
+            (0..pieces_per_limb).map(move |idx| (limb >> (idx * NTT_PIECE_BITS)) & PIECE_MASK)
+        })
+        .collect()
+}
+
+/// Joins little endian pieces of [NTT_PIECE_BITS] bits back into limbs.
+fn join_pieces(pieces: &[u64]) -> Vec<DigitType> {
+    let pieces_per_limb = (DigitType::BITS / NTT_PIECE_BITS) as usize;
+    pieces
+        .chunks(pieces_per_limb)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0, |limb, (idx, piece)| {
+                limb | piece << (idx as u32 * NTT_PIECE_BITS)
+            })
+        })
+        .collect()
+}
+
+/// Transforms coefficients in place into their values at the powers of a root of unity, or back again if `inverse` is true.
+///
+/// The number of coefficients must be a power of 2.
+fn ntt(values: &mut [u64], inverse: bool) {
+    let size = values.len();
+
+    //bit reversal permutation, so the butterflies below can work in place
+    let mut reversed = 0;
+    for idx in 1..size {
+        let mut bit = size >> 1;
+        while reversed & bit != 0 {
+            reversed ^= bit;
+            bit >>= 1;
+        }
+        reversed |= bit;
+        if idx < reversed {
+            values.swap(idx, reversed);
+        }
+    }
+
+    let mut len = 2;
+    while len <= size {
+        let root = pow_mod(NTT_GENERATOR, (NTT_PRIME - 1) / len as u64);
+        let root = if inverse {
+            pow_mod(root, NTT_PRIME - 2)
+        } else {
+            root
+        };
+        for start in (0..size).step_by(len) {
+            let mut power = 1;
+            for idx in start..start + len / 2 {
+                let even = values[idx];
+                let odd = mul_mod(values[idx + len / 2], power);
+                values[idx] = add_mod(even, odd);
+                values[idx + len / 2] = add_mod(even, NTT_PRIME - odd);
+                power = mul_mod(power, root);
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        let size_inverse = pow_mod(size as u64, NTT_PRIME - 2);
+        for value in values.iter_mut() {
+            *value = mul_mod(*value, size_inverse);
+        }
+    }
+}
+
+/// (lhs + rhs) mod [NTT_PRIME], for values already reduced.
+fn add_mod(lhs: u64, rhs: u64) -> u64 {
+    let (sum, overflow) = lhs.overflowing_add(rhs);
+    if overflow || sum >= NTT_PRIME {
+        sum.wrapping_sub(NTT_PRIME)
+    } else {
+        sum
+    }
+}
+
+/// (lhs * rhs) mod [NTT_PRIME].
+fn mul_mod(lhs: u64, rhs: u64) -> u64 {
+    //2 ^ 64 = 2 ^ 32 - 1 and 2 ^ 96 = -1 modulo the prime, so the high half of the product can be folded into the low half without dividing
+    let product = lhs as u128 * rhs as u128;
+    let (low, high) = (product as u64, (product >> 64) as u64);
+    let (high_high, high_low) = (high >> 32, high & NTT_EPSILON);
+
+    let (mut sum, borrow) = low.overflowing_sub(high_high);
+    if borrow {
+        sum = sum.wrapping_sub(NTT_EPSILON);
+    }
+    let (sum, carry) = sum.overflowing_add(high_low * NTT_EPSILON);
+    let sum = if carry {
+        sum.wrapping_add(NTT_EPSILON)
+    } else {
+        sum
+    };
+    if sum >= NTT_PRIME {
+        sum - NTT_PRIME
+    } else {
+        sum
+    }
+}
+
+/// base ^ exp mod [NTT_PRIME].
+fn pow_mod(mut base: u64, mut exp: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base);
+        }
+        base = mul_mod(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Adds `value` to `out` in place, carrying as far as needed.
+///
+/// Limbs of `value` past the end of `out` must be zero, and the sum must fit in `out`.
+fn add_into(out: &mut [DigitType], value: &[DigitType]) {
+    let mut carry = false;
+    for (idx, digit) in value.iter().enumerate() {
+        if idx >= out.len() {
+            debug_assert!(*digit == 0 && !carry, "sum doesn't fit in the output");
+            return;
+        }
+        let (sum, overflow_digit) = out[idx].overflowing_add(*digit);
+        let (sum, overflow_carry) = sum.overflowing_add(carry as DigitType);
+        out[idx] = sum;
+        carry = overflow_digit || overflow_carry;
+    }
+    for limb in out.iter_mut().skip(value.len()) {
+        if !carry {
+            break;
+        }
+        (*limb, carry) = limb.overflowing_add(1);
+    }
+    debug_assert!(!carry, "sum doesn't fit in the output");
+}
+
+/// Subtracts `value` from `out` in place, where `out` is at least `value`.
+fn sub_in_place(out: &mut [DigitType], value: &[DigitType]) {
+    let mut borrow = false;
+    for (idx, limb) in out.iter_mut().enumerate() {
+        let digit = value.get(idx).copied().unwrap_or(0);
+        if idx >= value.len() && !borrow {
+            break;
+        }
+        let (difference, borrow_digit) = limb.overflowing_sub(digit);
+        let (difference, borrow_carry) = difference.overflowing_sub(borrow as DigitType);
+        *limb = difference;
+        borrow = borrow_digit || borrow_carry;
+    }
+    debug_assert!(!borrow, "subtracted a larger number");
+}
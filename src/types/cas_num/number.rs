@@ -0,0 +1,213 @@
+//numbers in expressions, which stay exact rationals until they're combined with an inexact CASNum
+//integer literals and fractions of them are exact, decimal literals and numeric results like calc's are inexact
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub};
+
+use super::{CASNum, CASRational, Context};
+
+/// Limbs an exact number is rounded to when it's combined with an inexact one, enough for the digits `calc` gives by default.
+pub(super) const INEXACT_LIMBS: usize = 3;
+
+/// The value of a numeric literal, either an exact [CASRational] or an inexact [CASNum].
+///
+/// Arithmetic on two exact numbers is exact, anything else gives an inexact number. Numbers compare by value whether or not they're exact, so `1` and `1.0` are equal.
+#[derive(Clone, Debug)]
+pub(crate) enum Number {
+    /// An exact rational number, like `2` or `1/3`.
+    Exact(CASRational),
+    /// A binary floating point number, like `0.1`.
+    Inexact(CASNum),
+}
+
+impl Number {
+    /// Rounds the number to a [CASNum] with `num_limbs` limbs, see [CASRational::to_cas_num]. Inexact numbers are returned as they are.
+    pub(crate) fn to_cas_num(&self, num_limbs: usize) -> CASNum {
+        match self {
+            Number::Exact(value) => value.to_cas_num(num_limbs),
+            Number::Inexact(value) => value.clone(),
+        }
+    }
+
+    /// Rounds the number to a [CASNum] with the context's precision, whether or not it's exact.
+    pub(crate) fn round(&self, context: Context) -> CASNum {
+        match self {
+            Number::Exact(value) => value.round(context),
+            Number::Inexact(value) => value.clone().round(context),
+        }
+    }
+
+    /// The exact value of the number, or None if it's infinite or indeterminate.
+    pub(crate) fn exact(&self) -> Option<CASRational> {
+        match self {
+            Number::Exact(value) => Some(value.clone()),
+            Number::Inexact(value) => CASRational::try_from(value).ok(),
+        }
+    }
+
+    /// The reciprocal `1 / x`, exact if the number is, or None if the number is zero.
+    pub(crate) fn recip(&self) -> Option<Number> {
+        match self {
+            Number::Exact(value) => value.recip().map(Number::Exact),
+            Number::Inexact(value) if value.is_zero() => None,
+            Number::Inexact(value) => Some(Number::Inexact(CASNum::from(1) / value.clone())),
+        }
+    }
+
+    /// Applies an operation exactly if both numbers are exact, and to their values as [CASNum]s otherwise.
+    fn combine(
+        self,
+        rhs: Number,
+        exact: impl Fn(CASRational, CASRational) -> CASRational,
+        inexact: impl Fn(CASNum, CASNum) -> CASNum,
+    ) -> Number {
+        match (self, rhs) {
+            (Number::Exact(lhs), Number::Exact(rhs)) => Number::Exact(exact(lhs, rhs)),
+            (lhs, rhs) => Number::Inexact(inexact(
+                lhs.to_cas_num(INEXACT_LIMBS),
+                rhs.to_cas_num(INEXACT_LIMBS),
+            )),
+        }
+    }
+}
+
+impl From<CASRational> for Number {
+    fn from(value: CASRational) -> Self {
+        Number::Exact(value)
+    }
+}
+
+impl From<CASNum> for Number {
+    fn from(value: CASNum) -> Self {
+        Number::Inexact(value)
+    }
+}
+
+impl From<i32> for Number {
+    fn from(value: i32) -> Self {
+        Number::Exact(CASRational::from(value))
+    }
+}
+
+impl From<i64> for Number {
+    fn from(value: i64) -> Self {
+        Number::Exact(CASRational::from(value))
+    }
+}
+
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Number::Exact(CASRational::from(value))
+    }
+}
+
+impl From<i128> for Number {
+    fn from(value: i128) -> Self {
+        Number::Exact(CASRational::from(value))
+    }
+}
+
+impl From<f64> for Number {
+    fn from(value: f64) -> Self {
+        Number::Inexact(CASNum::from(value))
+    }
+}
+
+impl TryFrom<&Number> for i64 {
+    type Error = ();
+
+    /// Converts an integer that fits in an i64, whether or not it's exact.
+    fn try_from(value: &Number) -> Result<Self, Self::Error> {
+        i64::try_from(&value.exact().ok_or(())?)
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.exact(), other.exact()) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => self.to_cas_num(INEXACT_LIMBS) == other.to_cas_num(INEXACT_LIMBS),
+        }
+    }
+}
+
+impl Eq for Number {}
+
+impl Hash for Number {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        //equal numbers have the same exact value, so they hash the same whether or not they're exact
+        match self.exact() {
+            Some(value) => value.hash(state),
+            None => self.to_cas_num(INEXACT_LIMBS).hash(state),
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.exact(), other.exact()) {
+            (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+            _ => self
+                .to_cas_num(INEXACT_LIMBS)
+                .partial_cmp(&other.to_cas_num(INEXACT_LIMBS)),
+        }
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Exact(value) => write!(f, "{}", value),
+            Number::Inexact(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Number::Exact(value) => Number::Exact(-value),
+            Number::Inexact(value) => Number::Inexact(-value),
+        }
+    }
+}
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.combine(rhs, |lhs, rhs| lhs + rhs, |lhs, rhs| lhs + rhs)
+    }
+}
+
+impl Sub for Number {
+    type Output = Number;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.combine(rhs, |lhs, rhs| lhs - rhs, |lhs, rhs| lhs - rhs)
+    }
+}
+
+impl Mul for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.combine(rhs, |lhs, rhs| lhs * rhs, |lhs, rhs| lhs * rhs)
+    }
+}
+
+impl AddAssign<&Number> for Number {
+    fn add_assign(&mut self, rhs: &Number) {
+        *self = self.clone() + rhs.clone();
+    }
+}
+
+impl MulAssign<&Number> for Number {
+    fn mul_assign(&mut self, rhs: &Number) {
+        *self = self.clone() * rhs.clone();
+    }
+}
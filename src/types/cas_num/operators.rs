@@ -8,7 +8,11 @@ use num_traits::Zero;
 
 use crate::types::cas_num::{DigitType, NUM_BITS};
 
-use super::{CASNum, CASValue, Sign, INDETERMINATE, INFINITY, NEG_INFINITY, ZERO};
+use super::division::div_rem;
+use super::multiplication::multiply;
+use super::{
+    CASNum, CASValue, Context, RoundingMode, Sign, INDETERMINATE, INFINITY, NEG_INFINITY, ZERO,
+};
 
 impl Neg for CASNum {
     type Output = CASNum;
@@ -98,7 +102,7 @@ fn addition_finite(lhs: &CASNum, rhs: &CASNum) -> CASNum {
 
             let alignment = &lhs.value.align(&rhs.value).unwrap(); //we can unwrap safely since both self and rhs are finite
 
-            let exp = alignment.back().unwrap().2;
+            let mut exp = alignment.back().unwrap().2;
 
             for (a_digit, b_digit, _) in alignment {
                 let mut sum: u128 = *a_digit as u128 + *b_digit as u128 + carry;
@@ -115,10 +119,11 @@ fn addition_finite(lhs: &CASNum, rhs: &CASNum) -> CASNum {
 
             if carry != 0 {
                 digits.push_back(carry as DigitType);
+                exp += 1;
             }
 
             CASNum {
-                value: CASValue::Finite { digits, exp },
+                value: CASValue::Finite { digits, exp }.normalize(),
                 sign: Sign::Pos,
             }
         }
@@ -222,7 +227,7 @@ fn subtraction_finite(lhs: &CASNum, rhs: &CASNum) -> CASNum {
             }
 
             CASNum {
-                value: CASValue::Finite { digits, exp },
+                value: CASValue::Finite { digits, exp }.normalize(),
                 sign: Sign::Pos,
             }
         }
@@ -286,43 +291,31 @@ fn multiplication_finite(lhs: &CASNum, rhs: &CASNum) -> CASNum {
         //-a * -b = a * b
     };
 
-    let bit_mask: u128 = u64::MAX.into(); //all ones for extracting lower 64 bits
-
-    let cartesian = lhs.value.cartesian(&rhs.value).unwrap();
-
-    let max_digit = cartesian.back().unwrap().back().unwrap().2;
-    let min_digit = cartesian.front().unwrap().front().unwrap().2;
-
-    assert!(max_digit >= min_digit);
-
-    let mut temp_arr: Vec<u128> = vec![0; (max_digit - min_digit + 1) as usize];
-
-    for row in cartesian {
-        for (self_digit, rhs_digit, exp) in row {
-            temp_arr[(exp - min_digit) as usize] += (self_digit as u128) * (rhs_digit as u128);
-        }
-    }
-
-    let mut carry = 0;
-    let mut digits: VecDeque<DigitType> = VecDeque::new();
-
-    for &value in temp_arr.iter() {
-        let sum = value.checked_add(carry);
-        assert!(sum.is_some());
-
-        let adjusted = sum.unwrap() & bit_mask;
-        carry = sum.unwrap() >> NUM_BITS;
-        digits.push_back(adjusted as DigitType);
+    let (
+        CASValue::Finite {
+            digits: lhs_digits,
+            exp: lhs_exp,
+        },
+        CASValue::Finite {
+            digits: rhs_digits,
+            exp: rhs_exp,
+        },
+    ) = (&lhs.value, &rhs.value)
+    else {
+        unreachable!("multiplication_finite is only called with finite numbers");
+    };
+    if lhs.is_zero() || rhs.is_zero() {
+        return ZERO;
     }
 
-    while carry > 0 {
-        digits.push_back((carry & bit_mask) as u64);
-        carry >>= NUM_BITS;
-    }
+    let lhs_digits: Vec<DigitType> = lhs_digits.iter().copied().collect();
+    let rhs_digits: Vec<DigitType> = rhs_digits.iter().copied().collect();
+    let digits: VecDeque<DigitType> = multiply(&lhs_digits, &rhs_digits).into();
 
-    let exp = (digits.len() - temp_arr.len()) as isize
-        + lhs.value.exp().unwrap()
-        + rhs.value.exp().unwrap();
+    //the least significant limb of the product has the sum of the exponents of the least significant limbs of the operands
+    let low_exp =
+        (lhs_exp - lhs_digits.len() as isize + 1) + (rhs_exp - rhs_digits.len() as isize + 1);
+    let exp = low_exp + digits.len() as isize - 1;
     let val = CASValue::Finite { digits, exp };
 
     CASNum {
@@ -331,71 +324,89 @@ fn multiplication_finite(lhs: &CASNum, rhs: &CASNum) -> CASNum {
     }
 }
 
+/// Limbs kept in a quotient beyond the number of limbs in the longer of the dividend and divisor.
+const QUOTIENT_GUARD_LIMBS: usize = 1;
+
+/// Number of limbs kept when dividing `lhs` by `rhs` without a requested precision.
+fn quotient_limbs(lhs: &CASNum, rhs: &CASNum) -> usize {
+    lhs.value.num_digits().max(rhs.value.num_digits()) + QUOTIENT_GUARD_LIMBS
+}
+
 impl DivAssign<&CASNum> for CASNum {
     fn div_assign(&mut self, rhs: &Self) {
+        *self = self.quotient(rhs, quotient_limbs(self, rhs));
+    }
+}
+
+impl CASNum {
+    /// Divides by `rhs`, keeping the `num_limbs` most significant limbs of the quotient and truncating the rest.
+    pub(crate) fn quotient(&self, rhs: &CASNum, num_limbs: usize) -> CASNum {
+        self.div_with(rhs, Context::new(num_limbs, RoundingMode::TowardZero))
+    }
+
+    /// The quotient of two numbers, rounded to the context's precision.
+    pub(crate) fn div_with(&self, rhs: &CASNum, context: Context) -> CASNum {
         if self.value.is_zero() && rhs.value.is_zero() {
-            *self = INDETERMINATE;
-            return;
+            return INDETERMINATE;
             //0/0 == NAN
         }
 
-        if self.value.is_zero() || rhs.value.is_infinite() {
-            *self = ZERO;
-            return;
-        }
-
         if self.value.is_indeterminate() || rhs.value.is_indeterminate() {
-            *self = INDETERMINATE;
-            return;
+            return INDETERMINATE;
             //NAN / x == NAN
             //x / NAN == NAN
         }
 
+        if self.value.is_zero() || (self.value.is_finite() && rhs.value.is_infinite()) {
+            return ZERO;
+        }
+
         if self.value.is_infinite() && rhs.value.is_infinite() {
-            *self = INDETERMINATE;
+            return INDETERMINATE;
             //inf / inf == nan
-            return;
         }
 
-        if self.value.is_infinite() {
-            *self = match (self.sign, rhs.sign) {
-                (Sign::Pos, Sign::Pos) => INFINITY,
-                (Sign::Pos, Sign::Neg) => NEG_INFINITY,
-                (Sign::Neg, Sign::Pos) => NEG_INFINITY,
-                (Sign::Neg, Sign::Neg) => INFINITY,
+        let same_sign = self.sign == rhs.sign;
+
+        if rhs.value.is_zero() {
+            return if self.sign == Sign::Pos {
+                INFINITY
+            } else {
+                NEG_INFINITY
             };
-            return;
+            //x / 0 == inf, the sign of 0 is ignored since 0 == -0
         }
 
-        if let (
-            CASNum {
-                value:
-                    CASValue::Finite {
-                        digits: self_digits,
-                        exp: self_exp,
-                    },
-                sign: self_sign,
-            },
-            CASNum {
-                value:
-                    CASValue::Finite {
-                        digits: rhs_digits,
-                        exp: rhs_exp,
-                    },
-                sign: rhs_sign,
-            },
-        ) = (&self, &rhs)
-        {
-            let (quot, ..) = division_finite(self_digits, self_exp, rhs_digits, rhs_exp);
-            match (self_sign, rhs_sign) {
-                (Sign::Pos, Sign::Pos) | (Sign::Neg, Sign::Neg) => {
-                    *self = quot;
-                }
-                (Sign::Pos, Sign::Neg) | (Sign::Neg, Sign::Pos) => {
-                    *self = -quot;
-                } //a / -b = - (a / b)
-                  //-a / b = -(a / b)
-            };
+        if self.value.is_infinite() {
+            return if same_sign { INFINITY } else { NEG_INFINITY };
+            //inf / x == inf
+        }
+
+        match (&self.value, &rhs.value) {
+            (
+                CASValue::Finite {
+                    digits: self_digits,
+                    exp: self_exp,
+                },
+                CASValue::Finite {
+                    digits: rhs_digits,
+                    exp: rhs_exp,
+                },
+            ) => {
+                let (quot, rem) = division_finite(
+                    self_digits,
+                    self_exp,
+                    rhs_digits,
+                    rhs_exp,
+                    context.num_limbs,
+                );
+                //a / -b = - (a / b)
+                //-a / b = -(a / b)
+                //the sign is set before rounding, since floor and ceiling round by it
+                let quot = if same_sign { quot } else { -quot };
+                quot.round_with(context, !rem.is_zero())
+            }
+            _ => unreachable!(),
         }
     }
 }
@@ -473,13 +484,38 @@ impl Rem<u64> for CASNum {
     }
 }
 
+/// Divides two positive finite numbers, returning the quotient to at least `num_limbs` limbs, without rounding, and the remainder left over.
 fn division_finite(
     lhs_digits: &VecDeque<u64>,
     lhs_exp: &isize,
     rhs_digits: &VecDeque<u64>,
     rhs_exp: &isize,
+    num_limbs: usize,
 ) -> (CASNum, CASNum) {
-    todo!();
+    //lhs = a * 2^(64 * lhs_min) and rhs = b * 2^(64 * rhs_min) where a and b are integers
+    let lhs_min = lhs_exp - (lhs_digits.len() - 1) as isize;
+    let rhs_min = rhs_exp - (rhs_digits.len() - 1) as isize;
+
+    //a is shifted left so the integer quotient has enough limbs
+    let shift = (num_limbs + rhs_digits.len()).saturating_sub(lhs_digits.len()) + 1;
+    let mut dividend: Vec<u64> = vec![0; shift];
+    dividend.extend(lhs_digits.iter());
+    let divisor: Vec<u64> = rhs_digits.iter().copied().collect();
+
+    let (quot, rem) = div_rem(&dividend, &divisor);
+
+    let quot_min = lhs_min - shift as isize - rhs_min;
+    let rem_min = lhs_min - shift as isize;
+    let finite = |digits: Vec<u64>, min: isize| CASNum {
+        value: CASValue::Finite {
+            exp: min + digits.len() as isize - 1,
+            digits: VecDeque::from(digits),
+        }
+        .normalize(),
+        sign: Sign::Pos,
+    };
+
+    (finite(quot, quot_min), finite(rem, rem_min))
 }
 
 // impl Add<&CASNum> for CASNum {
@@ -0,0 +1,350 @@
+//exact rational numbers, a numerator and denominator of arbitrary size stored in the same limbs as CASNum
+//values are always kept in lowest terms with a positive denominator, so equal values are structurally equal
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::decimal::decimal;
+use super::division::{div_rem, integer_from_limbs};
+use super::integer::isqrt;
+use super::multiplication::multiply;
+use super::{CASNum, CASValue, Context, DigitType, Sign};
+
+/// An exact rational number.
+///
+/// The numerator and denominator are unsigned integers stored as little endian limbs with no leading zero limbs, where zero has no limbs. They have no common factors, the denominator is never zero, and zero is always positive.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct CASRational {
+    /// Sign of the number, positive for zero.
+    sign: Sign,
+    /// Magnitude of the numerator.
+    numerator: Vec<DigitType>,
+    /// The denominator, at least 1.
+    denominator: Vec<DigitType>,
+}
+
+impl CASRational {
+    /// Builds `numerator / denominator` in lowest terms, or None if the denominator is zero.
+    fn new(sign: Sign, numerator: Vec<DigitType>, denominator: Vec<DigitType>) -> Option<Self> {
+        let numerator = trim(numerator);
+        let denominator = trim(denominator);
+        if denominator.is_empty() {
+            return None;
+        }
+        if numerator.is_empty() {
+            return Some(CASRational {
+                sign: Sign::Pos,
+                numerator,
+                denominator: vec![1],
+            });
+        }
+        let divisor = gcd(&numerator, &denominator);
+        Some(CASRational {
+            sign,
+            numerator: div_rem(&numerator, &divisor).0,
+            denominator: div_rem(&denominator, &divisor).0,
+        })
+    }
+
+    /// Builds an integer from its sign and magnitude.
+    fn integer(sign: Sign, magnitude: u128) -> Self {
+        CASRational::new(
+            sign,
+            vec![magnitude as DigitType, (magnitude >> 64) as DigitType],
+            vec![1],
+        )
+        .unwrap()
+    }
+
+    /// Checks if the number is zero.
+    pub(crate) fn is_zero(&self) -> bool {
+        self.numerator.is_empty()
+    }
+
+    /// Checks if the number is an integer.
+    pub(crate) fn is_integer(&self) -> bool {
+        self.denominator == [1]
+    }
+
+    /// 1 / self, or None if the number is zero.
+    pub(crate) fn recip(&self) -> Option<CASRational> {
+        CASRational::new(self.sign, self.denominator.clone(), self.numerator.clone())
+    }
+
+    /// The exact square root, or None if the number is negative or isn't the square of a rational.
+    pub(crate) fn sqrt(&self) -> Option<CASRational> {
+        if self.sign == Sign::Neg {
+            return None;
+        }
+        //in lowest terms, a square's numerator and denominator are squares themselves
+        let root = |limbs: &[DigitType]| {
+            let root = isqrt(limbs);
+            (mul(&root, &root) == limbs).then_some(root)
+        };
+        CASRational::new(Sign::Pos, root(&self.numerator)?, root(&self.denominator)?)
+    }
+
+    /// Rounds the number to a [CASNum] with `num_limbs` limbs, truncating like [CASNum::quotient]. Integers are converted exactly, whatever their size.
+    pub(crate) fn to_cas_num(&self, num_limbs: usize) -> CASNum {
+        let numerator = integer_from_limbs(self.sign, &self.numerator);
+        if self.is_integer() {
+            return numerator;
+        }
+        numerator.quotient(&integer_from_limbs(Sign::Pos, &self.denominator), num_limbs)
+    }
+
+    /// Rounds the number to a [CASNum] with the context's precision.
+    pub(crate) fn round(&self, context: Context) -> CASNum {
+        integer_from_limbs(self.sign, &self.numerator)
+            .div_with(&integer_from_limbs(Sign::Pos, &self.denominator), context)
+    }
+}
+
+impl TryFrom<&CASNum> for CASRational {
+    type Error = ();
+
+    /// Converts a finite number exactly, since every finite [CASNum] is an integer divided by a power of 2 ^ 64.
+    fn try_from(value: &CASNum) -> Result<Self, Self::Error> {
+        let CASValue::Finite { digits, exp } = &value.value else {
+            return Err(());
+        };
+        //exponent of the least significant limb
+        let low = *exp - digits.len() as isize + 1;
+        let shift = vec![0; low.unsigned_abs()];
+        let digits: Vec<DigitType> = digits.iter().copied().collect();
+        let (numerator, denominator) = if low >= 0 {
+            ([shift, digits].concat(), vec![1])
+        } else {
+            (digits, [shift, vec![1]].concat())
+        };
+        CASRational::new(value.sign, numerator, denominator).ok_or(())
+    }
+}
+
+impl From<i32> for CASRational {
+    fn from(value: i32) -> Self {
+        CASRational::from(value as i128)
+    }
+}
+
+impl From<i64> for CASRational {
+    fn from(value: i64) -> Self {
+        CASRational::from(value as i128)
+    }
+}
+
+impl From<u64> for CASRational {
+    fn from(value: u64) -> Self {
+        CASRational::integer(Sign::Pos, value as u128)
+    }
+}
+
+impl From<i128> for CASRational {
+    fn from(value: i128) -> Self {
+        let sign = if value < 0 { Sign::Neg } else { Sign::Pos };
+        CASRational::integer(sign, value.unsigned_abs())
+    }
+}
+
+impl TryFrom<&CASRational> for i64 {
+    type Error = ();
+
+    /// Converts an integer that fits in an i64.
+    fn try_from(value: &CASRational) -> Result<Self, Self::Error> {
+        if !value.is_integer() || value.numerator.len() > 1 {
+            return Err(());
+        }
+        let magnitude = value.numerator.first().copied().unwrap_or(0) as i128;
+        let signed = if value.sign == Sign::Neg {
+            -magnitude
+        } else {
+            magnitude
+        };
+        i64::try_from(signed).map_err(|_| ())
+    }
+}
+
+impl Neg for CASRational {
+    type Output = CASRational;
+
+    fn neg(mut self) -> Self::Output {
+        if !self.is_zero() {
+            self.sign = match self.sign {
+                Sign::Pos => Sign::Neg,
+                Sign::Neg => Sign::Pos,
+            };
+        }
+        self
+    }
+}
+
+impl Add for CASRational {
+    type Output = CASRational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // a / b + c / d = (a * d + c * b) / (b * d)
+        let lhs_scaled = mul(&self.numerator, &rhs.denominator);
+        let rhs_scaled = mul(&rhs.numerator, &self.denominator);
+        let denominator = mul(&self.denominator, &rhs.denominator);
+        let (sign, numerator) = if self.sign == rhs.sign {
+            (self.sign, add(&lhs_scaled, &rhs_scaled))
+        } else {
+            match cmp(&lhs_scaled, &rhs_scaled) {
+                Ordering::Less => (rhs.sign, sub(&rhs_scaled, &lhs_scaled)),
+                _ => (self.sign, sub(&lhs_scaled, &rhs_scaled)),
+            }
+        };
+        CASRational::new(sign, numerator, denominator).unwrap()
+    }
+}
+
+impl Sub for CASRational {
+    type Output = CASRational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for CASRational {
+    type Output = CASRational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let sign = if self.sign == rhs.sign {
+            Sign::Pos
+        } else {
+            Sign::Neg
+        };
+        CASRational::new(
+            sign,
+            mul(&self.numerator, &rhs.numerator),
+            mul(&self.denominator, &rhs.denominator),
+        )
+        .unwrap()
+    }
+}
+
+impl PartialOrd for CASRational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CASRational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        //the sign of zero is positive, so zero is handled by comparing magnitudes
+        match (self.sign, other.sign) {
+            (Sign::Pos, Sign::Neg) => Ordering::Greater,
+            (Sign::Neg, Sign::Pos) => Ordering::Less,
+            (sign, _) => {
+                // a / b < c / d when a * d < c * b
+                let magnitudes = cmp(
+                    &mul(&self.numerator, &other.denominator),
+                    &mul(&other.numerator, &self.denominator),
+                );
+                if sign == Sign::Pos {
+                    magnitudes
+                } else {
+                    magnitudes.reverse()
+                }
+            }
+        }
+    }
+}
+
+impl Display for CASRational {
+    /// Writes the number in base 10, as `-7` or `22/7`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.sign == Sign::Neg {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", decimal(&self.numerator))?;
+        if !self.is_integer() {
+            write!(f, "/{}", decimal(&self.denominator))?;
+        }
+        Ok(())
+    }
+}
+
+/// Removes leading zero limbs.
+pub(super) fn trim(mut limbs: Vec<DigitType>) -> Vec<DigitType> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+/// Compares two unsigned integers.
+pub(super) fn cmp(lhs: &[DigitType], rhs: &[DigitType]) -> Ordering {
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+/// Shifts an unsigned integer right by any number of bits, dropping the bits shifted out.
+pub(super) fn shift_right(limbs: &[DigitType], bits: usize) -> Vec<DigitType> {
+    let limbs = &limbs[(bits / 64).min(limbs.len())..];
+    let shift = bits % 64;
+    if shift == 0 {
+        return trim(limbs.to_vec());
+    }
+    let shifted = limbs
+        .iter()
+        .enumerate()
+        .map(|(idx, limb)| {
+            let next = limbs.get(idx + 1).copied().unwrap_or(0);
+            (limb >> shift) | (next << (64 - shift))
+        })
+        .collect();
+    trim(shifted)
+}
+
+/// Adds two unsigned integers.
+pub(super) fn add(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    let mut sum = Vec::with_capacity(lhs.len().max(rhs.len()) + 1);
+    let mut carry = false;
+    for idx in 0..lhs.len().max(rhs.len()) {
+        let (digit, overflow_lhs) = lhs
+            .get(idx)
+            .copied()
+            .unwrap_or(0)
+            .overflowing_add(rhs.get(idx).copied().unwrap_or(0));
+        let (digit, overflow_carry) = digit.overflowing_add(carry as DigitType);
+        sum.push(digit);
+        carry = overflow_lhs || overflow_carry;
+    }
+    if carry {
+        sum.push(1);
+    }
+    sum
+}
+
+/// Subtracts two unsigned integers, where `lhs` is at least `rhs`.
+pub(super) fn sub(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    let mut difference = Vec::with_capacity(lhs.len());
+    let mut borrow = false;
+    for (idx, digit) in lhs.iter().enumerate() {
+        let (digit, borrow_rhs) = digit.overflowing_sub(rhs.get(idx).copied().unwrap_or(0));
+        let (digit, borrow_carry) = digit.overflowing_sub(borrow as DigitType);
+        difference.push(digit);
+        borrow = borrow_rhs || borrow_carry;
+    }
+    trim(difference)
+}
+
+/// Multiplies two unsigned integers.
+pub(super) fn mul(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    trim(multiply(lhs, rhs))
+}
+
+/// Greatest common divisor of two unsigned integers, by Euclid's algorithm.
+pub(super) fn gcd(lhs: &[DigitType], rhs: &[DigitType]) -> Vec<DigitType> {
+    let (mut lhs, mut rhs) = (lhs.to_vec(), rhs.to_vec());
+    while !rhs.is_empty() {
+        let remainder = div_rem(&lhs, &rhs).1;
+        (lhs, rhs) = (rhs, remainder);
+    }
+    lhs
+}
@@ -0,0 +1,202 @@
+//rounding a number to a given number of limbs or bits, knowing whether anything below its last limb was already discarded
+//arithmetic with a Context rounds each result this way, so a sequence of operations gives the same result whatever the operands' sizes
+//rounding to bits shifts the number so the last bit kept is the last bit of a limb, rounds it to limbs and shifts it back
+
+use std::collections::VecDeque;
+
+use super::{CASNum, CASValue, DigitType, Sign};
+
+/// Half of a limb, where rounding to the nearest switches from rounding down to rounding up.
+const HALF_LIMB: DigitType = 1 << 63;
+
+/// How a result that doesn't fit in the number of limbs asked for is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RoundingMode {
+    /// Round to the nearest value, and to the one with an even last limb when exactly halfway, like IEEE 754 arithmetic does by default.
+    HalfEven,
+    /// Drop the limbs that don't fit, so the magnitude never grows. Also called rounding down.
+    TowardZero,
+    /// Round any inexact result to the next larger magnitude. Also called rounding up.
+    AwayFromZero,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceiling,
+}
+
+/// The precision and rounding mode the results of arithmetic are rounded to.
+///
+/// Sums, differences, products and quotients found with a context are the exact result rounded once, so they don't depend on how many limbs the operands have. A context with a number of bits rounds like IEEE 754 arithmetic with that many bits in the significand, but with no limit on the exponent, so it can be checked against f64 results that don't overflow or become subnormal.
+///
+/// The `+`, `-` and `*` operators are exact and `/` rounds toward zero to a limb more than its longer operand, so only the `_with` methods and [CASNum::round] round to a context. Elementary functions and constants are always rounded to the nearest limb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Context {
+    /// Limbs results are rounded to, which hold at least `num_bits` bits if there are any.
+    pub(crate) num_limbs: usize,
+    /// Significant bits results are rounded to, counted from their highest set bit, or None to round to whole limbs.
+    pub(crate) num_bits: Option<usize>,
+    /// How results that don't fit are rounded.
+    pub(crate) mode: RoundingMode,
+}
+
+impl Context {
+    /// A context rounding to `num_limbs` limbs with `mode`.
+    pub(crate) fn new(num_limbs: usize, mode: RoundingMode) -> Self {
+        Context {
+            num_limbs,
+            num_bits: None,
+            mode,
+        }
+    }
+
+    /// A context rounding to `num_bits` significant bits with `mode`, like 53 bits rounded with [RoundingMode::HalfEven] for f64 arithmetic.
+    pub(crate) fn bits(num_bits: usize, mode: RoundingMode) -> Self {
+        Context {
+            //the highest set bit can be anywhere in the top limb
+            num_limbs: num_bits.div_ceil(64) + 1,
+            num_bits: Some(num_bits.max(1)),
+            mode,
+        }
+    }
+}
+
+impl CASNum {
+    /// Rounds the number to the context's precision.
+    pub(crate) fn round(self, context: Context) -> CASNum {
+        self.round_with(context, false)
+    }
+
+    /// Rounds a number to the context's precision, where `sticky` is true if nonzero bits below its last limb were already discarded, see [CASNum::round_to].
+    pub(super) fn round_with(self, context: Context, sticky: bool) -> CASNum {
+        let Some(num_bits) = context.num_bits else {
+            return self.round_to(context.num_limbs, sticky, context.mode);
+        };
+        if !self.value.is_finite() || self.is_zero() {
+            return self.round_to(context.num_limbs, sticky, context.mode);
+        }
+        //the lowest bit kept is moved to the bottom of a limb
+        let top = self.top_bit();
+        let low = top - num_bits as i64 + 1;
+        let shift = (-low).rem_euclid(64);
+        let num_limbs = ((top + shift).div_euclid(64) - (low + shift).div_euclid(64) + 1) as usize;
+        self.times_power_of_two(shift)
+            .round_to(num_limbs, sticky, context.mode)
+            .times_power_of_two(-shift)
+    }
+
+    /// The sum of two numbers, rounded to the context's precision.
+    pub(crate) fn add_with(&self, rhs: &CASNum, context: Context) -> CASNum {
+        (negligible(self, rhs, context) + negligible(rhs, self, context)).round(context)
+    }
+
+    /// The difference of two numbers, rounded to the context's precision.
+    pub(crate) fn sub_with(&self, rhs: &CASNum, context: Context) -> CASNum {
+        self.add_with(&-rhs.clone(), context)
+    }
+
+    /// The product of two numbers, rounded to the context's precision.
+    pub(crate) fn mul_with(&self, rhs: &CASNum, context: Context) -> CASNum {
+        (self.clone() * rhs.clone()).round(context)
+    }
+
+    /// Rounds a finite number to `num_limbs` limbs.
+    ///
+    /// `sticky` is true if the exact value has nonzero bits below the last limb stored, which were already discarded. Infinite and indeterminate numbers are returned as they are.
+    pub(super) fn round_to(self, num_limbs: usize, sticky: bool, mode: RoundingMode) -> CASNum {
+        let sign = self.sign;
+        let (mut digits, exp) = match self.value.normalize() {
+            CASValue::Finite { digits, exp } => (digits, exp),
+            value => return CASNum { value, sign },
+        };
+        if digits.len() <= num_limbs && !sticky {
+            return CASNum {
+                value: CASValue::Finite { digits, exp },
+                sign,
+            };
+        }
+        if digits.is_empty() {
+            //only bits below a zero were discarded, so there's nothing to round
+            return CASNum {
+                value: CASValue::Finite { digits, exp: 0 },
+                sign,
+            };
+        }
+
+        //the discarded bits are below the lowest limb, so padding with zeros down to a guard limb keeps the value the same
+        while digits.len() < num_limbs + 1 {
+            digits.push_front(0);
+        }
+        let cut = digits.len() - num_limbs;
+        let guard = digits[cut - 1];
+        let below_guard = sticky || digits.iter().take(cut - 1).any(|limb| *limb != 0);
+        let mut kept: VecDeque<DigitType> = digits.split_off(cut);
+
+        let inexact = guard != 0 || below_guard;
+        //whether the magnitude rounds up
+        let round_up = match mode {
+            RoundingMode::HalfEven => {
+                guard > HALF_LIMB
+                    || (guard == HALF_LIMB
+                        && (below_guard || kept.front().is_some_and(|limb| limb % 2 == 1)))
+            }
+            RoundingMode::TowardZero => false,
+            RoundingMode::AwayFromZero => inexact,
+            RoundingMode::Floor => inexact && sign == Sign::Neg,
+            RoundingMode::Ceiling => inexact && sign == Sign::Pos,
+        };
+        let mut exp = exp;
+        if round_up && increment(&mut kept) {
+            //every kept limb was all ones, so the result is the next power of 2 ^ 64
+            kept = VecDeque::from([1]);
+            exp += 1;
+        }
+
+        CASNum {
+            value: CASValue::Finite { digits: kept, exp }.normalize(),
+            sign,
+        }
+    }
+}
+
+/// `value`, or a single limb with its sign if it's so far below `other` that adding it exactly would only put limbs of zeros between them.
+///
+/// The stand-in is below the last limb of `other` and two limbs below where their sum is rounded to `context`, so the sum is strictly between the same two numbers of that precision and rounds the same way. Adding something like `1e-1000000` to `1` doesn't build a sum with thousands of limbs.
+fn negligible(value: &CASNum, other: &CASNum, context: Context) -> CASNum {
+    let (
+        CASValue::Finite { digits, exp },
+        CASValue::Finite {
+            digits: other_digits,
+            exp: other_exp,
+        },
+    ) = (&value.value, &other.value)
+    else {
+        return value.clone();
+    };
+    if digits.is_empty() || other_digits.is_empty() {
+        return value.clone();
+    }
+    let other_low = other_exp - other_digits.len() as isize + 1;
+    let below = other_low.min(other_exp - context.num_limbs as isize) - 2;
+    if *exp >= below {
+        return value.clone();
+    }
+    CASNum {
+        value: CASValue::Finite {
+            digits: VecDeque::from([1]),
+            exp: below,
+        },
+        sign: value.sign,
+    }
+}
+
+/// Adds 1 to the lowest limb, returning true if it carries out of the highest limb.
+fn increment(limbs: &mut VecDeque<DigitType>) -> bool {
+    for limb in limbs.iter_mut() {
+        let (sum, carry) = limb.overflowing_add(1);
+        *limb = sum;
+        if !carry {
+            return false;
+        }
+    }
+    true
+}
@@ -7,7 +7,13 @@ use rand_chacha::ChaCha8Rng;
 use super::super::cas_num::CASValue::*;
 use super::super::cas_num::Sign::*;
 use super::super::CASNum;
+use super::division;
 use super::literal;
+use super::multiplication;
+use super::{
+    CASComplex, CASInterval, CASRational, Context, Notation, Number, RoundingMode, INFINITY,
+    NEG_INFINITY, ZERO,
+};
 use rand::RngCore;
 
 fn comparison(a: i128, b: i128) -> bool {
@@ -790,6 +796,82 @@ fn multiplication_tests() {
     assert_eq!(num_wrong, 0);
 }
 
+/// Random limbs, with runs of zero and all ones limbs mixed in since they're where carries go wrong.
+fn random_limbs(rng: &mut ChaCha8Rng, len: usize) -> Vec<u64> {
+    (0..len)
+        .map(|_| match rng.next_u32() % 8 {
+            0 => 0,
+            1 => u64::MAX,
+            _ => rng.next_u64(),
+        })
+        .collect()
+}
+
+#[test]
+fn multiplication_large_tests() {
+    let mut rng = ChaCha8Rng::seed_from_u64(123);
+    //sizes on both sides of the thresholds between algorithms, balanced and not
+    let sizes: [(usize, usize); 10] = [
+        (1, 1),
+        (63, 64),
+        (64, 64),
+        (65, 200),
+        (1000, 7),
+        (1000, 999),
+        (3000, 2500),
+        (4096, 4096),
+        (5000, 4500),
+        (9000, 4100),
+    ];
+    for (lhs_len, rhs_len) in sizes {
+        let lhs = random_limbs(&mut rng, lhs_len);
+        let rhs = random_limbs(&mut rng, rhs_len);
+        let mut expected = vec![0; lhs_len + rhs_len];
+        multiplication::schoolbook_into(&mut expected, &lhs, &rhs);
+        assert!(
+            multiplication::multiply(&lhs, &rhs) == expected,
+            "{} x {} limbs",
+            lhs_len,
+            rhs_len
+        );
+    }
+
+    //all ones operands have the most carries, (2 ^ 64n - 1) ^ 2 = 2 ^ 128n - 2 ^ (64n + 1) + 1
+    let ones = vec![u64::MAX; 5000];
+    let mut expected = vec![0; 10000];
+    expected[0] = 1;
+    expected[5000] = u64::MAX - 1;
+    expected[5001..].fill(u64::MAX);
+    assert!(multiplication::multiply(&ones, &ones) == expected);
+}
+
+#[test]
+fn multiplication_large_casnum_tests() {
+    let mut rng = ChaCha8Rng::seed_from_u64(321);
+    let mut random = |len: usize, exp: isize| CASNum {
+        value: Finite {
+            digits: random_limbs(&mut rng, len).into(),
+            exp,
+        }
+        .normalize(),
+        sign: if rng.next_u32() % 2 == 0 { Pos } else { Neg },
+    };
+    let a = random(5000, 20);
+    let b = random(4200, -3000);
+    let c = random(3000, 10);
+
+    assert_eq!(a.clone() * b.clone(), b.clone() * a.clone());
+    assert_eq!(
+        a.clone() * (b.clone() + c.clone()),
+        a.clone() * b.clone() + a.clone() * c.clone()
+    );
+    assert_eq!(
+        (a.clone() * b.clone()) * c.clone(),
+        a.clone() * (b.clone() * c.clone())
+    );
+    assert_eq!(-a.clone() * -b.clone(), a * b);
+}
+
 #[test]
 
 fn conversion_tests_reverse() {
@@ -815,55 +897,95 @@ fn conversion_tests_reverse() {
     assert_eq!(num_wrong, 0);
 }
 
+/// Parses a whole string as a numeric literal.
+fn parse(lit: &str) -> Result<Number, String> {
+    let mut iter = lit.chars().enumerate().peekable();
+    let first_char = iter.next().unwrap().1;
+    let mut line_pos = 0;
+    let number = literal::parse_lit(first_char, &mut iter, &mut line_pos)
+        .unwrap()
+        .map_err(|err| format!("{:?}", err.kind))?;
+    assert!(iter.next().is_none(), "{} wasn't parsed to the end", lit);
+    Ok(number)
+}
+
 #[test]
 fn literal_parsing() {
-    let num_lits = vec![
-            "9999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999",
-            // "3",
-            // ".3",
-            // "1.3",
-            // "-3",
-            // "-.3",
-            // "-1.3",
-            // "1.-3",
-            // "12a",
-            // "1,2.a",
-            // "+-12,2.54",
-            // "02839583928934294234",
-            // "e3",
-            // "e.3",
-            // "e3.3",
-            // "e-3",
-            // "e-.3",
-            // "e-3.3",
-            // "3e1",
-            // ".3e1",
-            // "1.3e1",
-            // "-3e1",
-            // "-.3e1",
-            // "-1.3e1",
-            // "1.-3e1",
-            // "12ae1",
-            // "1,2.ae1",
-            // "+-12,2.54e1",
-            // "02839583928934294234e1",
-            // "3e-1",
-            // ".3e-1",
-            // "1.3e-1",
-            // "-3e-1",
-            // "-.3e-1",
-            // "-1.3e-1",
-            // "1.-3e-1",
-            // "12ae-1",
-            // "1,2.ae-1",
-            // "+-12,2.54e-1",
-            // "02839583928934294234e-1",
-        ];
-
-    for lit in num_lits {
-        let mut iter = lit.chars().enumerate().peekable();
-        let first_char = iter.next().unwrap().1;
-        literal::parse_lit(first_char, &mut iter, &mut 0);
+    let nines = "9".repeat(140);
+    let exact = parse(&nines).unwrap().exact().unwrap();
+    assert_eq!(exact.to_string(), nines);
+    assert_eq!(parse("1_000_000"), Ok(Number::from(1_000_000)));
+    assert_eq!(parse("0xFF_ff"), Ok(Number::from(0xFFFF)));
+    assert_eq!(parse("0b1010"), Ok(Number::from(10)));
+    assert_eq!(parse("0o17"), Ok(Number::from(15)));
+    assert_eq!(parse("0x1e"), Ok(Number::from(30)));
+    assert!(matches!(parse("12"), Ok(Number::Exact(_))));
+
+    //decimals are rounded from their exact value, not from the nearest f64
+    let tenth = |num_limbs| fraction(1, 10).round(Context::new(num_limbs, RoundingMode::HalfEven));
+    assert_eq!(parse("0.1"), Ok(Number::Inexact(tenth(3))));
+    assert_ne!(parse("0.1"), Ok(Number::from(0.1)));
+    assert_eq!(parse(".1"), parse("1e-1"));
+    assert_eq!(parse("0.000_1e3"), parse("0.1"));
+    assert_eq!(parse("1.5"), Ok(Number::from(1.5)));
+    assert_eq!(parse("25e-1"), Ok(Number::from(2.5)));
+    assert_eq!(parse("1.3E+2"), Ok(Number::from(130)));
+    assert!(matches!(parse("2e3"), Ok(Number::Inexact(_))));
+    let long = format!("0.{}", "3".repeat(60));
+    let Ok(Number::Inexact(third)) = parse(&long) else {
+        panic!("{} isn't inexact", long);
+    };
+    assert!(third.value.num_digits() > 3);
+    assert_eq!(third.to_decimal(60, Notation::Fixed), long);
+    let huge = parse("1e400").unwrap();
+    assert_eq!(huge.to_string(), "1e400");
+
+    //large exponents are scaled at the precision of the result, and still correctly rounded
+    for exp in [-400_i32, -23, 23, 400] {
+        //1.7 * 10 ^ exp = 17 * 10 ^ (exp - 1)
+        let mut exact = CASRational::from(17);
+        for _ in 0..(exp - 1).abs() {
+            exact = if exp > 0 {
+                exact * CASRational::from(10)
+            } else {
+                exact * fraction(1, 10)
+            };
+        }
+        assert_eq!(
+            parse(&format!("1.7e{}", exp)),
+            Ok(Number::Inexact(
+                exact.round(Context::new(3, RoundingMode::HalfEven))
+            )),
+            "1.7e{}",
+            exp
+        );
+    }
+    let parsed = |lit| parse(lit).unwrap().to_cas_num(3);
+    let huge = parsed("1e1000000");
+    assert!(parsed("9.99e999999") < huge && huge < parsed("1.01e1000000"));
+    let tiny = parsed("1e-1000000");
+    let product = tiny * huge;
+    //each is rounded to 3 limbs, which is at least 129 significant bits
+    assert!((product - CASNum::from(1)).abs() < CASNum::from(1e-37));
+
+    //exponents up to the limit don't need their power of 10's digits either
+    let huge = parsed("1e99999999999");
+    assert!(parsed("9.99e99999999998") < huge && huge < parsed("1.01e99999999999"));
+    assert!(parse("1e1000000000000").is_ok() && parse("1e-1000000000000").is_ok());
+
+    for lit in [
+        "1.2.3",
+        "1e5.3",
+        "0x",
+        "0b12",
+        ".",
+        "1__0",
+        "1_",
+        "0x_1",
+        "1e1000000000001",
+        "0.1e-1000000000000",
+    ] {
+        assert!(parse(lit).is_err(), "{}", lit);
     }
 }
 
@@ -994,3 +1116,1073 @@ fn div_by_u64() {
     }
     assert_eq!(num_wrong, 0);
 }
+#[test]
+fn division_tests() {
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut num_wrong = 0;
+    for _ in 0..1000 {
+        let num_1 = CASNum::from(rng.next_u64());
+        let num_2 = CASNum::from(rng.next_u64()) * CASNum::from(rng.next_u64());
+
+        if (num_1.clone() * num_2.clone()) / num_2.clone() != num_1 {
+            num_wrong += 1;
+        }
+        if -(num_1.clone() * num_2.clone()) / num_1.clone() != -num_2 {
+            num_wrong += 1;
+        }
+    }
+    assert_eq!(num_wrong, 0);
+
+    assert_eq!(CASNum::from(1) / CASNum::from(0.5), CASNum::from(2));
+    assert_eq!(CASNum::from(3) / CASNum::from(4), CASNum::from(0.75));
+    assert_eq!(CASNum::from(-1.5) / CASNum::from(0.25), CASNum::from(-6));
+
+    let third = CASNum::from(1).quotient(&CASNum::from(3), 10);
+    assert_eq!(third.value.num_digits(), 10);
+    let error = CASNum::from(1) - third * CASNum::from(3);
+    assert!(error > ZERO && error < CASNum::from(1.0e-180));
+
+    assert_eq!(CASNum::from(2) / ZERO, INFINITY);
+    assert_eq!(CASNum::from(-2) / ZERO, NEG_INFINITY);
+    assert_eq!(ZERO / CASNum::from(2), ZERO);
+    assert_eq!(CASNum::from(2) / INFINITY, ZERO);
+    assert!((ZERO / ZERO).value.is_indeterminate());
+}
+#[test]
+fn division_large_tests() {
+    let mut rng = ChaCha8Rng::seed_from_u64(456);
+    //sizes for short division, long division and division by a reciprocal
+    let sizes: [(usize, usize); 9] = [
+        (1, 1),
+        (7, 1),
+        (2, 2),
+        (10, 3),
+        (50, 49),
+        (300, 20),
+        (600, 300),
+        (1500, 700),
+        (3200, 1600),
+    ];
+    for (dividend_len, divisor_len) in sizes {
+        let dividend = random_limbs(&mut rng, dividend_len);
+        let mut divisor = random_limbs(&mut rng, divisor_len);
+        divisor[divisor_len - 1] |= 1;
+        let (quotient, remainder) = division::div_rem(&dividend, &divisor);
+
+        let integer = |limbs: &[u64]| division::integer_from_limbs(Pos, limbs);
+        assert!(
+            integer(&remainder) < integer(&divisor),
+            "{} / {} limbs",
+            dividend_len,
+            divisor_len
+        );
+        assert!(
+            integer(&quotient) * integer(&divisor) + integer(&remainder) == integer(&dividend),
+            "{} / {} limbs",
+            dividend_len,
+            divisor_len
+        );
+    }
+
+    //a divisor with a small top limb needs the largest normalizing shift
+    let dividend = vec![u64::MAX; 20];
+    let divisor = [u64::MAX, u64::MAX, 1];
+    let (quotient, remainder) = division::div_rem(&dividend, &divisor);
+    let integer = |limbs: &[u64]| division::integer_from_limbs(Pos, limbs);
+    assert!(integer(&remainder) < integer(&divisor));
+    assert!(integer(&quotient) * integer(&divisor) + integer(&remainder) == integer(&dividend));
+}
+
+#[test]
+fn division_rounding() {
+    let one = CASNum::from(1);
+    let third = |mode| one.div_with(&CASNum::from(3), Context::new(4, mode));
+    let two_thirds = |mode| CASNum::from(2).div_with(&CASNum::from(3), Context::new(4, mode));
+
+    //1/3 = 0x0.5555..., which rounds down either way, but 2/3 = 0x0.AAAA... rounds up to the nearest
+    assert_eq!(
+        third(RoundingMode::HalfEven),
+        third(RoundingMode::TowardZero)
+    );
+    assert!(two_thirds(RoundingMode::HalfEven) > two_thirds(RoundingMode::TowardZero));
+    assert_eq!(
+        two_thirds(RoundingMode::HalfEven) - two_thirds(RoundingMode::TowardZero),
+        from_limbs(Pos, -4, &[1])
+    );
+    assert_eq!(
+        CASNum::from(-2).div_with(&CASNum::from(3), Context::new(4, RoundingMode::HalfEven)),
+        -two_thirds(RoundingMode::HalfEven)
+    );
+    assert_eq!(
+        third(RoundingMode::TowardZero),
+        one.quotient(&CASNum::from(3), 4)
+    );
+
+    //exactly halfway rounds to an even last limb
+    let half = 1 << 63;
+    let round = |digits: &[u64]| {
+        from_limbs(Pos, 0, digits).div_with(&one, Context::new(1, RoundingMode::HalfEven))
+    };
+    assert_eq!(round(&[half, 1]), CASNum::from(2));
+    assert_eq!(round(&[half, 2]), CASNum::from(2));
+    assert_eq!(round(&[half + 1, 2]), CASNum::from(3));
+    assert_eq!(
+        round(&[half, u64::MAX]),
+        CASNum::from(1u128 << 64),
+        "rounding up carries into a new limb"
+    );
+}
+
+#[test]
+fn rounding_modes() {
+    use RoundingMode::*;
+    let modes = [HalfEven, TowardZero, AwayFromZero, Floor, Ceiling];
+    let round = |value: &CASNum, mode| value.clone().round(Context::new(1, mode));
+
+    //5 + 2 ^ -64 and its negative, rounded to integers
+    let above_five = from_limbs(Pos, 0, &[1, 5]);
+    let expected = [5, 5, 6, 5, 6];
+    for (mode, expected) in modes.into_iter().zip(expected) {
+        assert_eq!(
+            round(&above_five, mode),
+            CASNum::from(expected),
+            "{:?}",
+            mode
+        );
+    }
+    let below_minus_five = -above_five;
+    let expected = [-5, -5, -6, -6, -5];
+    for (mode, expected) in modes.into_iter().zip(expected) {
+        assert_eq!(
+            round(&below_minus_five, mode),
+            CASNum::from(expected),
+            "{:?}",
+            mode
+        );
+    }
+
+    //values that fit are never changed
+    for mode in modes {
+        assert_eq!(round(&CASNum::from(-7), mode), CASNum::from(-7));
+        assert_eq!(round(&ZERO, mode), ZERO);
+        assert_eq!(round(&INFINITY, mode), INFINITY);
+    }
+
+    //arithmetic rounds the exact result once
+    let context = |mode| Context::new(1, mode);
+    let tiny = from_limbs(Pos, -2, &[1]);
+    assert_eq!(
+        CASNum::from(1).add_with(&tiny, context(Ceiling)),
+        CASNum::from(2)
+    );
+    assert_eq!(
+        CASNum::from(1).add_with(&tiny, context(Floor)),
+        CASNum::from(1)
+    );
+    assert_eq!(
+        CASNum::from(1).sub_with(&tiny, context(Floor)),
+        from_limbs(Pos, -1, &[u64::MAX])
+    );
+    assert_eq!(
+        CASNum::from(1).sub_with(&tiny, context(HalfEven)),
+        CASNum::from(1)
+    );
+    //without adding a billion limbs of zeros between them
+    let tinier = from_limbs(Neg, -1_000_000_000, &[1]);
+    assert_eq!(
+        CASNum::from(1).add_with(&tinier, context(Floor)),
+        from_limbs(Pos, -1, &[u64::MAX])
+    );
+    assert_eq!(
+        tinier.sub_with(&CASNum::from(1), context(Ceiling)),
+        CASNum::from(-1)
+    );
+    let big = CASNum::from(u64::MAX);
+    assert_eq!(
+        big.mul_with(&big, context(TowardZero)),
+        from_limbs(Pos, 1, &[u64::MAX - 1])
+    );
+    assert_eq!(
+        big.mul_with(&big, context(AwayFromZero)),
+        from_limbs(Pos, 1, &[u64::MAX])
+    );
+
+    //floor and ceiling bracket the exact quotient
+    let minus_third = |mode| CASNum::from(-1).div_with(&CASNum::from(3), Context::new(3, mode));
+    assert!(minus_third(Floor) * CASNum::from(3) < CASNum::from(-1));
+    assert!(minus_third(Ceiling) * CASNum::from(3) > CASNum::from(-1));
+    assert_eq!(minus_third(Ceiling), minus_third(TowardZero));
+    assert_eq!(minus_third(Floor), minus_third(AwayFromZero));
+
+    //bits are counted from the highest set bit, and 1 / 3 = 0.010101... in binary
+    let third = |mode| CASNum::from(1).div_with(&CASNum::from(3), Context::bits(2, mode));
+    assert_eq!(third(Floor), CASNum::from(0.25));
+    assert_eq!(third(Ceiling), CASNum::from(0.375));
+    assert_eq!(
+        CASNum::from(7).round(Context::bits(2, HalfEven)),
+        CASNum::from(8)
+    );
+    assert_eq!(
+        CASNum::from(-5.5).round(Context::bits(2, TowardZero)),
+        CASNum::from(-4)
+    );
+
+    //53 bits rounded to the nearest are the arithmetic of f64s
+    let mut rng = ChaCha8Rng::seed_from_u64(53);
+    let double = Context::bits(53, HalfEven);
+    let mut random = || {
+        let significand = rng.next_u64() as f64 / u64::MAX as f64 - 0.5;
+        significand * 2_f64.powi((rng.next_u32() % 200) as i32 - 100)
+    };
+    for _ in 0..1000 {
+        let (a, b) = (random(), random());
+        let (x, y) = (CASNum::from(a), CASNum::from(b));
+        assert_eq!(x.add_with(&y, double), CASNum::from(a + b), "{} + {}", a, b);
+        assert_eq!(x.sub_with(&y, double), CASNum::from(a - b), "{} - {}", a, b);
+        assert_eq!(x.mul_with(&y, double), CASNum::from(a * b), "{} * {}", a, b);
+        assert_eq!(x.div_with(&y, double), CASNum::from(a / b), "{} / {}", a, b);
+    }
+}
+
+#[test]
+fn integer_div_rem() {
+    let div_rem = |a: i128, b: i128| CASNum::from(a).div_rem(&CASNum::from(b));
+    assert_eq!(div_rem(7, 2), Some((CASNum::from(3), CASNum::from(1))));
+    assert_eq!(div_rem(-7, 2), Some((CASNum::from(-3), CASNum::from(-1))));
+    assert_eq!(div_rem(7, -2), Some((CASNum::from(-3), CASNum::from(1))));
+    assert_eq!(div_rem(-6, -2), Some((CASNum::from(3), ZERO)));
+    assert_eq!(div_rem(1, 0), None);
+    assert_eq!(CASNum::from(1.5).div_rem(&CASNum::from(1)), None);
+    assert_eq!(INFINITY.div_rem(&CASNum::from(1)), None);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(654);
+    for _ in 0..100 {
+        let a = (rng.next_u64() / 3) as i128 * rng.next_u64() as i128 - (1 << 100);
+        let b = (rng.next_u64() >> (rng.next_u32() % 64)) as i128 + 1;
+        assert_eq!(
+            div_rem(a, b),
+            Some((CASNum::from(a / b), CASNum::from(a % b))),
+            "{} / {}",
+            a,
+            b
+        );
+    }
+
+    //integers with limbs below the point, and a quotient too large for any primitive
+    let big = from_limbs(Neg, 40, &[3, 5, 7]);
+    let small = from_limbs(Pos, 1, &[11, 13]);
+    let (quotient, remainder) = big.div_rem(&small).unwrap();
+    assert!(remainder <= ZERO && remainder.abs() < small);
+    assert_eq!(quotient * small + remainder, big);
+}
+#[test]
+fn normalized_results() {
+    assert_eq!(
+        CASNum::from(u64::MAX) + CASNum::from(1u64),
+        CASNum::from(1u128 << 64)
+    );
+    assert_eq!(
+        CASNum::from(1u128 << 64) - CASNum::from(1u64),
+        CASNum::from(u64::MAX)
+    );
+    assert_eq!(CASNum::from(0.5) + CASNum::from(0.5), CASNum::from(1));
+    assert_eq!(CASNum::from(1) - CASNum::from(0.75), CASNum::from(0.25));
+    assert!(CASNum::from(1) - CASNum::from(0.75) < CASNum::from(0.5));
+}
+
+/// Builds a finite number from its limbs, least significant first.
+fn from_limbs(sign: super::Sign, exp: isize, digits: &[u64]) -> CASNum {
+    CASNum {
+        value: Finite {
+            digits: digits.iter().copied().collect(),
+            exp,
+        },
+        sign,
+    }
+}
+
+#[test]
+fn elementary_functions() {
+    //expected values are correctly rounded to 3 limbs
+    type Function = fn(&CASNum, usize) -> CASNum;
+    let cases: [(Function, f64, super::Sign, isize, [u64; 3]); 23] = [
+        (
+            CASNum::sqrt,
+            2.0,
+            Pos,
+            0,
+            [0xB2FB1366EA957D3E, 0x6A09E667F3BCC908, 0x1],
+        ),
+        (
+            CASNum::cbrt,
+            2.0,
+            Pos,
+            0,
+            [0x3DDAB715BE250D0C, 0x428A2F98D728AE22, 0x1],
+        ),
+        (
+            CASNum::exp,
+            1.0,
+            Pos,
+            0,
+            [0xBF7158809CF4F3C7, 0xB7E151628AED2A6A, 0x2],
+        ),
+        (
+            CASNum::exp,
+            -10.0,
+            Pos,
+            -1,
+            [0xF2BDF1D29CB28267, 0x538B648EAA1310E5, 0x0002F9AF36AC8F93],
+        ),
+        (
+            CASNum::exp,
+            100.0,
+            Pos,
+            2,
+            [0x933224286534456F, 0xA9B171BF4ACC2250, 0x13494],
+        ),
+        (
+            CASNum::ln,
+            2.0,
+            Pos,
+            -1,
+            [0x40F343267298B62E, 0xC9E3B39803F2F6AF, 0xB17217F7D1CF79AB],
+        ),
+        (
+            CASNum::ln,
+            10.0,
+            Pos,
+            0,
+            [0xA95B58AE0B4C28A4, 0x4D763776AAA2B05B, 0x2],
+        ),
+        (
+            CASNum::ln,
+            0.001,
+            Neg,
+            0,
+            [0xFC120A0A21E599EB, 0xE862A663FFE80F92, 0x6],
+        ),
+        (
+            CASNum::sin,
+            1.0,
+            Pos,
+            -1,
+            [0x89E511132F518B4E, 0xC6E9E909C50F3C32, 0xD76AA47848677020],
+        ),
+        (
+            CASNum::cos,
+            1.0,
+            Pos,
+            -1,
+            [0xA2373A894F96C3B8, 0xC2466D976871BD29, 0x8A51407DA8345C91],
+        ),
+        (
+            CASNum::tan,
+            1.0,
+            Pos,
+            0,
+            [0xCC7D41323140B3B5, 0x8EB245CBEE3A5B8A, 0x1],
+        ),
+        (
+            CASNum::sin,
+            100.0,
+            Neg,
+            -1,
+            [0x57892550CEA15742, 0x47B0AAE841F590E3, 0x81A12DBC626DC038],
+        ),
+        (
+            CASNum::cos,
+            -7.5,
+            Pos,
+            -1,
+            [0x722CB3939E8D797E, 0x467704338AED2BEF, 0x58BD1799BD70BB04],
+        ),
+        (
+            CASNum::csc,
+            2.0,
+            Pos,
+            0,
+            [0x7B2B4E69D995D0F1, 0x19893A272F9123D2, 0x1],
+        ),
+        (
+            CASNum::sec,
+            2.0,
+            Neg,
+            0,
+            [0x3039087095E5B607, 0x672ADFD9F3C906F9, 0x2],
+        ),
+        (
+            CASNum::cot,
+            2.0,
+            Neg,
+            -1,
+            [0xCC6805C71644FC99, 0x5151F2C857AAE2C4, 0x75290BA4BEA937B8],
+        ),
+        (
+            CASNum::asin,
+            0.5,
+            Pos,
+            -1,
+            [0x70AC3405B19A884D, 0x2DD99707AB3D688B, 0x860A91C16B9B2C23],
+        ),
+        (
+            CASNum::acos,
+            0.3,
+            Pos,
+            0,
+            [0x43BAC69CABB1C40A, 0x441F5ECBEEF58A64, 0x1],
+        ),
+        (
+            CASNum::atan,
+            -3.0,
+            Neg,
+            0,
+            [0x7CB88581DEF96353, 0x3FC176B7A855FFD7, 0x1],
+        ),
+        (
+            CASNum::acot,
+            -2.0,
+            Pos,
+            0,
+            [0x5B276737A5532CC3, 0xAD8DCE72FEB5CB30, 0x2],
+        ),
+        (
+            CASNum::asec,
+            2.0,
+            Pos,
+            0,
+            [0x5BB32E0F567AD117, 0x0C152382D7365846, 0x1],
+        ),
+        (
+            CASNum::acsc,
+            2.0,
+            Pos,
+            -1,
+            [0x70AC3405B19A884D, 0x2DD99707AB3D688B, 0x860A91C16B9B2C23],
+        ),
+        (
+            CASNum::acos,
+            -1.0,
+            Pos,
+            0,
+            [0x13198A2E03707345, 0x243F6A8885A308D3, 0x3],
+        ),
+    ];
+    for (function, arg, sign, exp, digits) in cases {
+        let expected = from_limbs(sign, exp, &digits);
+        assert_eq!(function(&CASNum::from(arg), 3), expected, "f({})", arg);
+    }
+
+    let pow = CASNum::from(10).pow(&CASNum::from(2.5), 3);
+    assert_eq!(
+        pow,
+        from_limbs(Pos, 0, &[0xF2346620A1DEF12F, 0x3A4EDFA975882139, 0x13C])
+    );
+}
+
+#[test]
+fn elementary_exact_values() {
+    assert_eq!(CASNum::from(-27).cbrt(3), CASNum::from(-3));
+    assert_eq!(CASNum::from(8).log2(3), CASNum::from(3));
+    assert_eq!(CASNum::from(1000).log10(3), CASNum::from(3));
+    assert_eq!(CASNum::from(81).log(&CASNum::from(3), 3), CASNum::from(4));
+    assert_eq!(CASNum::from(4).pow(&CASNum::from(0.5), 3), CASNum::from(2));
+    assert_eq!(CASNum::from(-2).pow(&CASNum::from(3), 3), CASNum::from(-8));
+    assert_eq!(
+        CASNum::from(2).pow(&CASNum::from(-2), 3),
+        CASNum::from(0.25)
+    );
+    assert_eq!(ZERO.exp(3), CASNum::from(1));
+    assert_eq!(CASNum::from(1).ln(3), ZERO);
+    assert_eq!(CASNum::from(1).acos(3), ZERO);
+    assert_eq!(ZERO.sin(3), ZERO);
+
+    assert_eq!(ZERO.ln(3), NEG_INFINITY);
+    assert_eq!(INFINITY.exp(3), INFINITY);
+    assert_eq!(NEG_INFINITY.exp(3), ZERO);
+    assert_eq!(INFINITY.atan(3), ZERO.acos(3));
+    assert_eq!(CASNum::from(1e20).exp(3), INFINITY);
+    assert_eq!(CASNum::from(0.5).pow(&INFINITY, 3), ZERO);
+}
+
+#[test]
+fn elementary_domain_errors() {
+    let indeterminate = [
+        CASNum::from(-1).sqrt(3),
+        CASNum::from(-1).ln(3),
+        CASNum::from(2).asin(3),
+        CASNum::from(-1.5).acos(3),
+        CASNum::from(0.5).asec(3),
+        CASNum::from(2).log(&CASNum::from(1), 3),
+        CASNum::from(-8).pow(&CASNum::from(0.5), 3),
+        INFINITY.sin(3),
+        (ZERO / ZERO).cos(3),
+    ];
+    for value in indeterminate {
+        assert!(value.value.is_indeterminate(), "{:?}", value);
+    }
+}
+
+#[test]
+fn elementary_identities() {
+    let one = CASNum::from(1);
+    //the top limb of a result can be as small as 1, so 4 limbs are only sure to hold 3 limbs of precision
+    let tolerance = CASNum::from(1e-55);
+    for arg in [0.1, 0.7, 1.3, 4.0, 25.0, -3.2] {
+        let x = CASNum::from(arg);
+        let sin = x.sin(4);
+        let cos = x.cos(4);
+        let pythagoras = sin.clone() * sin + cos.clone() * cos - one.clone();
+        assert!(pythagoras.abs() < tolerance, "sin^2 + cos^2 at {}", arg);
+
+        let round_trip = x.exp(4).ln(4) - x.clone();
+        assert!(round_trip.abs() < tolerance, "ln(exp({}))", arg);
+
+        let round_trip = x.atan(4).tan(4) - x;
+        assert!(round_trip.abs() < tolerance, "tan(atan({}))", arg);
+    }
+}
+
+#[test]
+fn constants() {
+    //expected values are correctly rounded to 3 limbs
+    let cases: [(fn(usize) -> CASNum, CASNum); 4] = [
+        (
+            CASNum::pi,
+            from_limbs(Pos, 0, &[0x13198A2E03707345, 0x243F6A8885A308D3, 3]),
+        ),
+        (
+            CASNum::tau,
+            from_limbs(Pos, 0, &[0x2633145C06E0E689, 0x487ED5110B4611A6, 6]),
+        ),
+        (
+            CASNum::e,
+            from_limbs(Pos, 0, &[0xBF7158809CF4F3C7, 0xB7E151628AED2A6A, 2]),
+        ),
+        (
+            CASNum::phi,
+            from_limbs(Pos, 0, &[0xF39CC0605CEDC834, 0x9E3779B97F4A7C15, 1]),
+        ),
+    ];
+    for (constant, expected) in cases {
+        assert_eq!(constant(3), expected);
+        //cached values are the same as the first
+        assert_eq!(constant(3), expected);
+    }
+
+    //π from the Chudnovsky series against 4 * atan(1), which doesn't use it
+    let num_limbs = 40;
+    let tolerance = CASNum::from(1).times_power_of_two(-64 * (num_limbs as i64 - 2));
+    let difference = CASNum::pi(num_limbs) - CASNum::from(1).atan(num_limbs).times_power_of_two(2);
+    assert!(difference.abs() < tolerance);
+    assert_eq!(CASNum::pi(num_limbs).rounded(3), CASNum::pi(3));
+}
+
+/// p / q as an exact rational.
+fn fraction(p: i128, q: i128) -> CASRational {
+    CASRational::from(p) * CASRational::from(q).recip().unwrap()
+}
+
+#[test]
+fn rational_arithmetic() {
+    assert_eq!(fraction(1, 3) + fraction(1, 6), fraction(1, 2));
+    assert_eq!(fraction(1, 3) - fraction(1, 2), fraction(-1, 6));
+    assert_eq!(fraction(-2, 3) * fraction(9, -4), fraction(3, 2));
+    assert_eq!(fraction(4, 6), fraction(2, 3));
+    assert_eq!(fraction(5, 5), CASRational::from(1));
+    assert_eq!(fraction(1, 3) - fraction(1, 3), CASRational::from(0));
+    assert_eq!(CASRational::from(0).recip(), None);
+
+    //numerators and denominators bigger than a limb
+    let big = fraction(10i128.pow(30), 3 * 10i128.pow(25));
+    assert_eq!(big, fraction(100_000, 3));
+    assert_eq!(big.to_string(), "100000/3");
+    let power = CASRational::from(1i128 << 64) * CASRational::from(1i128 << 64);
+    assert_eq!(power.to_string(), "340282366920938463463374607431768211456");
+    assert_eq!((power.clone() + fraction(1, 7)) - power, fraction(1, 7));
+
+    assert!(fraction(-1, 2) < fraction(-1, 3));
+    assert!(fraction(-1, 3) < CASRational::from(0));
+    assert!(CASRational::from(0) < fraction(1, 3));
+    assert!(fraction(1, 3) < fraction(1, 2));
+
+    assert_eq!(fraction(-7, 3).to_string(), "-7/3");
+    assert_eq!(CASRational::from(0).to_string(), "0");
+    assert_eq!(i64::try_from(&CASRational::from(i64::MIN)), Ok(i64::MIN));
+    assert_eq!(i64::try_from(&fraction(1, 2)), Err(()));
+    assert_eq!(i64::try_from(&CASRational::from(1i128 << 64)), Err(()));
+}
+
+#[test]
+fn rational_conversion() {
+    assert_eq!(
+        CASRational::try_from(&CASNum::from(0.375)),
+        Ok(fraction(3, 8))
+    );
+    assert_eq!(
+        CASRational::try_from(&CASNum::from(-6)),
+        Ok(CASRational::from(-6))
+    );
+    assert_eq!(
+        CASRational::try_from(&CASNum::from(1u128 << 70)),
+        Ok(CASRational::from(1i128 << 70))
+    );
+    assert_eq!(CASRational::try_from(&ZERO), Ok(CASRational::from(0)));
+    assert_eq!(CASRational::try_from(&INFINITY), Err(()));
+
+    assert_eq!(fraction(-1, 4).to_cas_num(2), CASNum::from(-0.25));
+    assert_eq!(
+        fraction(1, 3).to_cas_num(3),
+        CASNum::from(1).quotient(&CASNum::from(3), 3)
+    );
+    //integers are converted exactly whatever the precision
+    assert_eq!(
+        CASRational::from(i128::MAX).to_cas_num(1),
+        CASNum::from(i128::MAX)
+    );
+}
+
+#[test]
+fn exact_and_inexact_numbers() {
+    assert_eq!(Number::from(2), Number::from(2.0));
+    assert_ne!(Number::from(fraction(1, 3)), Number::from(1.0 / 3.0));
+    let set = std::collections::HashSet::from([Number::from(2), Number::from(0.5)]);
+    assert!(set.contains(&Number::from(2.0)));
+    assert!(set.contains(&Number::from(fraction(1, 2))));
+
+    assert!(matches!(
+        Number::from(fraction(1, 3)) * Number::from(3),
+        Number::Exact(value) if value == CASRational::from(1)
+    ));
+    assert!(matches!(
+        Number::from(fraction(1, 2)) + Number::from(0.25),
+        Number::Inexact(value) if value == CASNum::from(0.75)
+    ));
+    assert!(Number::from(fraction(-1, 2)) < Number::from(-0.25));
+    assert!(Number::from(1) < Number::from(INFINITY));
+    assert_eq!(i64::try_from(&Number::from(4.0)), Ok(4));
+
+    assert!(matches!(
+        Number::from(fraction(-2, 3)).recip(),
+        Some(Number::Exact(value)) if value == fraction(-3, 2)
+    ));
+    assert_eq!(Number::from(0.25).recip(), Some(Number::from(4)));
+    assert_eq!(Number::from(0).recip(), None);
+    assert_eq!(Number::from(0.0).recip(), None);
+}
+
+#[test]
+fn decimal_output() {
+    use Notation::*;
+    let number = CASNum::from(1234.5);
+    assert_eq!(number.to_decimal(5, Fixed), "1234.5");
+    assert_eq!(number.to_decimal(5, Scientific), "1.2345e3");
+    assert_eq!(number.to_decimal(7, Engineering), "1.234500e3");
+    assert_eq!(number.to_decimal(2, Fixed), "1200");
+    assert_eq!(CASNum::from(0.00012).to_decimal(2, Fixed), "0.00012");
+    assert_eq!(CASNum::from(0.00012).to_decimal(2, Engineering), "120e-6");
+    assert_eq!(CASNum::from(-0.5).to_decimal(1, Scientific), "-5e-1");
+
+    //exactly halfway rounds to an even last digit, and rounding can carry into a new digit
+    assert_eq!(CASNum::from(0.125).to_decimal(2, Fixed), "0.12");
+    assert_eq!(CASNum::from(0.375).to_decimal(2, Fixed), "0.38");
+    assert_eq!(CASNum::from(2.5).to_decimal(1, Fixed), "2");
+    assert_eq!(CASNum::from(9.5).to_decimal(1, Scientific), "1e1");
+    assert_eq!(CASNum::from(999.9).to_decimal(3, Fixed), "1000");
+
+    //beyond the range and precision of f64
+    let mut power = CASNum::from(1);
+    for _ in 0..400 {
+        power = power * CASNum::from(10);
+    }
+    assert_eq!(power.to_decimal(3, Scientific), "1.00e400");
+    assert_eq!(power.to_decimal(3, Engineering), "10.0e399");
+    assert_eq!(power.to_string(), "1e400");
+    let above = (power.clone() + CASNum::from(1)).to_string();
+    assert_eq!(above, format!("1.{}1e400", "0".repeat(399)));
+    let tiny = CASNum::from(1).quotient(&power, 30);
+    assert_eq!(tiny.to_decimal(5, Scientific), "1.0000e-400");
+    let third = CASNum::from(1).quotient(&CASNum::from(3), 4);
+    assert_eq!(third.to_decimal(40, Fixed), format!("0.{}", "3".repeat(40)));
+    //without finding powers of 10 with a million digits
+    let huge = parse("1e1000000").unwrap().to_cas_num(3);
+    assert_eq!(huge.to_string(), "1e1000000");
+    let tiny = CASNum::from(1) / huge;
+    assert_eq!(tiny.to_decimal(5, Engineering), "100.00e-1000002");
+    let third = tiny / CASNum::from(-3);
+    assert_eq!(
+        third.round_decimal(3, RoundingMode::Floor).to_string(),
+        "-3.34e-1000001"
+    );
+    assert_eq!(
+        third.round_decimal(3, RoundingMode::Ceiling).to_string(),
+        "-3.33e-1000001"
+    );
+    //or stepping through each power of 10 an estimate of the exponent is off by
+    for lit in ["1e99999999999", "2.5e-999999999999", "7.25e123456789"] {
+        let value = parse(lit).unwrap().to_cas_num(3);
+        assert_eq!(value.to_string(), lit);
+    }
+
+    assert_eq!(
+        CASNum::from(1u128 << 64).to_string(),
+        "18446744073709551616"
+    );
+    assert_eq!(CASNum::from(-0.5).to_string(), "-0.5");
+    assert_eq!(CASNum::from(1e21).to_string(), "1e21");
+    assert_eq!(format!("{:.3}", CASNum::from(2) / CASNum::from(3)), "0.667");
+    assert_eq!(ZERO.to_string(), "0");
+    assert_eq!(ZERO.to_decimal(3, Scientific), "0.00e0");
+    assert_eq!(NEG_INFINITY.to_decimal(3, Fixed), "-∞");
+}
+
+#[test]
+fn decimal_rounding() {
+    use RoundingMode::*;
+    let pi = CASNum::pi(4);
+    assert_eq!(pi.round_decimal(3, HalfEven).to_string(), "3.14");
+    assert_eq!(pi.round_decimal(3, Ceiling).to_string(), "3.15");
+    assert_eq!((-pi.clone()).round_decimal(3, Floor).to_string(), "-3.15");
+    assert_eq!(
+        (-pi.clone()).round_decimal(3, TowardZero).to_string(),
+        "-3.14"
+    );
+    assert_eq!(
+        CASNum::from(2.5).round_decimal(5, HalfEven).to_string(),
+        "2.5"
+    );
+    assert_eq!(
+        CASNum::from(1e-30).round_decimal(2, HalfEven).to_string(),
+        "1e-30"
+    );
+    assert_eq!(INFINITY.round_decimal(5, Floor), INFINITY);
+
+    //rounded numbers are written as the digits they were rounded to, without trailing zeros
+    let third = CASNum::from(1).quotient(&CASNum::from(3), 6);
+    let sevenths = CASNum::from(-22).quotient(&CASNum::from(7), 6);
+    for value in [pi.clone(), third, sevenths] {
+        for digits in 1..100 {
+            let written = value.to_decimal(digits, Notation::Fixed);
+            let written = written.trim_end_matches('0').trim_end_matches('.');
+            let rounded = value.round_decimal(digits, HalfEven);
+            assert_eq!(
+                rounded.to_string(),
+                written,
+                "{} to {} digits",
+                value,
+                digits
+            );
+
+            let (lo, hi) = (
+                value.round_decimal(digits, Floor),
+                value.round_decimal(digits, Ceiling),
+            );
+            assert!(lo <= value && value <= hi, "{} to {} digits", value, digits);
+        }
+    }
+}
+
+/// Greatest common divisor by Euclid's algorithm, to check [CASNum::gcd] against.
+fn gcd_i128(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd_i128(b, a % b)
+    }
+}
+
+#[test]
+fn number_theory() {
+    let num = CASNum::from;
+    let mut rng = ChaCha8Rng::seed_from_u64(20);
+    for _ in 0..200 {
+        let a = (rng.next_u64() as i64 as i128) << 20;
+        let b = (rng.next_u64() >> 20) as i128 * 360;
+        assert_eq!(
+            num(a).gcd(&num(b)),
+            Some(num(gcd_i128(a, b))),
+            "gcd({}, {})",
+            a,
+            b
+        );
+        assert_eq!(
+            num(a).modulo(&num(b)),
+            Some(num(a.rem_euclid(b))),
+            "{} mod {}",
+            a,
+            b
+        );
+    }
+    assert_eq!(num(-4).lcm(&num(6)), Some(num(12)));
+    assert_eq!(num(0).lcm(&num(6)), Some(ZERO));
+    assert_eq!(num(7).modulo(&num(-3)), Some(num(1)));
+    assert_eq!(num(7).modulo(&ZERO), None);
+    assert_eq!(CASNum::from(2.5).gcd(&num(5)), None);
+
+    //3 ^ (10 ^ 20) mod 1000000007, by Fermat's little theorem 10 ^ 20 can be reduced mod 1000000006
+    let modulus = 1_000_000_007;
+    let mut expected = 1;
+    for _ in 0..(100_000_000_000_000_000_000u128 % (modulus - 1)) {
+        expected = expected * 3 % modulus;
+    }
+    let exp = num(100_000_000_000_000_000_000i128);
+    assert_eq!(
+        num(3).mod_pow(&exp, &num(modulus as i128)),
+        Some(num(expected as i128))
+    );
+    assert_eq!(num(-2).mod_pow(&num(3), &num(5)), Some(num(2)));
+    assert_eq!(num(5).mod_pow(&ZERO, &num(1)), Some(ZERO));
+    assert_eq!(num(5).mod_pow(&num(-1), &num(7)), None);
+
+    assert_eq!(num(10i128.pow(36)).isqrt(), Some(num(10i128.pow(18))));
+    assert_eq!(
+        num(10i128.pow(36) - 1).isqrt(),
+        Some(num(10i128.pow(18) - 1))
+    );
+    assert_eq!(
+        num(i128::MAX).isqrt(),
+        Some(num(13_043_817_825_332_782_212))
+    );
+    assert_eq!(num(-1).isqrt(), None);
+
+    assert_eq!(ZERO.factorial(), Some(num(1)));
+    assert_eq!(num(30).factorial(), Some(num((1..=30).product())));
+    assert_eq!(num(-1).factorial(), None);
+    assert_eq!(
+        num(100).binomial(&num(50)),
+        Some(num(100_891_344_545_564_193_334_812_497_256))
+    );
+    assert_eq!(num(10).binomial(&num(-1)), Some(ZERO));
+    assert_eq!(num(10).binomial(&num(11)), Some(ZERO));
+    //only 2 factors are multiplied, since 10 ^ 15 choose 10 ^ 15 - 2 is 10 ^ 15 choose 2
+    let big = num(10i128.pow(15));
+    assert_eq!(
+        big.binomial(&(big.clone() - num(2))),
+        Some(num(10i128.pow(15) / 2 * (10i128.pow(15) - 1)))
+    );
+}
+
+#[test]
+fn primes() {
+    let num = CASNum::from;
+    let primes: Vec<i128> = (0..200)
+        .filter(|n: &i128| *n > 1 && (2..*n).all(|d| n % d != 0))
+        .collect();
+    for n in -5..200 {
+        assert_eq!(num(n).is_prime(), Some(primes.contains(&n)), "{}", n);
+    }
+    //strong pseudoprimes to the bases 2, 3, 5 and 7, and a Carmichael number
+    assert_eq!(num(3_215_031_751).is_prime(), Some(false));
+    assert_eq!(num(561).is_prime(), Some(false));
+    assert_eq!(num((1 << 61) - 1).is_prime(), Some(true));
+    //above the bound where the bases are random
+    assert_eq!(num((1 << 89) - 1).is_prime(), Some(true));
+    assert_eq!(num(i128::MAX).is_prime(), Some(true));
+    assert_eq!(
+        num(((1 << 61) - 1) * ((1 << 31) - 1)).is_prime(),
+        Some(false)
+    );
+    assert_eq!(CASNum::from(0.5).is_prime(), None);
+
+    let factors = |n: i128| {
+        num(n).factor().map(|factors| {
+            factors
+                .into_iter()
+                .map(|(prime, count)| {
+                    (
+                        i64::try_from(&CASRational::try_from(&prime).unwrap()).unwrap(),
+                        count,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(factors(360), Some(vec![(2, 3), (3, 2), (5, 1)]));
+    assert_eq!(factors(-97), Some(vec![(97, 1)]));
+    assert_eq!(factors(1), Some(vec![]));
+    assert_eq!(factors(0), None);
+    //2 ^ 64 + 1, which needs Pollard's rho method
+    assert_eq!(
+        factors((1 << 64) + 1),
+        Some(vec![(274_177, 1), (67_280_421_310_721, 1)])
+    );
+    assert_eq!(
+        factors(1_000_003 * 1_000_003 * 999_983),
+        Some(vec![(999_983, 1), (1_000_003, 2)])
+    );
+}
+
+/// Whether both parts of `value` are within `1e-14` of `re` and `im`, which are only as precise as an `f64`.
+fn close_to(value: &CASComplex, re: f64, im: f64) -> bool {
+    let tolerance = CASNum::from(1e-14);
+    (value.re.clone() - CASNum::from(re)).abs() < tolerance
+        && (value.im.clone() - CASNum::from(im)).abs() < tolerance
+}
+
+#[test]
+fn complex_arithmetic() {
+    let z = |re: i128, im: i128| CASComplex::new(CASNum::from(re), CASNum::from(im));
+    let context = Context::new(4, RoundingMode::HalfEven);
+    assert_eq!(z(1, 2) + z(3, -5), z(4, -3));
+    assert_eq!(z(1, 2) - z(3, -5), z(-2, 7));
+    assert_eq!(z(1, 2) * z(3, -5), z(13, 1));
+    assert_eq!(CASComplex::i() * CASComplex::i(), z(-1, 0));
+    assert_eq!(z(13, 1).div_with(&z(3, -5), context), z(1, 2));
+    assert_eq!(z(3, -4).conj(), z(3, 4));
+    assert_eq!(z(3, -4).abs(4), CASNum::from(5));
+    assert_eq!(z(-2, 0).to_real(), Some(CASNum::from(-2)));
+    assert_eq!(z(0, 1).to_real(), None);
+    //powers of exact values are exact
+    assert_eq!(z(1, 1).pow(&z(8, 0), 4), z(16, 0));
+    assert_eq!(z(0, 1).pow(&z(-1, 0), 4), z(0, -1));
+    assert!(!z(1, 1).div_with(&z(0, 0), context).is_finite());
+
+    assert_eq!(z(1, 2).to_string(), "1 + 2i");
+    assert_eq!(z(1, -2).to_string(), "1 - 2i");
+    assert_eq!(z(0, -1).to_string(), "-i");
+    assert_eq!(z(0, 3).to_string(), "3i");
+    assert_eq!(z(-7, 0).to_string(), "-7");
+}
+
+#[test]
+fn complex_functions() {
+    let z = |re: f64, im: f64| CASComplex::new(CASNum::from(re), CASNum::from(im));
+    let pi = f64::from(CASNum::pi(4));
+    assert_eq!(z(-4.0, 0.0).sqrt(4), z(0.0, 2.0));
+    assert!(close_to(&z(-1.0, 0.0).ln(4), 0.0, pi));
+    assert!(close_to(&z(0.0, pi).exp(4), -1.0, 0.0));
+    assert!(close_to(&z(-8.0, 0.0).cbrt(4), 1.0, 3f64.sqrt()));
+    assert!(close_to(
+        &z(0.0, 1.0).pow(&z(0.0, 1.0), 4),
+        (-pi / 2.0).exp(),
+        0.0
+    ));
+    //asin(2) is on the branch with a positive imaginary part
+    assert!(close_to(
+        &z(2.0, 0.0).asin(4),
+        pi / 2.0,
+        -(2.0 + 3f64.sqrt()).ln()
+    ));
+    let w = z(0.5, -1.5);
+    for (value, inverse) in [
+        (w.sin(4).asin(4), w.clone()),
+        (w.cos(4).acos(4), w.clone()),
+        (w.tan(4).atan(4), w.clone()),
+        (w.exp(4).ln(4), w.clone()),
+        (w.sqrt(4).pow(&z(2.0, 0.0), 4), w.clone()),
+    ] {
+        assert!(close_to(&(value.clone() - inverse), 0.0, 0.0), "{}", value);
+    }
+    let (r, theta) = w.to_polar(4);
+    assert!(close_to(
+        &(CASComplex::from_polar(&r, &theta, 4) - w),
+        0.0,
+        0.0
+    ));
+    assert!(!z(0.0, 0.0).ln(4).is_finite());
+}
+
+#[test]
+fn interval_arithmetic() {
+    let interval = |lo: f64, hi: f64| CASInterval::new(CASNum::from(lo), CASNum::from(hi));
+    let a = interval(1.0, 2.0);
+    let b = interval(-3.0, 0.5);
+    assert_eq!(a.add(&b, 4), interval(-2.0, 2.5));
+    assert_eq!(a.sub(&b, 4), interval(0.5, 5.0));
+    assert_eq!(a.mul(&b, 4), interval(-6.0, 1.0));
+    assert_eq!(-b.clone(), interval(-0.5, 3.0));
+    assert_eq!(interval(2.0, 1.0), a);
+    //dividing by an interval with 0 inside it could give anything
+    assert_eq!(a.div(&b, 4), interval(f64::NEG_INFINITY, f64::INFINITY));
+    assert_eq!(a.div(&interval(0.0, 4.0), 4), interval(0.25, f64::INFINITY));
+    assert!(a.div(&interval(0.0, 0.0), 4).is_indeterminate());
+    //0 * ∞ is 0, since the infinite end isn't a number in the interval
+    assert_eq!(
+        interval(0.0, 1.0).mul(&interval(1.0, f64::INFINITY), 4),
+        interval(0.0, f64::INFINITY)
+    );
+
+    //ends are rounded outward, so 1 / 3 is strictly between them
+    let third = CASInterval::point(CASNum::from(1)).div(&CASInterval::point(CASNum::from(3)), 2);
+    assert!(third.lo < third.hi);
+    assert!(third.lo.clone() * CASNum::from(3) < CASNum::from(1));
+    assert!(third.hi.clone() * CASNum::from(3) > CASNum::from(1));
+    assert_eq!(third.to_string(), format!("[{}, {}]", third.lo, third.hi));
+    //and written outward too, so the written interval holds it
+    assert_eq!(format!("{:.5}", third), "[0.33333, 0.33334]");
+    assert_eq!(format!("{:.3}", -third.clone()), "[-0.334, -0.333]");
+
+    //even powers are smallest at 0
+    let two = CASInterval::point(CASNum::from(2));
+    assert_eq!(b.pow(&two, 4), interval(0.0, 9.0));
+    assert_eq!(
+        b.pow(&CASInterval::point(CASNum::from(3)), 4),
+        interval(-27.0, 0.125)
+    );
+    assert_eq!(
+        a.pow(&CASInterval::point(CASNum::from(-1)), 4),
+        interval(0.5, 1.0)
+    );
+}
+
+#[test]
+fn interval_functions() {
+    let interval = |lo: f64, hi: f64| CASInterval::new(CASNum::from(lo), CASNum::from(hi));
+    let x = CASNum::from(0.75);
+    let point = CASInterval::point(x.clone());
+    //each result holds the value found to many more limbs, and is only a few of its last limb wide
+    for (value, exact) in [
+        (point.sqrt(2), x.sqrt(8)),
+        (point.cbrt(2), x.cbrt(8)),
+        (point.exp(2), x.exp(8)),
+        (point.ln(2), x.ln(8)),
+        (point.log10(2), x.log10(8)),
+        (point.sin(2), x.sin(8)),
+        (point.cos(2), x.cos(8)),
+        (point.tan(2), x.tan(8)),
+        (point.csc(2), x.csc(8)),
+        (point.sec(2), x.sec(8)),
+        (point.cot(2), x.cot(8)),
+        (point.asin(2), x.asin(8)),
+        (point.acos(2), x.acos(8)),
+        (point.atan(2), x.atan(8)),
+        (point.acot(2), x.acot(8)),
+        (point.pow(&point, 2), x.pow(&x, 8)),
+        (CASInterval::enclose(2, CASNum::pi), CASNum::pi(8)),
+    ] {
+        assert!(value.contains(&exact), "{} doesn't hold {}", value, exact);
+        let width = value.hi.clone() - value.lo.clone();
+        assert!(
+            width < exact.abs() * CASNum::from(1e-18),
+            "{} is too wide",
+            value
+        );
+    }
+
+    //sin and cos reach 1 and -1 inside intervals with a peak or trough
+    let around_peak = interval(1.0, 2.0).sin(2);
+    assert_eq!(around_peak.hi, CASNum::from(1));
+    assert!(around_peak.contains(&CASNum::from(1.5f64.sin())));
+    assert_eq!(interval(3.0, 3.5).cos(2).lo, CASNum::from(-1));
+    assert_eq!(interval(0.0, 7.0).sin(2), interval(-1.0, 1.0));
+    assert_eq!(interval(0.0, f64::INFINITY).cos(2), interval(-1.0, 1.0));
+    assert!(CASInterval::point(CASNum::from(f64::INFINITY))
+        .sin(2)
+        .is_indeterminate());
+    //tan has a pole at π / 2
+    assert_eq!(
+        interval(1.0, 2.0).tan(2),
+        interval(f64::NEG_INFINITY, f64::INFINITY)
+    );
+    assert!(interval(-1.0, 1.0)
+        .tan(2)
+        .contains(&CASNum::from(0.5f64.tan())));
+
+    //parts outside of a function's domain are left out
+    let root = interval(-1.0, 4.0).sqrt(2);
+    assert_eq!(root.lo, CASNum::from(0));
+    assert!(root.contains(&CASNum::from(2)) && root.hi < CASNum::from(2.000001));
+    assert_eq!(interval(0.0, 1.0).ln(2).lo, CASNum::from(f64::NEG_INFINITY));
+    assert!(interval(-2.0, -1.0).sqrt(2).is_indeterminate());
+    assert!(interval(1.5, 3.0).asin(2).is_indeterminate());
+}
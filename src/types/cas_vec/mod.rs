@@ -0,0 +1,93 @@
+//! Vectors of expressions, like `[1, x, 3]`.
+//!
+//! In an expression tree a vector is a node whose children are its elements. [CASVec] is the same vector as a list, so it can be taken apart and put back together.
+
+use crate::{
+    parser::trees::{Tree, TreeNode, TreeNodeRef},
+    types::{
+        cas_error::{CASError, CASErrorKind},
+        symbol::{Symbol, SymbolType},
+    },
+};
+
+/// A vector whose elements are expressions.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CASVec {
+    /// The elements, in order.
+    pub(crate) elements: Vec<Tree<Symbol>>,
+}
+
+impl CASVec {
+    /// The vector with the given elements.
+    pub(crate) fn new(elements: Vec<Tree<Symbol>>) -> Self {
+        CASVec { elements }
+    }
+
+    /// Number of elements.
+    pub(crate) fn dim(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Reads the vector at the root of a node, or returns None if the node isn't a vector.
+    ///
+    /// The elements share their nodes with the original.
+    pub(crate) fn from_node(node: &TreeNodeRef<Symbol>) -> Option<Self> {
+        let node = node.0.borrow();
+        match node.data.symbol_type {
+            SymbolType::Vector { .. } => Some(CASVec::new(
+                node.children
+                    .iter()
+                    .map(|child| Tree {
+                        root: child.clone(),
+                    })
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Builds the node for the vector, at `line_pos`.
+    pub(crate) fn into_node(self, line_pos: usize) -> TreeNodeRef<Symbol> {
+        TreeNodeRef::new_from_node(TreeNode {
+            data: Symbol {
+                symbol_type: SymbolType::Vector { dim: self.dim() },
+                line_pos,
+            },
+            children: self.elements.into_iter().map(|tree| tree.root).collect(),
+        })
+    }
+
+    /// Returns a [CASErrorKind::DimensionMismatch] error at `line_pos` unless the vector has `dim` elements.
+    pub(crate) fn check_dim(&self, dim: usize, line_pos: usize) -> Result<(), CASError> {
+        if self.dim() == dim {
+            return Ok(());
+        }
+        Err(CASError {
+            line_pos,
+            kind: CASErrorKind::DimensionMismatch {
+                expected: dim,
+                found: self.dim(),
+            },
+        })
+    }
+
+    /// Combines the elements of two vectors with the same number of elements pairwise, like the sum `[a, b] + [c, d] = [a + c, b + d]`.
+    pub(crate) fn zip_with<F>(
+        &self,
+        rhs: &CASVec,
+        line_pos: usize,
+        mut f: F,
+    ) -> Result<Self, CASError>
+    where
+        F: FnMut(&Tree<Symbol>, &Tree<Symbol>) -> Result<Tree<Symbol>, CASError>,
+    {
+        rhs.check_dim(self.dim(), line_pos)?;
+        Ok(CASVec::new(
+            self.elements
+                .iter()
+                .zip(&rhs.elements)
+                .map(|(lhs, rhs)| f(lhs, rhs))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
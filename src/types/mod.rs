@@ -1,4 +1,5 @@
 pub(crate) mod cas_error;
+pub(crate) mod cas_matrix;
 pub(crate) mod cas_num;
 pub(crate) mod cas_vec;
 pub(crate) mod symbol;
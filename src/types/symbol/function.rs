@@ -7,14 +7,22 @@ use phf_macros::phf_map;
 
 #[derive(Hash, Debug, Clone, PartialEq, Eq)]
 pub(crate) enum Func {
-    ResFun(ResFun),
-    Function { num_args: usize, name: String },
+    ResFun {
+        /// The reserved function being called.
+        fun: ResFun,
+        /// Number of arguments it was called with, which is only different from `fun.num_args()` for variadic functions like `der`.
+        num_args: usize,
+    },
+    Function {
+        num_args: usize,
+        name: String,
+    },
 }
 
 impl Display for Func {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Func::ResFun(res_fun) => write!(f, "{}", res_fun), //use resfun display
+            Func::ResFun { fun, .. } => write!(f, "{}", fun), //use resfun display
             Func::Function { name, .. } => write!(f, "{}()", name),
         }
     }
@@ -40,32 +48,87 @@ pub(crate) enum ResFun {
     Acsc,
     Asec,
     Acot,
-    Calc, //calculates value of expression without arbitrary precision, uses values from variable table
+    Calc, //calculates value of expression to a number of significant digits, uses values from variable table
     Der,  //computes derivatives, 2 args, var and expression
     //der(x, x^2) -> 2 * x
-    Grad, //finds gradient, returns vector of expressions
+    /// `grad(f, [x, y, z])` is the gradient of `f`, the vector of its partial derivatives with respect to each variable.
+    Grad,
+    /// `div(F, [x, y, z])` is the divergence of the vector field `F`.
     Div,
+    /// `curl(F, [x, y, z])` is the curl of the vector field `F`, which must have 3 elements.
     Curl,
+    /// `jacob(F, [x, y, z])` is the Jacobian of the vector field `F`, the matrix with a row for each element of `F` and a column for each variable.
     Jacob,
     SymInt,
     DefInt,
     // "der", //derivative
     // "grad", "div", "curl", "jacob", "sym_int", //symbolic integration
     // "def_int",
+    /// Greatest common divisor of two integers.
+    Gcd,
+    /// Least common multiple of two integers.
+    Lcm,
+    /// `mod(a, n)` is `a` modulo `n`, and `mod(a, b, n)` is `a ^ b` modulo `n`.
+    Mod,
+    /// Factorial of an integer.
+    Fact,
+    /// `binom(n, k)` is `n` choose `k`.
+    Binom,
+    /// 1 if an integer is prime, 0 if it isn't.
+    IsPrime,
+    /// Prime factorization of an integer, like `factor(360) -> 2 ^ 3 * 3 ^ 2 * 5`.
+    Factor,
+    /// `interval(a, b)` is every number between `a` and `b`, and `interval(x)` is just `x`. `calc` finds bounds on the value of expressions with intervals in them.
+    Interval,
+    /// Dot product of two vectors with the same number of elements.
+    Dot,
+    /// Cross product of two vectors with 3 elements.
+    Cross,
+    /// Euclidean norm of a vector, `norm(v) = sqrt(dot(v, v))`.
+    Norm,
+    /// Transpose of a matrix.
+    Transpose,
+    /// Determinant of a square matrix.
+    Det,
+    /// Inverse of a square matrix.
+    Inv,
+    /// Number of linearly independent rows of a matrix.
+    Rank,
+    /// `solve(A, b)` is the vector `x` with `A * x = b`, for a square matrix `A` with an inverse.
+    Solve,
 }
 
 impl ResFun {
     pub(crate) fn num_args(self) -> usize {
+        //for variadic functions this is the least number of arguments they can take
         match self {
-            ResFun::Der => 2,    //der(x^2, x) -> 2*x
+            ResFun::Der => 2, //der(x^2, x) -> 2*x, der(x^3, x, 2) -> 6*x, der(x^2 * y, x, y) -> 2*x
             ResFun::SymInt => 2, //sym_int(x^2, x) -> x^3/3 + C
             ResFun::DefInt => 4,
             ResFun::Log => 2,
+            ResFun::Gcd | ResFun::Lcm | ResFun::Mod | ResFun::Binom => 2,
+            ResFun::Dot | ResFun::Cross | ResFun::Solve => 2,
+            ResFun::Grad | ResFun::Div | ResFun::Curl | ResFun::Jacob => 2, //grad(x * y, [x, y]) -> [y, x]
             _ => 1,
         }
     }
 }
 
+impl ResFun {
+    /// The most arguments the function can take, or `None` if there is no limit.
+    ///
+    /// This is only different from [ResFun::num_args] for variadic functions.
+    pub(crate) fn max_args(self) -> Option<usize> {
+        match self {
+            ResFun::Der => None,     //der(f, x, 2, y, z, ...)
+            ResFun::Calc => Some(2), //calc(x ^ 2) or calc(x ^ 2, 50) for 50 significant digits
+            ResFun::Mod => Some(3),
+            ResFun::Interval => Some(2), //interval(x) or interval(1, 2)
+            _ => Some(self.num_args()),
+        }
+    }
+}
+
 impl Display for ResFun {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
@@ -95,6 +158,22 @@ impl Display for ResFun {
             ResFun::SymInt => "∫ ",
             ResFun::DefInt => "∫ ",
             ResFun::Log => "log()",
+            ResFun::Gcd => "gcd()",
+            ResFun::Lcm => "lcm()",
+            ResFun::Mod => "mod()",
+            ResFun::Fact => "fact()",
+            ResFun::Binom => "binom()",
+            ResFun::IsPrime => "isprime()",
+            ResFun::Factor => "factor()",
+            ResFun::Interval => "interval()",
+            ResFun::Dot => "dot()",
+            ResFun::Cross => "cross()",
+            ResFun::Norm => "norm()",
+            ResFun::Transpose => "transpose()",
+            ResFun::Det => "det()",
+            ResFun::Inv => "inv()",
+            ResFun::Rank => "rank()",
+            ResFun::Solve => "solve()",
         };
         write!(f, "{}", name)
     }
@@ -128,4 +207,20 @@ pub(crate) static RESERVED_FUNCTIONS: phf::Map<&'static str, ResFun> = phf_map!
     "jacob" => ResFun::Jacob,
     "sym_int" => ResFun::SymInt,
     "def_int" => ResFun::DefInt,
+    "gcd" => ResFun::Gcd,
+    "lcm" => ResFun::Lcm,
+    "mod" => ResFun::Mod,
+    "fact" => ResFun::Fact,
+    "binom" => ResFun::Binom,
+    "isprime" => ResFun::IsPrime,
+    "factor" => ResFun::Factor,
+    "interval" => ResFun::Interval,
+    "dot" => ResFun::Dot,
+    "cross" => ResFun::Cross,
+    "norm" => ResFun::Norm,
+    "transpose" => ResFun::Transpose,
+    "det" => ResFun::Det,
+    "inv" => ResFun::Inv,
+    "rank" => ResFun::Rank,
+    "solve" => ResFun::Solve,
 };
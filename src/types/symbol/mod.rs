@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::types::CASNum;
+use crate::types::cas_num::Number;
 use function::Func;
-use operator::Operator;
+use operator::{precedence, Operator};
 
 use constant::{Const, ResConst};
 use std::hash::Hash;
@@ -14,11 +14,20 @@ pub(crate) mod operator;
 
 pub(crate) enum SymbolType {
     //type of tokens of output of parsing
-    Variable { name: String },
+    Variable {
+        name: String,
+    },
     Operator(Operator),
     Function(Func),
-    Num { value: CASNum },
+    Num {
+        value: Number,
+    },
     Const(Const),
+    /// A vector like `[1, x, 3]`, whose elements are the children of its node.
+    Vector {
+        /// Number of elements.
+        dim: usize,
+    },
 }
 
 #[derive(Debug, Clone, Eq)]
@@ -47,10 +56,11 @@ impl PartialOrd for SymbolType {
      * Num
      * Const
      * Function
+     * Vector
      * Variable
      * Operator
      *
-     * Variables, functions, and constants are sorted lexicographically by their name, nums are sorted by value, and operators are sorted by precedence from lowest to highest.
+     * Variables, functions, and constants are sorted lexicographically by their name, nums are sorted by value, operators are sorted by precedence from lowest to highest, and vectors are sorted by dimension.
      *
      * Note that in practice, when expressions are simplified arguments of multiplication are sorted in ascending order, while arguments of addition are sorted in descending order. This imitates the way polynomials are traditionally formatted:
      *
@@ -62,9 +72,44 @@ impl PartialOrd for SymbolType {
      * ```
      */
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        use crate::types::symbol::Const::ResConst;
-        use crate::types::symbol::ResConst::*;
-        todo!()
+        /// Position of each variant in the ordering above.
+        fn rank(symbol_type: &SymbolType) -> u8 {
+            match symbol_type {
+                SymbolType::Num { .. } => 0,
+                SymbolType::Const(_) => 1,
+                SymbolType::Function(_) => 2,
+                SymbolType::Vector { .. } => 3,
+                SymbolType::Variable { .. } => 4,
+                SymbolType::Operator(_) => 5,
+            }
+        }
+
+        match (self, other) {
+            (SymbolType::Num { value: lhs }, SymbolType::Num { value: rhs }) => {
+                lhs.partial_cmp(rhs)
+            }
+            (SymbolType::Const(lhs), SymbolType::Const(rhs)) => {
+                Some(lhs.to_string().cmp(&rhs.to_string()))
+            }
+            (SymbolType::Function(lhs), SymbolType::Function(rhs)) => Some(
+                lhs.to_string()
+                    .cmp(&rhs.to_string())
+                    .then(self.num_args().cmp(&other.num_args())),
+            ),
+            (SymbolType::Variable { name: lhs }, SymbolType::Variable { name: rhs }) => {
+                Some(lhs.cmp(rhs))
+            }
+            (SymbolType::Vector { dim: lhs }, SymbolType::Vector { dim: rhs }) => {
+                Some(lhs.cmp(rhs))
+            }
+            (SymbolType::Operator(lhs), SymbolType::Operator(rhs)) => Some(
+                precedence(lhs)
+                    .cmp(&precedence(rhs))
+                    .then((*lhs as u8).cmp(&(*rhs as u8))),
+            ),
+            //operators with the same precedence like + and - are ordered by declaration, so only equal operators compare equal
+            _ => Some(rank(self).cmp(&rank(other))),
+        }
     }
 }
 
@@ -75,7 +120,8 @@ impl SymbolType {
             SymbolType::Operator(Operator::Neg) => 1,
             SymbolType::Operator(..) => 2,
             SymbolType::Function(Func::Function { num_args, .. }) => *num_args,
-            Self::Function(Func::ResFun(res_fun)) => res_fun.num_args(),
+            Self::Function(Func::ResFun { num_args, .. }) => *num_args,
+            SymbolType::Vector { dim } => *dim,
         }
     }
 }
@@ -88,6 +134,7 @@ impl Display for SymbolType {
             SymbolType::Function(func) => write!(f, "{}", func),
             SymbolType::Num { value } => write!(f, "{}", value),
             SymbolType::Const(constant) => write!(f, "{}", constant),
+            SymbolType::Vector { .. } => write!(f, "[]"),
         }
     }
 }
@@ -67,6 +67,12 @@ pub(crate) fn commutative(op: &Operator) -> bool {
     }
 }
 
+/// Checks if nested uses of an operator can be regrouped, i.e. `(a + b) + c = a + (b + c)`.
+pub(crate) fn associative(op: &Operator) -> bool {
+    matches!(op, Operator::Add | Operator::Mult)
+    //== is commutative but (a == b) == c is not a == (b == c)
+}
+
 //TODO: find some way of making a double ended map for this idk
 
 pub(crate) static OPERATORS: phf::Map<&'static str, Operator> = phf_map! {
@@ -1,19 +1,19 @@
 use std::fmt;
 
 use super::{
+    cas_num::Number,
     symbol::{
         constant::ResConst,
         function::ResFun,
         operator::Operator::{self, *},
     },
-    CASNum,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum TokenType {
     //type of tokens outputted by scanner
     Name(String), //variable name
-    Num(CASNum),
+    Num(Number),
     Operator(Operator), //operators
     Const(ResConst),    //constants like pi, e, etc.
     ResFun(ResFun),     //reserved function